@@ -0,0 +1,208 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use crate::GenIterReturn;
+
+/// the state of an [`Abortable`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AbortOutcome<'a, R> {
+    /// the generator can still be resumed
+    Running,
+    /// the generator completed with this return value
+    Completed(&'a R),
+    /// the generator was stopped with [`Abortable::stop`], after yielding `yields_so_far` items
+    Stopped {
+        yields_so_far: usize,
+    },
+}
+
+/// `Abortable<G>` is a [`GenIterReturn`] that can be stopped before its completion,
+/// keeping a record of it, where dropping the generator would leave nothing.
+///
+/// created by [`GenIterReturn::abortable`]. Like `GenIterReturn`,
+/// `&mut Abortable<G>` is the iterator.
+///
+/// [`stop`](Abortable::stop) drops the generator right away, the iteration
+/// ends and [`outcome`](Abortable::outcome) is then [`AbortOutcome::Stopped`].
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter_return, AbortOutcome};
+///
+/// let mut g = gen_iter_return!({
+///     for i in 0.. {
+///         yield i;
+///     }
+///     "never"
+/// }).abortable();
+///
+/// while let Some(i) = (&mut g).next() {
+///     if i == 2 {
+///         g.stop();
+///     }
+/// }
+/// assert_eq!(g.outcome(), AbortOutcome::Stopped { yields_so_far: 3 });
+/// ```
+pub struct Abortable<G: Coroutine + Unpin> {
+    // `None` once stopped
+    gen: Option<GenIterReturn<G>>,
+    yields: usize,
+}
+
+impl<G: Coroutine + Unpin> Abortable<G> {
+    #[inline]
+    pub fn new(g: GenIterReturn<G>) -> Self {
+        Abortable { gen: Some(g), yields: 0 }
+    }
+
+    /// drop the generator, unless it completed already.
+    ///
+    /// stopping it again does nothing.
+    pub fn stop(&mut self) {
+        if let Some(ref g) = self.gen {
+            if !g.is_done() {
+                self.gen = None;
+            }
+        }
+    }
+
+    /// whether the generator is running, completed, or was stopped
+    pub fn outcome(&self) -> AbortOutcome<'_, G::Return> {
+        match self.gen {
+            Some(ref g) => match g.as_result() {
+                Ok(r) => AbortOutcome::Completed(r),
+                Err(_) => AbortOutcome::Running,
+            },
+            None => AbortOutcome::Stopped { yields_so_far: self.yields },
+        }
+    }
+
+    /// the number of items yielded so far
+    #[inline]
+    pub fn yields(&self) -> usize {
+        self.yields
+    }
+
+    /// the return value if the generator completed, else `self`,
+    /// whose [`outcome`](Abortable::outcome) tells if it is running or was stopped
+    pub fn return_or_self(self) -> Result<G::Return, Self> {
+        let yields = self.yields;
+        match self.gen {
+            Some(g) => g.return_or_self().map_err(|g| Abortable { gen: Some(g), yields }),
+            None => Err(self),
+        }
+    }
+}
+
+impl<G: Coroutine + Unpin> From<GenIterReturn<G>> for Abortable<G> {
+    #[inline]
+    fn from(g: GenIterReturn<G>) -> Self {
+        Abortable::new(g)
+    }
+}
+
+impl<G: Coroutine + Unpin> Iterator for &mut Abortable<G> {
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        let y = self.gen.as_mut().and_then(|mut g| g.next())?;
+        self.yields += 1;
+        Some(y)
+    }
+}
+
+impl<G: Coroutine + Unpin> FusedIterator for &mut Abortable<G> {}
+
+impl<G> fmt::Debug for Abortable<G>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Abortable")
+            .field("gen", &self.gen)
+            .field("yields", &self.yields)
+            .finish()
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// allow stopping this generator before its completion, see [`Abortable`]
+    #[inline]
+    pub fn abortable(self) -> Abortable<G> {
+        Abortable::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AbortOutcome, GenIterReturn};
+    use core::cell::Cell;
+
+    struct DropFlag<'a>(&'a Cell<bool>);
+
+    impl Drop for DropFlag<'_> {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    #[test]
+    fn stopped_halfway() {
+        let dropped = Cell::new(false);
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            let _flag = DropFlag(&dropped);
+            for i in 0..4 {
+                yield i;
+            }
+            "done"
+        }).abortable();
+        assert_eq!(g.outcome(), AbortOutcome::Running);
+        assert_eq!((&mut g).take(2).sum::<u32>(), 1);
+
+        g.stop();
+        // the generator is dropped right away
+        assert!(dropped.get());
+        assert_eq!(g.outcome(), AbortOutcome::Stopped { yields_so_far: 2 });
+        assert_eq!((&mut g).next(), None);
+
+        g.stop();
+        assert_eq!(g.outcome(), AbortOutcome::Stopped { yields_so_far: 2 });
+        let g = g.return_or_self().unwrap_err();
+        assert_eq!(g.outcome(), AbortOutcome::Stopped { yields_so_far: 2 });
+    }
+
+    #[test]
+    fn completed() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            yield 2;
+            "done"
+        }).abortable();
+        assert_eq!((&mut g).count(), 2);
+        assert_eq!(g.outcome(), AbortOutcome::Completed(&"done"));
+
+        // too late to stop it
+        g.stop();
+        assert_eq!(g.outcome(), AbortOutcome::Completed(&"done"));
+        assert_eq!(g.yields(), 2);
+        assert_eq!(g.return_or_self().ok(), Some("done"));
+    }
+
+    #[test]
+    fn running() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            "done"
+        }).abortable();
+        assert_eq!((&mut g).next(), Some(1));
+        let mut g = g.return_or_self().unwrap_err();
+        assert_eq!(g.outcome(), AbortOutcome::Running);
+        assert_eq!(g.yields(), 1);
+        assert_eq!((&mut g).next(), None);
+        assert_eq!(g.return_or_self().ok(), Some("done"));
+    }
+}
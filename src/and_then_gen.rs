@@ -0,0 +1,165 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::iter::Iterator;
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::{fmt, mem};
+
+use crate::GenIterReturn;
+
+enum State<G: Coroutine + Unpin, F, H> {
+    First(GenIterReturn<G>, F),
+    Second(H),
+    Done,
+}
+
+/// a generator running `G`, then the generator made by `F` from its return value.
+///
+/// created by [`GenIterReturn::and_then_gen`].
+pub struct AndThenGen<G: Coroutine + Unpin, F, H> {
+    state: State<G, F, H>,
+}
+
+// `f` is never pinned
+impl<G: Coroutine + Unpin, F, H: Unpin> Unpin for AndThenGen<G, F, H> {}
+
+impl<G, F, H> Coroutine for AndThenGen<G, F, H>
+where
+    G: Coroutine + Unpin,
+    F: FnOnce(G::Return) -> GenIterReturn<H>,
+    H: Coroutine<Yield = G::Yield> + Unpin,
+{
+    type Yield = G::Yield;
+    type Return = H::Return;
+
+    fn resume(self: Pin<&mut Self>, _: ()) -> CoroutineState<G::Yield, H::Return> {
+        let this = self.get_mut();
+        loop {
+            match this.state {
+                State::First(ref mut g, _) => {
+                    if let Some(y) = (&mut *g).next() {
+                        return CoroutineState::Yielded(y);
+                    }
+                    let (g, f) = match mem::replace(&mut this.state, State::Done) {
+                        State::First(g, f) => (g, f),
+                        _ => unreachable!(),
+                    };
+                    match g.into_result() {
+                        Ok(r) => match f(r).into_result() {
+                            Ok(r) => return CoroutineState::Complete(r),
+                            Err(h) => this.state = State::Second(h),
+                        },
+                        Err(_) => unreachable!(),
+                    }
+                },
+                State::Second(ref mut h) => {
+                    let state = Pin::new(h).resume(());
+                    if let CoroutineState::Complete(_) = state {
+                        this.state = State::Done;
+                    }
+                    return state;
+                },
+                State::Done => panic!("`AndThenGen` resumed after completion"),
+            }
+        }
+    }
+}
+
+impl<G, F, H> fmt::Debug for AndThenGen<G, F, H>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+    H: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.state {
+            State::First(ref g, _) => f.debug_tuple("First").field(g).finish(),
+            State::Second(ref h) => f.debug_tuple("Second").field(h).finish(),
+            State::Done => f.write_str("Done"),
+        }
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// once this generator completes, go on with the generator `f` makes from its
+    /// return value, yielding its items and returning its return value.
+    ///
+    /// `f` is only called when this generator completes.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_iter_return;
+    ///
+    /// let mut g = gen_iter_return!({
+    ///     yield "hello";
+    ///     42
+    /// }).and_then_gen(|session| gen_iter_return!(move {
+    ///     yield "data";
+    ///     session + 1
+    /// }));
+    ///
+    /// assert_eq!((&mut g).collect::<Vec<_>>(), ["hello", "data"]);
+    /// assert_eq!(g.return_or_self().ok(), Some(43));
+    /// ```
+    #[inline]
+    pub fn and_then_gen<F, H>(self, f: F) -> GenIterReturn<AndThenGen<G, F, H>>
+    where
+        F: FnOnce(G::Return) -> GenIterReturn<H>,
+        H: Coroutine<Yield = G::Yield> + Unpin,
+    {
+        GenIterReturn::new(AndThenGen { state: State::First(self, f) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIterReturn;
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    fn handshake() -> GenIterReturn<impl core::ops::Coroutine<Yield = u32, Return = u32> + Unpin> {
+        GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            yield 2;
+            10
+        })
+    }
+
+    #[test]
+    fn chained() {
+        let mut g = handshake().and_then_gen(|session| GenIterReturn::new(#[coroutine] move || {
+            yield session;
+            yield session + 1;
+            "closed"
+        }));
+        assert_eq!((&mut g).collect::<Vec<_>>(), [1, 2, 10, 11]);
+        assert_eq!(g.return_or_self().ok(), Some("closed"));
+    }
+
+    #[test]
+    fn lazy() {
+        let called = Cell::new(false);
+        let mut g = handshake().and_then_gen(|session| {
+            called.set(true);
+            GenIterReturn::new(#[coroutine] move || {
+                yield session;
+            })
+        });
+        // stopped before the first generator completes
+        assert_eq!((&mut g).take(2).collect::<Vec<_>>(), [1, 2]);
+        assert!(!called.get());
+        assert!(g.return_or_self().is_err());
+    }
+
+    #[test]
+    fn second_already_done() {
+        let mut second = GenIterReturn::new(#[coroutine] || {
+            yield 0;
+            "early"
+        });
+        assert_eq!((&mut second).count(), 1);
+        let mut g = handshake().and_then_gen(|_| second);
+        assert_eq!((&mut g).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(g.return_or_self().ok(), Some("early"));
+    }
+}
@@ -0,0 +1,341 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+
+#[cfg(feature = "async_iterator")]
+use core::async_iter::AsyncIterator;
+
+#[cfg(feature = "futures")]
+use futures_core::stream::{FusedStream, Stream};
+
+/// `AsyncGenIter<G>` is an asynchronous iterator over a generator that yields [`Poll`]s,
+/// so that it can wait for something without blocking the thread.
+/// It implements `futures_core::Stream` with the `futures` feature,
+/// and `core::async_iter::AsyncIterator` with the `async_iterator` feature.
+///
+/// - yielding `Poll::Ready(v)` produces the item `v`
+/// - yielding `Poll::Pending` makes `poll_next` return `Pending`
+/// - completing ends the stream
+///
+/// The generator is resumed with the [`Waker`] of the current `poll_next`:
+/// it is the argument of the generator closure, and the value of every `yield`.
+/// Before yielding `Poll::Pending`, the generator has to hand that waker to
+/// whatever will wake the task, exactly like a hand written `Future`.
+/// The waker is cloned for every poll.
+///
+#[cfg_attr(feature = "futures", doc = "```")]
+#[cfg_attr(not(feature = "futures"), doc = "```ignore")]
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// extern crate futures;
+/// extern crate gen_iter;
+///
+/// use futures::executor::block_on;
+/// use futures::stream::StreamExt;
+/// use gen_iter::AsyncGenIter;
+/// use std::task::{Poll, Waker};
+///
+/// let s = AsyncGenIter::new(#[coroutine] |mut waker: Waker| {
+///     for i in 0..3 {
+///         // not ready the first time, wake immediately to be polled again
+///         waker.wake_by_ref();
+///         waker = yield Poll::Pending;
+///         waker = yield Poll::Ready(i);
+///     }
+/// });
+///
+/// assert_eq!(block_on(s.collect::<Vec<_>>()), [0, 1, 2]);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct AsyncGenIter<G>(Option<G>);
+
+impl<G, T> AsyncGenIter<G>
+where
+    G: Coroutine<Waker, Yield = Poll<T>, Return = ()> + Unpin,
+{
+    #[inline]
+    pub fn new(g: G) -> Self {
+        AsyncGenIter(Some(g))
+    }
+
+    /// the `poll_next` of both `Stream` and `AsyncIterator`
+    fn poll_item(&mut self, cx: &mut Context) -> Poll<Option<T>> {
+        let g = match self.0 {
+            Some(ref mut g) => g,
+            None => return Poll::Ready(None),
+        };
+
+        match Pin::new(g).resume(cx.waker().clone()) {
+            CoroutineState::Yielded(Poll::Ready(v)) => Poll::Ready(Some(v)),
+            CoroutineState::Yielded(Poll::Pending) => Poll::Pending,
+            CoroutineState::Complete(()) => {
+                self.0 = None;
+                Poll::Ready(None)
+            },
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<G, T> Stream for AsyncGenIter<G>
+where
+    G: Coroutine<Waker, Yield = Poll<T>, Return = ()> + Unpin,
+{
+    type Item = T;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<T>> {
+        self.get_mut().poll_item(cx)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<G, T> FusedStream for AsyncGenIter<G>
+where
+    G: Coroutine<Waker, Yield = Poll<T>, Return = ()> + Unpin,
+{
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+#[cfg(feature = "async_iterator")]
+impl<G, T> AsyncIterator for AsyncGenIter<G>
+where
+    G: Coroutine<Waker, Yield = Poll<T>, Return = ()> + Unpin,
+{
+    type Item = T;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<T>> {
+        self.get_mut().poll_item(cx)
+    }
+}
+
+impl<G, T> From<G> for AsyncGenIter<G>
+where
+    G: Coroutine<Waker, Yield = Poll<T>, Return = ()> + Unpin,
+{
+    #[inline]
+    fn from(g: G) -> Self {
+        AsyncGenIter::new(g)
+    }
+}
+
+#[doc(hidden)]
+pub type __AsyncWaker = Waker;
+
+/// macro to simplify construction of an [`AsyncGenIter`] from a generator closure
+/// taking the [`Waker`] of the current poll.
+///
+/// the closure parameter is typed by the macro. every `yield` evaluates to the
+/// waker of the poll that resumed the generator, so a generator that waits has
+/// to take `mut cx` and write `cx = yield Poll::Pending;` to keep it up to date.
+///
+#[cfg_attr(feature = "futures", doc = "```")]
+#[cfg_attr(not(feature = "futures"), doc = "```ignore")]
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// extern crate futures;
+/// extern crate gen_iter;
+///
+/// use futures::executor::block_on;
+/// use futures::stream::StreamExt;
+/// use gen_iter::async_gen_iter;
+/// use std::cell::Cell;
+/// use std::task::Poll;
+///
+/// let polls = Cell::new(0);
+/// let ready = || { polls.set(polls.get() + 1); polls.get() > 3 };
+///
+/// let s = async_gen_iter!(|mut cx| {
+///     while !ready() {
+///         cx.wake_by_ref();
+///         cx = yield Poll::Pending;
+///     }
+///     yield Poll::Ready("ready");
+/// });
+///
+/// assert_eq!(block_on(s.collect::<Vec<_>>()), ["ready"]);
+/// assert_eq!(polls.get(), 4);
+/// ```
+#[macro_export]
+macro_rules! async_gen_iter {
+    (|mut $cx: ident| $body: block) => {
+        $crate::AsyncGenIter::new(#[coroutine] |mut $cx: $crate::__AsyncWaker| $body)
+    };
+    (|$cx: ident| $body: block) => {
+        $crate::AsyncGenIter::new(#[coroutine] |$cx: $crate::__AsyncWaker| $body)
+    };
+    (move |mut $cx: ident| $body: block) => {
+        $crate::AsyncGenIter::new(#[coroutine] move |mut $cx: $crate::__AsyncWaker| $body)
+    };
+    (move |$cx: ident| $body: block) => {
+        $crate::AsyncGenIter::new(#[coroutine] move |$cx: $crate::__AsyncWaker| $body)
+    };
+}
+
+#[cfg(all(test, feature = "futures"))]
+mod tests {
+    use super::AsyncGenIter;
+    use core::pin::Pin;
+    use core::task::{Context, Poll, Waker};
+    use futures::stream::{FusedStream, Stream};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::task::Wake;
+    use std::thread;
+    use std::time::Duration;
+    use std::vec::Vec;
+    use futures::executor::block_on;
+    use futures::stream::StreamExt;
+
+    /// counts how often the task was woken
+    struct CountWake(AtomicUsize);
+
+    impl Wake for CountWake {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// an event that can be waited for, like a timer or an interrupt
+    #[derive(Default)]
+    struct Event {
+        fired: bool,
+        waker: Option<Waker>,
+    }
+
+    fn fire(event: &Mutex<Event>) {
+        let mut e = event.lock().unwrap();
+        e.fired = true;
+        if let Some(w) = e.waker.take() {
+            w.wake();
+        }
+    }
+
+    #[test]
+    fn pending_until_woken() {
+        let event = Arc::new(Mutex::new(Event::default()));
+        let ev = event.clone();
+        let mut s = AsyncGenIter::new(#[coroutine] move |mut waker: Waker| {
+            for i in 0..2 {
+                loop {
+                    let mut e = ev.lock().unwrap();
+                    if e.fired {
+                        e.fired = false;
+                        break;
+                    }
+                    e.waker = Some(waker);
+                    drop(e);
+                    waker = yield Poll::Pending;
+                }
+                waker = yield Poll::Ready(i);
+            }
+        });
+
+        let wakes = Arc::new(CountWake(AtomicUsize::new(0)));
+        let waker = Waker::from(wakes.clone());
+        let mut cx = Context::from_waker(&waker);
+        let mut poll = || Pin::new(&mut s).poll_next(&mut cx);
+
+        // pends a few times without being woken
+        assert_eq!(poll(), Poll::Pending);
+        assert_eq!(poll(), Poll::Pending);
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 0);
+
+        fire(&event);
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+        assert_eq!(poll(), Poll::Ready(Some(0)));
+
+        assert_eq!(poll(), Poll::Pending);
+        fire(&event);
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 2);
+        assert_eq!(poll(), Poll::Ready(Some(1)));
+
+        assert_eq!(poll(), Poll::Ready(None));
+        assert_eq!(poll(), Poll::Ready(None));
+        assert!(s.is_terminated());
+    }
+
+    #[test]
+    fn macro_noop_waker() {
+        let mut s = async_gen_iter!(|_cx| {
+            for i in 0..3 {
+                if i % 2 == 0 {
+                    yield Poll::Pending;
+                }
+                yield Poll::Ready(i);
+            }
+        });
+
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut polls = Vec::new();
+        loop {
+            let p = Pin::new(&mut s).poll_next(&mut cx);
+            polls.push(p);
+            if p == Poll::Ready(None) {
+                break;
+            }
+        }
+
+        assert_eq!(polls, [
+            Poll::Pending,
+            Poll::Ready(Some(0)),
+            Poll::Ready(Some(1)),
+            Poll::Pending,
+            Poll::Ready(Some(2)),
+            Poll::Ready(None),
+        ]);
+    }
+
+    #[test]
+    fn macro_real_wakeup() {
+        let event = Arc::new(Mutex::new(Event::default()));
+        let ev = event.clone();
+        let s = async_gen_iter!(move |mut cx| {
+            loop {
+                let mut e = ev.lock().unwrap();
+                if e.fired {
+                    break;
+                }
+                e.waker = Some(cx);
+                drop(e);
+                cx = yield Poll::Pending;
+            }
+            yield Poll::Ready("fired");
+        });
+
+        let firing = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            fire(&event);
+        });
+        assert_eq!(block_on(s.collect::<Vec<_>>()), ["fired"]);
+        firing.join().unwrap();
+    }
+}
+
+#[cfg(all(test, feature = "async_iterator"))]
+mod async_iterator_tests {
+    use super::AsyncGenIter;
+    use core::async_iter::AsyncIterator;
+    use core::pin::Pin;
+    use core::task::{Context, Poll, Waker};
+
+    #[test]
+    fn poll_next() {
+        let mut s = AsyncGenIter::new(#[coroutine] |_: Waker| {
+            let waker = yield Poll::Pending;
+            waker.wake_by_ref();
+            yield Poll::Ready('a');
+        });
+
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(Pin::new(&mut s).poll_next(&mut cx), Poll::Pending);
+        assert_eq!(Pin::new(&mut s).poll_next(&mut cx), Poll::Ready(Some('a')));
+        assert_eq!(Pin::new(&mut s).poll_next(&mut cx), Poll::Ready(None));
+        assert_eq!(Pin::new(&mut s).poll_next(&mut cx), Poll::Ready(None));
+    }
+}
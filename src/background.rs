@@ -0,0 +1,285 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use std::panic;
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
+
+use crate::{GenIter, GenIterReturn};
+
+/// `BackgroundGenIter<Y, R>` iterates over the yields of a generator
+/// running ahead on its own thread.
+///
+/// created by [`GenIter::background`] and [`GenIterReturn::background`].
+///
+/// The worker thread pushes the yields into a channel holding at most `buffer`
+/// of them, and stops when
+/// - the generator completes: the iterator ends once the buffered yields are read
+/// - the iterator is dropped: the worker notices on its next send,
+///   the yield that could not be sent is lost
+///
+/// A panic of the generator is resumed on the consuming thread,
+/// when the iterator reaches the end of the yields.
+pub struct BackgroundGenIter<Y, R = ()> {
+    rx: Option<Receiver<Y>>,
+    worker: Option<JoinHandle<Option<R>>>,
+    ret: Option<R>,
+}
+
+impl<Y, R> BackgroundGenIter<Y, R> {
+    fn spawn<I>(iter: I, buffer: usize) -> Self
+    where
+        I: Iterator<Item = Y> + Send + 'static,
+        I: IntoReturn<R>,
+        Y: Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = mpsc::sync_channel(buffer);
+        let mut iter = iter;
+        let worker = thread::spawn(move || {
+            for y in iter.by_ref() {
+                if tx.send(y).is_err() {
+                    return None;
+                }
+            }
+            iter.into_return()
+        });
+
+        BackgroundGenIter { rx: Some(rx), worker: Some(worker), ret: None }
+    }
+
+    /// wait for the worker thread, after the end of the yields
+    fn finish(&mut self) {
+        if let Some(h) = self.worker.take() {
+            match h.join() {
+                Ok(r) => self.ret = r,
+                Err(e) => panic::resume_unwind(e),
+            }
+        }
+    }
+
+    /// stop the generator if it is not done yet, and wait for the worker thread.
+    ///
+    /// returns the return value of the generator, or `None` if it was stopped
+    /// before it completed. the yields that were not read yet are lost.
+    /// a panic of the generator is resumed on the calling thread.
+    pub fn join(mut self) -> Option<R> {
+        self.rx = None;
+        self.finish();
+        self.ret
+    }
+}
+
+impl<Y, R> Iterator for BackgroundGenIter<Y, R> {
+    type Item = Y;
+
+    #[inline]
+    fn next(&mut self) -> Option<Y> {
+        match self.rx.as_ref()?.recv() {
+            Ok(y) => Some(y),
+            Err(_) => {
+                self.finish();
+                None
+            },
+        }
+    }
+}
+
+impl<Y, R> FusedIterator for BackgroundGenIter<Y, R> {}
+
+impl<Y, R> fmt::Debug for BackgroundGenIter<Y, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BackgroundGenIter")
+            .field("finished", &self.worker.as_ref().is_none_or(JoinHandle::is_finished))
+            .finish_non_exhaustive()
+    }
+}
+
+/// what the worker thread gives back once the yields are exhausted
+trait IntoReturn<R> {
+    fn into_return(self) -> Option<R>;
+}
+
+impl<G: Coroutine<Return = ()> + Unpin> IntoReturn<()> for GenIter<G> {
+    #[inline]
+    fn into_return(self) -> Option<()> {
+        Some(())
+    }
+}
+
+/// `GenIterReturn` is only an iterator through `&mut`
+struct ReturnIter<G: Coroutine + Unpin>(GenIterReturn<G>);
+
+impl<G: Coroutine + Unpin> Iterator for ReturnIter<G> {
+    type Item = G::Yield;
+
+    #[inline]
+    fn next(&mut self) -> Option<G::Yield> {
+        (&mut self.0).next()
+    }
+}
+
+impl<G: Coroutine + Unpin> IntoReturn<G::Return> for ReturnIter<G> {
+    #[inline]
+    fn into_return(self) -> Option<G::Return> {
+        self.0.return_or_self().ok()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// move the generator to its own thread, running up to `buffer` yields
+    /// ahead of the returned iterator.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_iter;
+    ///
+    /// let squares = gen_iter!({
+    ///     for i in 0..5u32 {
+    ///         // expensive work here
+    ///         yield i * i;
+    ///     }
+    /// }).background(2);
+    ///
+    /// assert_eq!(squares.collect::<Vec<_>>(), [0, 1, 4, 9, 16]);
+    /// ```
+    pub fn background(self, buffer: usize) -> BackgroundGenIter<G::Yield>
+    where
+        G: Send + 'static,
+        G::Yield: Send + 'static,
+    {
+        BackgroundGenIter::spawn(self, buffer)
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// like [`GenIter::background`], the return value is given by
+    /// [`join`](BackgroundGenIter::join).
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_iter_return;
+    ///
+    /// let mut it = gen_iter_return!({
+    ///     yield 1;
+    ///     yield 2;
+    ///     return "done";
+    /// }).background(1);
+    ///
+    /// assert_eq!((&mut it).collect::<Vec<_>>(), [1, 2]);
+    /// assert_eq!(it.join(), Some("done"));
+    /// ```
+    pub fn background(self, buffer: usize) -> BackgroundGenIter<G::Yield, G::Return>
+    where
+        G: Send + 'static,
+        G::Yield: Send + 'static,
+        G::Return: Send + 'static,
+    {
+        BackgroundGenIter::spawn(ReturnIter(self), buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, GenIterReturn};
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use std::vec::Vec;
+
+    #[test]
+    fn slow_producer() {
+        let mut it = GenIterReturn::new(#[coroutine] || {
+            for i in 0..10 {
+                thread::sleep(Duration::from_millis(2));
+                yield i;
+            }
+            "done"
+        }).background(4);
+
+        assert_eq!((&mut it).collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+        assert_eq!(it.next(), None);
+        assert_eq!(it.join(), Some("done"));
+    }
+
+    #[test]
+    fn fast_producer() {
+        let (produced_tx, produced_rx) = mpsc::channel();
+        let mut it = GenIter(#[coroutine] move || {
+            for i in 0..20 {
+                produced_tx.send(i).unwrap();
+                yield i;
+            }
+        }).background(3);
+
+        // the worker runs ahead, up to the buffer and the yield it is blocked on
+        let recv = || produced_rx.recv_timeout(Duration::from_secs(5));
+        for i in 0..4 {
+            assert_eq!(recv(), Ok(i));
+        }
+        assert_eq!(produced_rx.recv_timeout(Duration::from_millis(20)), Err(mpsc::RecvTimeoutError::Timeout));
+
+        // every item taken lets it produce one more
+        for i in 0..16 {
+            assert_eq!(it.next(), Some(i));
+            assert_eq!(recv(), Ok(i + 4));
+        }
+        for i in 16..20 {
+            assert_eq!(it.next(), Some(i));
+        }
+        assert_eq!(it.next(), None);
+        assert_eq!(it.join(), Some(()));
+    }
+
+    #[test]
+    fn early_drop() {
+        // the sender is dropped when the worker thread stops
+        let (alive_tx, alive_rx) = mpsc::channel::<()>();
+        let mut it = GenIter(#[coroutine] move || {
+            let _alive = alive_tx;
+            let mut i = 0u64;
+            loop {
+                i += 1;
+                yield i;
+            }
+        }).background(2);
+
+        assert_eq!(it.next(), Some(1));
+        drop(it);
+
+        let start = Instant::now();
+        assert_eq!(alive_rx.recv_timeout(Duration::from_secs(5)), Err(mpsc::RecvTimeoutError::Disconnected));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn join_stops_early() {
+        let mut it = GenIterReturn::new(#[coroutine] || {
+            loop {
+                yield ();
+            }
+        }).background(1);
+        assert_eq!(it.next(), Some(()));
+        assert!(it.join().is_none());
+    }
+
+    #[test]
+    fn panic_propagates() {
+        let mut it = GenIter(#[coroutine] || {
+            yield 1;
+            panic!("worker failed");
+        }).background(1);
+
+        assert_eq!(it.next(), Some(1));
+        let err = panic::catch_unwind(AssertUnwindSafe(|| it.next())).unwrap_err();
+        assert_eq!(err.downcast_ref::<&str>(), Some(&"worker failed"));
+    }
+}
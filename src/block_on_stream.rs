@@ -0,0 +1,133 @@
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context, Poll, Waker};
+
+use std::boxed::Box;
+use std::sync::Arc;
+use std::task::Wake;
+use std::thread::{self, Thread};
+
+use futures_core::stream::Stream;
+
+use crate::GenIter;
+
+/// wakes a parked thread
+struct ThreadWaker {
+    thread: Thread,
+    woken: AtomicBool,
+}
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::Release);
+        self.thread.unpark();
+    }
+}
+
+/// iterate over a stream, blocking the current thread until each item is ready.
+///
+/// the iterator is a generator polling the stream. whenever the stream is not
+/// ready, the thread is parked until the stream wakes it, spurious unparks
+/// park it again. the iterator can move between threads: every `next`
+/// wakes the thread that called it.
+///
+/// ```
+/// extern crate futures;
+/// extern crate gen_iter;
+///
+/// use futures::stream;
+/// use gen_iter::block_on_stream;
+///
+/// let items: Vec<_> = block_on_stream(stream::iter(1..=3)).collect();
+/// assert_eq!(items, [1, 2, 3]);
+/// ```
+pub fn block_on_stream<S: Stream>(s: S) -> impl Iterator<Item = S::Item> {
+    let mut s = Box::pin(s);
+    GenIter(#[coroutine] move || {
+        loop {
+            // the context must not be held across the yield
+            let item = {
+                let woken = Arc::new(ThreadWaker {
+                    thread: thread::current(),
+                    woken: AtomicBool::new(false),
+                });
+                let waker = Waker::from(woken.clone());
+                let mut cx = Context::from_waker(&waker);
+
+                loop {
+                    match Pin::as_mut(&mut s).poll_next(&mut cx) {
+                        Poll::Ready(item) => break item,
+                        Poll::Pending => while !woken.woken.swap(false, Ordering::Acquire) {
+                            thread::park();
+                        },
+                    }
+                }
+            };
+
+            match item {
+                Some(item) => yield item,
+                None => return,
+            }
+        }
+    }).fuse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::block_on_stream;
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+    use futures::stream::{self, Stream};
+    use std::thread;
+    use std::vec::Vec;
+
+    /// pends before every item, and wakes the task from another thread
+    struct Reluctant {
+        items: std::ops::Range<u32>,
+        ready: bool,
+    }
+
+    impl Stream for Reluctant {
+        type Item = u32;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<u32>> {
+            if self.ready {
+                self.ready = false;
+                return Poll::Ready(self.items.next());
+            }
+
+            self.ready = true;
+            let waker = cx.waker().clone();
+            thread::spawn(move || waker.wake());
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn ready_stream() {
+        let mut items = block_on_stream(stream::iter(["a", "b"]));
+        assert_eq!(items.next(), Some("a"));
+        assert_eq!(items.next(), Some("b"));
+        assert_eq!(items.next(), None);
+        assert_eq!(items.next(), None);
+    }
+
+    #[test]
+    fn pending_stream() {
+        let items = block_on_stream(Reluctant { items: 0..5, ready: false });
+        assert_eq!(items.collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn moved_between_threads() {
+        let mut items = block_on_stream(Reluctant { items: 0..4, ready: false });
+        assert_eq!(items.next(), Some(0));
+
+        let rest = thread::spawn(move || items.collect::<Vec<_>>());
+        assert_eq!(rest.join().unwrap(), [1, 2, 3]);
+    }
+}
@@ -0,0 +1,174 @@
+use core::ops::{ControlFlow, Coroutine};
+use core::iter::Iterator;
+use core::marker::Unpin;
+use core::fmt;
+
+use crate::GenIterReturn;
+
+/// `Budgeted<G>` runs a generator a slice at a time, like a bit of work every frame
+/// of a game or a UI, without threads.
+///
+/// created by [`GenIterReturn::budgeted`].
+///
+/// Every [`run_budget`](Budgeted::run_budget) resumes the generator at most
+/// `max_resumes` times, and the next one goes on from there. The resume completing
+/// the generator counts in the budget.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter_return;
+/// use std::ops::ControlFlow;
+///
+/// let mut work = gen_iter_return!({
+///     for i in 0..4 {
+///         yield i;
+///     }
+///     "done"
+/// }).budgeted();
+///
+/// let mut frames = 0;
+/// let ret = loop {
+///     frames += 1;
+///     if let ControlFlow::Break(r) = work.run_budget(2, |_| {}) {
+///         break r;
+///     }
+/// };
+/// assert_eq!((frames, ret), (3, "done"));
+/// ```
+pub struct Budgeted<G: Coroutine + Unpin> {
+    // `None` once the return value was given
+    gen: Option<GenIterReturn<G>>,
+}
+
+impl<G: Coroutine + Unpin> Budgeted<G> {
+    #[inline]
+    pub fn new(g: GenIterReturn<G>) -> Self {
+        Budgeted { gen: Some(g) }
+    }
+
+    /// resume the generator at most `max_resumes` times, giving every yield to `sink`.
+    ///
+    /// returns `Continue` if the budget ran out first, and `Break` with the return
+    /// value once the generator completes.
+    ///
+    /// # Panics
+    /// if called again after it returned `Break`
+    pub fn run_budget<F>(&mut self, max_resumes: usize, mut sink: F) -> ControlFlow<G::Return>
+    where
+        F: FnMut(G::Yield),
+    {
+        let mut g = self.gen.as_mut().expect("`Budgeted` ran after its completion");
+        for _ in 0..max_resumes {
+            match g.next() {
+                Some(y) => sink(y),
+                None => break,
+            }
+        }
+        if !g.is_done() {
+            return ControlFlow::Continue(());
+        }
+        match self.gen.take().unwrap().into_result() {
+            Ok(r) => ControlFlow::Break(r),
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// whether the generator completed
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.gen.as_ref().is_none_or(GenIterReturn::is_done)
+    }
+
+    /// get back the generator, `None` once the return value was given
+    #[inline]
+    pub fn into_inner(self) -> Option<GenIterReturn<G>> {
+        self.gen
+    }
+}
+
+impl<G: Coroutine + Unpin> From<GenIterReturn<G>> for Budgeted<G> {
+    #[inline]
+    fn from(g: GenIterReturn<G>) -> Self {
+        Budgeted::new(g)
+    }
+}
+
+impl<G> fmt::Debug for Budgeted<G>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Budgeted")
+            .field("gen", &self.gen)
+            .finish()
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// run this generator a few resumes at a time, see [`Budgeted`]
+    #[inline]
+    pub fn budgeted(self) -> Budgeted<G> {
+        Budgeted::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIterReturn;
+    use core::ops::ControlFlow;
+    use std::panic;
+    use std::vec::Vec;
+
+    fn ten() -> GenIterReturn<impl core::ops::Coroutine<Yield = u32, Return = &'static str> + Unpin> {
+        GenIterReturn::new(#[coroutine] || {
+            for i in 0..10 {
+                yield i;
+            }
+            "done"
+        })
+    }
+
+    #[test]
+    fn slices() {
+        let mut work = ten().budgeted();
+        let mut seen = Vec::new();
+        let mut calls = 0;
+        let ret = loop {
+            calls += 1;
+            let before = seen.len();
+            match work.run_budget(3, |y| seen.push(y)) {
+                ControlFlow::Continue(()) => assert_eq!(seen.len() - before, 3),
+                ControlFlow::Break(r) => break r,
+            }
+        };
+        assert_eq!(calls, 4);
+        assert_eq!(ret, "done");
+        assert_eq!(seen, (0..10).collect::<Vec<_>>());
+        assert!(work.is_done());
+        assert!(work.into_inner().is_none());
+    }
+
+    #[test]
+    fn zero_budget_and_done() {
+        let mut work = ten().budgeted();
+        assert_eq!(work.run_budget(0, |_| panic!("no resume")), ControlFlow::Continue(()));
+        assert!(!work.is_done());
+
+        // a generator already done gives its return value right away
+        let mut g = ten();
+        assert_eq!((&mut g).count(), 10);
+        let mut work = g.budgeted();
+        assert!(work.is_done());
+        assert_eq!(work.run_budget(0, |_| {}), ControlFlow::Break("done"));
+    }
+
+    #[test]
+    fn after_completion() {
+        let mut work = ten().budgeted();
+        assert_eq!(work.run_budget(11, |_| {}), ControlFlow::Break("done"));
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| work.run_budget(1, |_| {})));
+        assert!(result.is_err());
+    }
+}
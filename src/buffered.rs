@@ -0,0 +1,190 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use alloc::collections::VecDeque;
+
+use crate::GenIter;
+
+/// `Buffered<G>` resumes a generator ahead of time, when [`fill`](Buffered::fill)
+/// is called, and gives the items buffered first, like a frame based program doing
+/// the expensive resumes when it has time left.
+///
+/// created by [`GenIter::buffered`], with the `alloc` feature.
+///
+/// `fill` tops the buffer up to `k` items. `next` gives the buffered items, and resumes
+/// the generator only once the buffer is empty. Once the generator completed,
+/// it is not resumed anymore, by `fill` or `next`.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter;
+///
+/// let mut frames = gen_iter!({
+///     for i in 0..5 {
+///         // expensive
+///         yield i;
+///     }
+/// }).buffered(2);
+///
+/// // during an idle moment
+/// assert_eq!(frames.fill(), 2);
+/// // served from the buffer
+/// assert_eq!(frames.next(), Some(0));
+/// assert_eq!(frames.next(), Some(1));
+/// // resumed on demand
+/// assert_eq!(frames.next(), Some(2));
+/// ```
+pub struct Buffered<G: Coroutine<Return = ()> + Unpin> {
+    gen: GenIter<G>,
+    buffer: VecDeque<G::Yield>,
+    k: usize,
+    done: bool,
+}
+
+impl<G: Coroutine<Return = ()> + Unpin> Buffered<G> {
+    /// resume the generator until `k` items are buffered or it completes,
+    /// returns the number of items buffered
+    pub fn fill(&mut self) -> usize {
+        while !self.done && self.buffer.len() < self.k {
+            match self.gen.next() {
+                Some(y) => self.buffer.push_back(y),
+                None => self.done = true,
+            }
+        }
+        self.buffer.len()
+    }
+
+    /// the number of items buffered
+    #[inline]
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// the number of items `fill` buffers
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.k
+    }
+
+    /// whether the generator completed, the buffered items can still be given
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+impl<G: Coroutine<Return = ()> + Unpin> Iterator for Buffered<G> {
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        if let Some(y) = self.buffer.pop_front() {
+            return Some(y);
+        }
+        if self.done {
+            return None;
+        }
+        let y = self.gen.next();
+        self.done = y.is_none();
+        y
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.buffer.len();
+        if self.done {
+            (buffered, Some(buffered))
+        } else {
+            (buffered, None)
+        }
+    }
+}
+
+impl<G: Coroutine<Return = ()> + Unpin> FusedIterator for Buffered<G> {}
+
+impl<G> fmt::Debug for Buffered<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+    G::Yield: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Buffered")
+            .field("gen", &self.gen)
+            .field("buffer", &self.buffer)
+            .field("k", &self.k)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+impl<G: Coroutine<Return = ()> + Unpin> GenIter<G> {
+    /// buffer up to `k` items of this generator ahead of time, see [`Buffered`]
+    #[inline]
+    pub fn buffered(self, k: usize) -> Buffered<G> {
+        Buffered { gen: self, buffer: VecDeque::with_capacity(k), k, done: false }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIter;
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    #[test]
+    fn fill_then_next() {
+        let resumes = Cell::new(0);
+        let mut g = GenIter(#[coroutine] || {
+            for i in 0..10 {
+                resumes.set(resumes.get() + 1);
+                yield i;
+            }
+        }).buffered(3);
+        assert_eq!(resumes.get(), 0);
+
+        assert_eq!(g.fill(), 3);
+        assert_eq!(resumes.get(), 3);
+        assert_eq!(g.next(), Some(0));
+        assert_eq!(resumes.get(), 3);
+
+        // tops up to 3 again
+        assert_eq!(g.fill(), 3);
+        assert_eq!(resumes.get(), 4);
+        assert_eq!(g.by_ref().take(3).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(g.buffered(), 0);
+
+        // the buffer is empty, resumed on demand
+        assert_eq!(g.next(), Some(4));
+        assert_eq!(resumes.get(), 5);
+        assert_eq!(g.fill(), 3);
+        assert_eq!(g.collect::<Vec<_>>(), [5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn completes_during_fill() {
+        let mut g = GenIter(#[coroutine] || {
+            yield 1;
+            yield 2;
+        }).buffered(5);
+        assert_eq!(g.fill(), 2);
+        assert!(g.is_done());
+        assert_eq!(g.size_hint(), (2, Some(2)));
+        assert_eq!(g.fill(), 2);
+        assert_eq!(g.by_ref().collect::<Vec<_>>(), [1, 2]);
+        // not resumed after its completion
+        assert_eq!(g.next(), None);
+        assert_eq!(g.fill(), 0);
+    }
+
+    #[test]
+    fn zero() {
+        let mut g = GenIter(#[coroutine] || {
+            yield 1;
+        }).buffered(0);
+        assert_eq!(g.fill(), 0);
+        assert!(!g.is_done());
+        assert_eq!(g.collect::<Vec<_>>(), [1]);
+    }
+}
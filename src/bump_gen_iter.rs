@@ -0,0 +1,156 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::iter::Iterator;
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::ptr;
+use core::fmt;
+
+use bumpalo::Bump;
+
+/// `BumpGenIter<'a, Y>` iterates over a generator allocated in a `bumpalo::Bump` arena,
+/// like the generators of a phase of a program, all freed at once at the end of it.
+///
+/// created by [`BumpGenIter::new_in`], with the `bumpalo` feature.
+///
+/// The generator is type-erased, so generators of different types yielding `Y` can be
+/// kept together, and it borrows the arena: it cannot be used after `Bump::reset`,
+/// which needs the arena mutably. It is dropped with the iterator, the arena only
+/// frees its memory.
+///
+/// The arena never moves its values, but it can reuse their memory at the next `reset`
+/// without running their destructors, if a `BumpGenIter` was leaked with `mem::forget`.
+/// That breaks the guarantee of `Pin`, so the generator has to be `Unpin`:
+/// the generators that are not, the `static` ones, can be boxed with `alloc`.
+///
+/// It is not fused, like [`GenIter`](crate::GenIter).
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use bumpalo::Bump;
+/// use gen_iter::BumpGenIter;
+///
+/// let mut bump = Bump::new();
+/// for phase in 0..3 {
+///     let gens = (0..10).map(|i| BumpGenIter::new_in(&bump, #[coroutine] move || {
+///         yield phase * i;
+///     })).collect::<Vec<_>>();
+///     assert_eq!(gens.into_iter().flatten().sum::<u32>(), phase * 45);
+///     bump.reset();
+/// }
+/// ```
+///
+/// Using it after a reset does not compile:
+///
+/// ```compile_fail
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use bumpalo::Bump;
+/// use gen_iter::BumpGenIter;
+///
+/// let mut bump = Bump::new();
+/// let mut it = BumpGenIter::new_in(&bump, #[coroutine] || {
+///     yield 1;
+/// });
+/// bump.reset();
+/// it.next();
+/// ```
+pub struct BumpGenIter<'a, Y> {
+    gen: &'a mut (dyn Coroutine<Yield = Y, Return = ()> + Unpin + 'a),
+}
+
+impl<'a, Y> BumpGenIter<'a, Y> {
+    /// move `gen` into the arena `bump`
+    #[inline]
+    pub fn new_in<G>(bump: &'a Bump, gen: G) -> Self
+    where
+        G: Coroutine<Yield = Y, Return = ()> + Unpin + 'a,
+    {
+        BumpGenIter { gen: bump.alloc(gen) }
+    }
+}
+
+impl<Y> Iterator for BumpGenIter<'_, Y> {
+    type Item = Y;
+
+    #[inline]
+    fn next(&mut self) -> Option<Y> {
+        match Pin::new(&mut *self.gen).resume(()) {
+            CoroutineState::Yielded(y) => Some(y),
+            CoroutineState::Complete(()) => None,
+        }
+    }
+}
+
+impl<Y> Drop for BumpGenIter<'_, Y> {
+    fn drop(&mut self) {
+        // the arena does not drop its values, and only this iterator uses the generator,
+        // it is not used anymore after this
+        unsafe { ptr::drop_in_place(&mut *self.gen) };
+    }
+}
+
+impl<Y> fmt::Debug for BumpGenIter<'_, Y> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BumpGenIter").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BumpGenIter;
+    use bumpalo::Bump;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    #[test]
+    fn many() {
+        let mut bump = Bump::new();
+        let gens = (0..100u64).map(|i| BumpGenIter::new_in(&bump, #[coroutine] move || {
+            for j in 0..i % 4 {
+                yield i * j;
+            }
+        })).collect::<Vec<_>>();
+        let used = bump.allocated_bytes();
+        assert_eq!(gens.into_iter().flatten().count(), 25 * 6);
+
+        bump.reset();
+        // the arena keeps its last chunk for the next phase
+        let gens = (0..100u64).map(|i| BumpGenIter::new_in(&bump, #[coroutine] move || {
+            yield i;
+        })).collect::<Vec<_>>();
+        assert!(bump.allocated_bytes() <= used);
+        assert_eq!(gens.into_iter().flatten().sum::<u64>(), 4950);
+    }
+
+    #[test]
+    fn mixed_types() {
+        let bump = Bump::new();
+        let gens = Vec::from([
+            BumpGenIter::new_in(&bump, #[coroutine] || {
+                yield 'a';
+            }),
+            BumpGenIter::new_in(&bump, #[coroutine] || {
+                yield 'b';
+                yield 'c';
+            }),
+        ]);
+        assert_eq!(gens.into_iter().flatten().collect::<std::string::String>(), "abc");
+    }
+
+    #[test]
+    fn drops_generator() {
+        let bump = Bump::new();
+        let data = Rc::new(());
+        let held = data.clone();
+        let mut it = BumpGenIter::new_in(&bump, #[coroutine] move || {
+            let _held = held;
+            yield 1;
+            yield 2;
+        });
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(Rc::strong_count(&data), 2);
+        drop(it);
+        assert_eq!(Rc::strong_count(&data), 1);
+    }
+}
@@ -0,0 +1,307 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::{AbortOutcome, Abortable, GenIter, GenIterReturn};
+
+/// `CancelToken` cancels a [`CancellableGenIter`] or a [`CancellableGenIterReturn`],
+/// from any thread.
+///
+/// created by [`GenIter::cancellable`] and [`GenIterReturn::cancellable`],
+/// cloning it is cheap.
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// cancel the iteration, the generator is not resumed anymore
+    #[inline]
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+impl fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CancelToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// `CancellableGenIter<G>` iterates over a generator like [`GenIter`],
+/// until its [`CancelToken`] is cancelled.
+///
+/// created by [`GenIter::cancellable`].
+///
+/// The token is checked before every resume: once it is cancelled,
+/// the generator is not resumed anymore and `next()` returns `None`.
+/// A resume already running is not interrupted.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter;
+///
+/// let (mut it, token) = gen_iter!({
+///     for i in 0.. {
+///         yield i;
+///     }
+/// }).cancellable();
+///
+/// assert_eq!(it.next(), Some(0));
+/// token.cancel();
+/// assert_eq!(it.next(), None);
+/// assert!(it.was_cancelled());
+/// ```
+pub struct CancellableGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    gen: GenIter<G>,
+    token: CancelToken,
+    cancelled: bool,
+}
+
+impl<G> CancellableGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// whether the iteration ended because of the token
+    #[inline]
+    pub fn was_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    /// get back the generator
+    #[inline]
+    pub fn into_inner(self) -> GenIter<G> {
+        self.gen
+    }
+}
+
+impl<G> Iterator for CancellableGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        if self.cancelled || self.token.is_cancelled() {
+            self.cancelled = true;
+            return None;
+        }
+        self.gen.next()
+    }
+}
+
+impl<G> fmt::Debug for CancellableGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CancellableGenIter")
+            .field("gen", &self.gen)
+            .field("cancelled", &self.cancelled)
+            .finish()
+    }
+}
+
+/// `CancellableGenIterReturn<G>` is a [`GenIterReturn`] that stops
+/// once its [`CancelToken`] is cancelled.
+///
+/// created by [`GenIterReturn::cancellable`]. Like `GenIterReturn`,
+/// `&mut CancellableGenIterReturn<G>` is the iterator.
+///
+/// The token is checked before every resume: once it is cancelled, the
+/// generator is dropped like with [`Abortable::stop`], and
+/// [`outcome`](CancellableGenIterReturn::outcome) is [`AbortOutcome::Stopped`].
+/// A generator that completed stays completed.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter_return, AbortOutcome};
+///
+/// let (mut g, token) = gen_iter_return!({
+///     yield 1;
+///     yield 2;
+///     "done"
+/// }).cancellable();
+///
+/// assert_eq!((&mut g).next(), Some(1));
+/// token.cancel();
+/// assert_eq!((&mut g).next(), None);
+/// assert_eq!(g.outcome(), AbortOutcome::Stopped { yields_so_far: 1 });
+/// ```
+pub struct CancellableGenIterReturn<G: Coroutine + Unpin> {
+    gen: Abortable<G>,
+    token: CancelToken,
+}
+
+impl<G: Coroutine + Unpin> CancellableGenIterReturn<G> {
+    /// whether the generator is running, completed, or was cancelled
+    #[inline]
+    pub fn outcome(&self) -> AbortOutcome<'_, G::Return> {
+        self.gen.outcome()
+    }
+
+    /// whether the iteration ended because of the token
+    #[inline]
+    pub fn was_cancelled(&self) -> bool {
+        matches!(self.outcome(), AbortOutcome::Stopped { .. })
+    }
+
+    /// the return value if the generator completed, else `self`
+    pub fn return_or_self(self) -> Result<G::Return, Self> {
+        let token = self.token;
+        self.gen.return_or_self().map_err(|gen| CancellableGenIterReturn { gen, token })
+    }
+}
+
+impl<G: Coroutine + Unpin> Iterator for &mut CancellableGenIterReturn<G> {
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        if self.token.is_cancelled() {
+            self.gen.stop();
+        }
+        (&mut self.gen).next()
+    }
+}
+
+impl<G: Coroutine + Unpin> FusedIterator for &mut CancellableGenIterReturn<G> {}
+
+impl<G> fmt::Debug for CancellableGenIterReturn<G>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CancellableGenIterReturn")
+            .field("gen", &self.gen)
+            .field("token", &self.token)
+            .finish()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// stop the iteration when the returned token is cancelled, see [`CancellableGenIter`]
+    pub fn cancellable(self) -> (CancellableGenIter<G>, CancelToken) {
+        let token = CancelToken::default();
+        (CancellableGenIter { gen: self, token: token.clone(), cancelled: false }, token)
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// stop the generator when the returned token is cancelled, see [`CancellableGenIterReturn`]
+    pub fn cancellable(self) -> (CancellableGenIterReturn<G>, CancelToken) {
+        let token = CancelToken::default();
+        (CancellableGenIterReturn { gen: self.abortable(), token: token.clone() }, token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AbortOutcome, GenIter, GenIterReturn};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{mpsc, Arc};
+    use std::thread;
+
+    #[test]
+    fn cancel_from_another_thread() {
+        let resumes = Arc::new(AtomicUsize::new(0));
+        let counter = resumes.clone();
+        let (mut it, token) = GenIter(#[coroutine] move || {
+            for i in 0.. {
+                counter.fetch_add(1, Ordering::SeqCst);
+                yield i;
+            }
+        }).cancellable();
+
+        let (step, steps) = mpsc::channel::<()>();
+        let (stepped, stepped_rx) = mpsc::channel();
+        // one `next()` for every step
+        let worker = thread::spawn(move || {
+            for () in steps {
+                stepped.send(it.next()).unwrap();
+            }
+            it.was_cancelled()
+        });
+        for i in 0..2 {
+            step.send(()).unwrap();
+            assert_eq!(stepped_rx.recv().unwrap(), Some(i));
+        }
+
+        let canceller = thread::spawn(move || token.cancel());
+        canceller.join().unwrap();
+        step.send(()).unwrap();
+        step.send(()).unwrap();
+        assert_eq!(stepped_rx.recv().unwrap(), None);
+        assert_eq!(stepped_rx.recv().unwrap(), None);
+
+        drop(step);
+        assert!(worker.join().unwrap());
+        assert_eq!(resumes.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn not_cancelled() {
+        let (it, token) = GenIter(#[coroutine] || {
+            yield 1;
+            yield 2;
+        }).cancellable();
+        let mut it = it.into_inner();
+        assert_eq!(it.next(), Some(1));
+        token.cancel();
+        // the generator itself does not know about the token
+        assert_eq!(it.next(), Some(2));
+
+        let (mut it, token) = GenIter(#[coroutine] || {
+            yield 1;
+        }).cancellable();
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(it.next(), None);
+        assert!(!it.was_cancelled());
+        assert!(!token.is_cancelled());
+    }
+
+    #[test]
+    fn gen_iter_return() {
+        let (mut g, token) = GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            yield 2;
+            "done"
+        }).cancellable();
+        assert_eq!((&mut g).next(), Some(1));
+        assert_eq!(g.outcome(), AbortOutcome::Running);
+
+        thread::spawn(move || token.cancel()).join().unwrap();
+        assert_eq!((&mut g).next(), None);
+        assert!(g.was_cancelled());
+        assert_eq!(g.outcome(), AbortOutcome::Stopped { yields_so_far: 1 });
+        assert!(g.return_or_self().is_err());
+
+        // cancelling a completed generator keeps its return value
+        let (mut g, token) = GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            "done"
+        }).cancellable();
+        assert_eq!((&mut g).count(), 1);
+        token.cancel();
+        assert_eq!((&mut g).next(), None);
+        assert!(!g.was_cancelled());
+        assert_eq!(g.return_or_self().ok(), Some("done"));
+    }
+}
@@ -0,0 +1,179 @@
+use core::ops::Coroutine;
+use core::marker::Unpin;
+use core::fmt;
+
+use std::panic;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread::{self, JoinHandle};
+
+use crate::GenIterExchange;
+
+/// the handle of the worker thread created by [`GenIterExchange::channel_pair`]
+pub struct ExchangeJoinHandle<G: Coroutine<R> + Unpin, R>(JoinHandle<Result<G::Return, GenIterExchange<G, R>>>);
+
+impl<G: Coroutine<R> + Unpin, R> fmt::Debug for ExchangeJoinHandle<G, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ExchangeJoinHandle").field(&self.0).finish()
+    }
+}
+
+impl<G: Coroutine<R> + Unpin, R> ExchangeJoinHandle<G, R> {
+    /// wait for the worker thread to finish.
+    ///
+    /// returns the return value of the generator, or the generator itself
+    /// if the worker stopped before it completed. a panic of the generator
+    /// is resumed on the calling thread.
+    pub fn join(self) -> Result<G::Return, GenIterExchange<G, R>> {
+        match self.0.join() {
+            Ok(r) => r,
+            Err(e) => panic::resume_unwind(e),
+        }
+    }
+
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.0.is_finished()
+    }
+}
+
+impl<G: Coroutine<R> + Unpin, R> GenIterExchange<G, R> {
+    /// move the generator to its own thread, talking to it through channels.
+    ///
+    /// every argument sent to the returned `SyncSender` resumes the generator once,
+    /// and what it yields comes out of the returned `Receiver`.
+    /// both channels hold at most `bound` values, so a slow side holds back the other.
+    ///
+    /// the worker thread stops when
+    /// - the generator completes: sending further arguments returns an error,
+    ///   and the yields channel is closed
+    /// - every sender of arguments is dropped
+    /// - the receiver of yields is dropped: the value that could not be sent is lost
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_exchange;
+    ///
+    /// let (tx, rx, handle) = gen_exchange!(|mut s: String| {
+    ///     while s != "quit" {
+    ///         s = yield s.to_uppercase();
+    ///     }
+    ///     "done"
+    /// }).channel_pair(1);
+    ///
+    /// tx.send("hello".to_string()).unwrap();
+    /// assert_eq!(rx.recv().unwrap(), "HELLO");
+    ///
+    /// tx.send("quit".to_string()).unwrap();
+    /// assert_eq!(handle.join().ok(), Some("done"));
+    /// assert!(tx.send("late".to_string()).is_err());
+    /// ```
+    pub fn channel_pair(self, bound: usize) -> (SyncSender<R>, Receiver<G::Yield>, ExchangeJoinHandle<G, R>)
+    where
+        G: Send + 'static,
+        G::Yield: Send + 'static,
+        G::Return: Send + 'static,
+        R: Send + 'static,
+    {
+        let (args_tx, args_rx) = mpsc::sync_channel(bound);
+        let (yields_tx, yields_rx) = mpsc::sync_channel(bound);
+
+        let mut exchange = self;
+        let worker = thread::spawn(move || {
+            for arg in args_rx {
+                match exchange.next_with(arg) {
+                    Some(y) => if yields_tx.send(y).is_err() {
+                        break;
+                    },
+                    None => break,
+                }
+            }
+            exchange.return_or_self()
+        });
+
+        (args_tx, yields_rx, ExchangeJoinHandle(worker))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIterExchange;
+    use std::panic;
+    use std::thread;
+    use std::vec::Vec;
+
+    #[test]
+    fn transform_across_threads() {
+        let (tx, rx, handle) = GenIterExchange::new(#[coroutine] |mut x: u64| {
+            let mut count = 0;
+            while x != 0 {
+                count += 1;
+                x = yield x * x;
+            }
+            count
+        }).channel_pair(2);
+
+        let producer = thread::spawn(move || {
+            for x in (1..=10).chain(Some(0)) {
+                tx.send(x).unwrap();
+            }
+        });
+
+        let squares: Vec<u64> = rx.iter().collect();
+        assert_eq!(squares, (1..=10).map(|x| x * x).collect::<Vec<_>>());
+
+        producer.join().unwrap();
+        assert_eq!(handle.join().ok(), Some(10));
+    }
+
+    #[test]
+    fn senders_dropped() {
+        let (tx, rx, handle) = GenIterExchange::new(#[coroutine] |mut x: u32| {
+            loop {
+                x = yield x + 1;
+            }
+        }).channel_pair(1);
+
+        tx.send(1).unwrap();
+        assert_eq!(rx.recv(), Ok(2));
+        drop(tx);
+        assert!(rx.recv().is_err());
+
+        let mut g = match handle.join() {
+            Ok(_) => panic!("generator is done but should not"),
+            Err(g) => g,
+        };
+        assert_eq!(g.next_with(5), Some(6));
+    }
+
+    #[test]
+    fn send_after_completion() {
+        let (tx, rx, handle) = GenIterExchange::new(#[coroutine] |x: u32| {
+            if x > 100 {
+                yield x;
+            }
+            x * 2
+        }).channel_pair(0);
+
+        tx.send(21).unwrap();
+        assert!(rx.recv().is_err());
+        assert_eq!(handle.join().ok(), Some(42));
+        assert!(tx.send(1).is_err());
+    }
+
+    #[test]
+    fn worker_panic_propagates() {
+        let (tx, _rx, handle) = GenIterExchange::new(#[coroutine] |x: u32| {
+            if x == 0 {
+                panic!("zero");
+            }
+            let _: u32 = yield x;
+        }).channel_pair(1);
+
+        tx.send(0).unwrap();
+        let err = panic::catch_unwind(panic::AssertUnwindSafe(|| handle.join()))
+            .err()
+            .expect("join should resume the panic");
+        assert_eq!(err.downcast_ref::<&str>(), Some(&"zero"));
+    }
+}
@@ -0,0 +1,322 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::GenIterReturn;
+
+/// `ChunkByYields<G, F, K>` groups the consecutive items of a generator with the same key,
+/// like `slice::chunk_by`.
+///
+/// created by [`GenIterReturn::chunk_by_yields`], with the `alloc` feature.
+/// Like `GenIterReturn`, `&mut ChunkByYields<G, F, K>` is the iterator, over
+/// the key of every group with its items. The last group is given once the generator
+/// completes, then [`return_or_self`](ChunkByYields::return_or_self) gives the return value.
+///
+/// A group ends when an item with another key comes, this item is kept
+/// for the next group. [`CountByYields`] only counts the items of every group.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter_return;
+///
+/// let mut words = gen_iter_return!({
+///     for w in ["apple", "avocado", "banana", "cherry", "cranberry"] {
+///         yield w;
+///     }
+///     5
+/// }).chunk_by_yields(|w| w.chars().next());
+///
+/// assert_eq!((&mut words).collect::<Vec<_>>(), [
+///     (Some('a'), vec!["apple", "avocado"]),
+///     (Some('b'), vec!["banana"]),
+///     (Some('c'), vec!["cherry", "cranberry"]),
+/// ]);
+/// assert_eq!(words.return_or_self().ok(), Some(5));
+/// ```
+#[cfg(feature = "alloc")]
+pub struct ChunkByYields<G: Coroutine + Unpin, F, K> {
+    gen: GenIterReturn<G>,
+    key: F,
+    // the first item of the next group
+    next: Option<(K, G::Yield)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<G, F, K> ChunkByYields<G, F, K>
+where
+    G: Coroutine + Unpin,
+    F: FnMut(&G::Yield) -> K,
+    K: PartialEq,
+{
+    /// whether the generator completed and every group was given
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.next.is_none() && self.gen.is_done()
+    }
+
+    /// the return value once every group was given, else `self`
+    pub fn return_or_self(self) -> Result<G::Return, Self> {
+        if self.next.is_some() {
+            return Err(self);
+        }
+        let ChunkByYields { gen, key, next } = self;
+        gen.return_or_self().map_err(|gen| ChunkByYields { gen, key, next })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<G, F, K> Iterator for &mut ChunkByYields<G, F, K>
+where
+    G: Coroutine + Unpin,
+    F: FnMut(&G::Yield) -> K,
+    K: PartialEq,
+{
+    type Item = (K, Vec<G::Yield>);
+
+    fn next(&mut self) -> Option<(K, Vec<G::Yield>)> {
+        let (k, first) = match self.next.take() {
+            Some(next) => next,
+            None => {
+                let y = (&mut self.gen).next()?;
+                ((self.key)(&y), y)
+            },
+        };
+        let mut group = Vec::from([first]);
+        for y in &mut self.gen {
+            let k2 = (self.key)(&y);
+            if k2 != k {
+                self.next = Some((k2, y));
+                break;
+            }
+            group.push(y);
+        }
+        Some((k, group))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<G, F, K> FusedIterator for &mut ChunkByYields<G, F, K>
+where
+    G: Coroutine + Unpin,
+    F: FnMut(&G::Yield) -> K,
+    K: PartialEq,
+{}
+
+#[cfg(feature = "alloc")]
+impl<G, F, K> fmt::Debug for ChunkByYields<G, F, K>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Yield: fmt::Debug,
+    G::Return: fmt::Debug,
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ChunkByYields")
+            .field("gen", &self.gen)
+            .field("next", &self.next)
+            .finish_non_exhaustive()
+    }
+}
+
+/// `CountByYields<G, F, K>` counts the consecutive items of a generator with the same key,
+/// like `ChunkByYields` (with `alloc`) without keeping the items.
+///
+/// created by [`GenIterReturn::count_by_yields`]. Like `GenIterReturn`,
+/// `&mut CountByYields<G, F, K>` is the iterator, over the key of every group
+/// with its number of items.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter_return;
+///
+/// let mut runs = gen_iter_return!({
+///     for b in [0, 0, 1, 1, 1, 0] {
+///         yield b;
+///     }
+/// }).count_by_yields(|b| *b);
+///
+/// assert_eq!((&mut runs).collect::<Vec<_>>(), [(0, 2), (1, 3), (0, 1)]);
+/// ```
+pub struct CountByYields<G: Coroutine + Unpin, F, K> {
+    gen: GenIterReturn<G>,
+    key: F,
+    // the key of the first item of the next group
+    next: Option<K>,
+}
+
+impl<G, F, K> CountByYields<G, F, K>
+where
+    G: Coroutine + Unpin,
+    F: FnMut(&G::Yield) -> K,
+    K: PartialEq,
+{
+    /// whether the generator completed and every group was counted
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.next.is_none() && self.gen.is_done()
+    }
+
+    /// the return value once every group was counted, else `self`
+    pub fn return_or_self(self) -> Result<G::Return, Self> {
+        if self.next.is_some() {
+            return Err(self);
+        }
+        let CountByYields { gen, key, next } = self;
+        gen.return_or_self().map_err(|gen| CountByYields { gen, key, next })
+    }
+}
+
+impl<G, F, K> Iterator for &mut CountByYields<G, F, K>
+where
+    G: Coroutine + Unpin,
+    F: FnMut(&G::Yield) -> K,
+    K: PartialEq,
+{
+    type Item = (K, usize);
+
+    fn next(&mut self) -> Option<(K, usize)> {
+        let k = match self.next.take() {
+            Some(k) => k,
+            None => {
+                let y = (&mut self.gen).next()?;
+                (self.key)(&y)
+            },
+        };
+        let mut count = 1;
+        for y in &mut self.gen {
+            let k2 = (self.key)(&y);
+            if k2 != k {
+                self.next = Some(k2);
+                break;
+            }
+            count += 1;
+        }
+        Some((k, count))
+    }
+}
+
+impl<G, F, K> FusedIterator for &mut CountByYields<G, F, K>
+where
+    G: Coroutine + Unpin,
+    F: FnMut(&G::Yield) -> K,
+    K: PartialEq,
+{}
+
+impl<G, F, K> fmt::Debug for CountByYields<G, F, K>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CountByYields")
+            .field("gen", &self.gen)
+            .field("next", &self.next)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// group the consecutive items with the same `key`, see [`ChunkByYields`]
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn chunk_by_yields<F, K>(self, key: F) -> ChunkByYields<G, F, K>
+    where
+        F: FnMut(&G::Yield) -> K,
+        K: PartialEq,
+    {
+        ChunkByYields { gen: self, key, next: None }
+    }
+
+    /// count the consecutive items with the same `key`, see [`CountByYields`]
+    #[inline]
+    pub fn count_by_yields<F, K>(self, key: F) -> CountByYields<G, F, K>
+    where
+        F: FnMut(&G::Yield) -> K,
+        K: PartialEq,
+    {
+        CountByYields { gen: self, key, next: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIterReturn;
+    use std::vec::Vec;
+
+    fn digits(n: &'static [u32]) -> GenIterReturn<impl core::ops::Coroutine<Yield = u32, Return = usize> + Unpin> {
+        GenIterReturn::new(#[coroutine] move || {
+            for d in n {
+                yield *d;
+            }
+            n.len()
+        })
+    }
+
+    #[test]
+    fn count_groups() {
+        let mut g = digits(&[1, 3, 5, 2, 4, 7]).count_by_yields(|d| d % 2);
+        assert_eq!((&mut g).collect::<Vec<_>>(), [(1, 3), (0, 2), (1, 1)]);
+        assert!(g.is_done());
+        assert_eq!(g.return_or_self().ok(), Some(6));
+    }
+
+    #[test]
+    fn count_pending_group() {
+        let mut g = digits(&[1, 2]).count_by_yields(|d| *d);
+        assert_eq!((&mut g).next(), Some((1, 1)));
+        // the generator completed, but the last group was not counted yet
+        let mut g = g.return_or_self().err().unwrap();
+        assert_eq!((&mut g).next(), Some((2, 1)));
+        assert_eq!(g.return_or_self().ok(), Some(2));
+    }
+
+    #[test]
+    fn count_empty() {
+        let mut g = digits(&[]).count_by_yields(|d| *d);
+        assert_eq!((&mut g).next(), None);
+        assert_eq!(g.return_or_self().ok(), Some(0));
+    }
+
+    #[cfg(feature = "alloc")]
+    mod alloc_tests {
+        use super::digits;
+        use std::vec;
+        use std::vec::Vec;
+
+        #[test]
+        fn groups() {
+            let mut g = digits(&[1, 3, 5, 2, 4, 7]).chunk_by_yields(|d| d % 2);
+            assert_eq!((&mut g).collect::<Vec<_>>(), [
+                (1, vec![1, 3, 5]),
+                (0, vec![2, 4]),
+                (1, vec![7]),
+            ]);
+            assert_eq!(g.return_or_self().ok(), Some(6));
+        }
+
+        #[test]
+        fn single_group() {
+            static ALL: [u32; 1000] = [7; 1000];
+            let mut g = digits(&ALL).chunk_by_yields(|_| ());
+            let (key, group) = (&mut g).next().unwrap();
+            assert_eq!((key, group.len()), ((), 1000));
+            assert_eq!((&mut g).next(), None);
+            assert_eq!(g.return_or_self().ok(), Some(1000));
+        }
+
+        #[test]
+        fn empty() {
+            let mut g = digits(&[]).chunk_by_yields(|d| *d);
+            assert_eq!((&mut g).next(), None);
+            assert!(g.is_done());
+            assert_eq!(g.return_or_self().ok(), Some(0));
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use core::ops::Coroutine;
+use core::marker::Unpin;
+use core::fmt;
+
+use crate::GenIterExchange;
+
+/// the result of [`connect`]
+pub enum ConnectOutcome<A, B, R>
+where
+    A: Coroutine<R> + Unpin,
+    B: Coroutine<A::Yield> + Unpin,
+{
+    /// the first generator completed, the second one can still be resumed
+    FirstFinished {
+        ret: A::Return,
+        second: GenIterExchange<B, A::Yield>,
+    },
+    /// the second generator completed, the first one can still be resumed
+    SecondFinished {
+        ret: B::Return,
+        first: GenIterExchange<A, R>,
+    },
+}
+
+impl<A, B, R> fmt::Debug for ConnectOutcome<A, B, R>
+where
+    A: Coroutine<R> + Unpin + fmt::Debug,
+    B: Coroutine<A::Yield> + Unpin + fmt::Debug,
+    A::Return: fmt::Debug,
+    B::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConnectOutcome::FirstFinished { ref ret, ref second } => f
+                .debug_struct("FirstFinished")
+                .field("ret", ret)
+                .field("second", second)
+                .finish(),
+            ConnectOutcome::SecondFinished { ref ret, ref first } => f
+                .debug_struct("SecondFinished")
+                .field("ret", ret)
+                .field("first", first)
+                .finish(),
+        }
+    }
+}
+
+/// wire two generators to each other: what one yields is the resume argument of the other.
+///
+/// `a` is resumed first with `first`, then `b` with what `a` yielded, then `a` again
+/// with what `b` yielded, and so on until one of them completes.
+/// there is no other way to stop, two generators that never complete loop forever.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{connect, gen_exchange, ConnectOutcome};
+///
+/// let ping = gen_exchange!(|mut pong: u32| {
+///     while pong < 3 {
+///         pong = yield pong + 1;
+///     }
+///     "ping is tired"
+/// });
+/// let pong = gen_exchange!(|mut ping: u32| {
+///     loop {
+///         ping = yield ping;
+///     }
+/// });
+///
+/// match connect(ping, pong, 0) {
+///     ConnectOutcome::FirstFinished { ret, .. } => assert_eq!(ret, "ping is tired"),
+///     ConnectOutcome::SecondFinished { .. } => unreachable!(),
+/// }
+/// ```
+pub fn connect<A, B, R>(
+    mut a: GenIterExchange<A, R>,
+    mut b: GenIterExchange<B, A::Yield>,
+    first: R,
+) -> ConnectOutcome<A, B, R>
+where
+    A: Coroutine<R> + Unpin,
+    B: Coroutine<A::Yield, Yield = R> + Unpin,
+{
+    let mut msg = first;
+    loop {
+        let to_b = match a.next_with(msg) {
+            Some(y) => y,
+            None => {
+                let ret = a.return_or_self().ok().expect("generator is done");
+                return ConnectOutcome::FirstFinished { ret, second: b };
+            },
+        };
+        msg = match b.next_with(to_b) {
+            Some(y) => y,
+            None => {
+                let ret = b.return_or_self().ok().expect("generator is done");
+                return ConnectOutcome::SecondFinished { ret, first: a };
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{connect, ConnectOutcome};
+    use crate::GenIterExchange;
+    use core::ops::Coroutine;
+
+    #[derive(Debug, PartialEq)]
+    enum Bid {
+        Offer(u32),
+        Accept(u32),
+    }
+
+    /// raises its offer by 10 until it meets the ask, and returns the price it paid
+    fn buyer() -> GenIterExchange<impl Coroutine<u32, Yield = Bid, Return = u32> + Unpin, u32> {
+        GenIterExchange::new(#[coroutine] |mut ask: u32| {
+            let mut offer = 10;
+            loop {
+                if offer >= ask {
+                    return ask;
+                }
+                offer += 10;
+                ask = yield Bid::Offer(offer);
+            }
+        })
+    }
+
+    /// lowers its ask by 15 until an offer is within `tolerance` of it,
+    /// and returns the price it sold for
+    fn seller(tolerance: u32) -> GenIterExchange<impl Coroutine<Bid, Yield = u32, Return = u32> + Unpin, Bid> {
+        GenIterExchange::new(#[coroutine] move |mut bid: Bid| {
+            let mut ask = 100;
+            loop {
+                match bid {
+                    Bid::Accept(price) => return price,
+                    Bid::Offer(offer) if offer + tolerance >= ask => return offer,
+                    Bid::Offer(_) => ask -= 15,
+                }
+                bid = yield ask;
+            }
+        })
+    }
+
+    #[test]
+    fn first_finishes() {
+        let mut seller = match connect(buyer(), seller(0), 100) {
+            ConnectOutcome::FirstFinished { ret, second } => {
+                assert_eq!(ret, 40);
+                second
+            },
+            _ => panic!("unexpected outcome"),
+        };
+
+        assert!(!seller.is_complete());
+        assert_eq!(seller.next_with(Bid::Accept(40)), None);
+        assert_eq!(seller.return_or_self().ok(), Some(40));
+    }
+
+    #[test]
+    fn second_finishes() {
+        let mut buyer = match connect(buyer(), seller(15), 100) {
+            ConnectOutcome::SecondFinished { ret, first } => {
+                assert_eq!(ret, 50);
+                first
+            },
+            _ => panic!("unexpected outcome"),
+        };
+
+        assert_eq!(buyer.next_with(45), None);
+        assert_eq!(buyer.return_or_self().ok(), Some(45));
+    }
+
+    #[test]
+    fn already_complete() {
+        let mut buyer = buyer();
+        assert_eq!(buyer.next_with(0), None);
+
+        match connect(buyer, seller(0), 100) {
+            ConnectOutcome::FirstFinished { ret, second } => {
+                assert_eq!(ret, 0);
+                assert!(!second.is_complete());
+            },
+            _ => panic!("unexpected outcome"),
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use core::ops::Coroutine;
+use core::iter::Iterator;
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::{fmt, mem};
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::GenIterReturn;
+
+type BoxedCoroutine<Y, R> = Pin<Box<dyn Coroutine<Yield = Y, Return = R>>>;
+
+/// the id of a generator in a [`CoroutineSet`], given by [`CoroutineSet::insert`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CoroutineId(u64);
+
+/// `CoroutineSet<Y, R>` runs a changing set of generators in turn,
+/// like the tasks of a scheduler or the entities of a game.
+///
+/// The generators are boxed, they can have different types if their items and return
+/// values have the same types. Every [`poll_round`](CoroutineSet::poll_round) resumes each
+/// of them once, in the order they were inserted, and gives their items with their id.
+/// The generators completing are removed from the set, their return values are recorded
+/// with their id, see [`completions`](CoroutineSet::completions).
+///
+/// A round borrows the set, so a generator is only inserted or removed between rounds.
+/// A generator inserted is resumed from the next round on.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter_return, CoroutineSet};
+///
+/// let mut set = CoroutineSet::new();
+/// let a = set.insert(gen_iter_return!({
+///     yield 'a';
+///     1
+/// }));
+/// let b = set.insert(gen_iter_return!({
+///     yield 'b';
+///     yield 'c';
+///     2
+/// }));
+///
+/// let mut items = Vec::new();
+/// while !set.is_empty() {
+///     set.poll_round(|_, y| items.push(y));
+/// }
+/// assert_eq!(items, ['a', 'b', 'c']);
+/// assert_eq!(set.completions(), [(a, 1), (b, 2)]);
+/// ```
+pub struct CoroutineSet<Y, R> {
+    // in the order of their ids
+    gens: Vec<(CoroutineId, GenIterReturn<BoxedCoroutine<Y, R>>)>,
+    completions: Vec<(CoroutineId, R)>,
+    next_id: u64,
+}
+
+impl<Y, R> CoroutineSet<Y, R> {
+    #[inline]
+    pub fn new() -> Self {
+        CoroutineSet { gens: Vec::new(), completions: Vec::new(), next_id: 0 }
+    }
+
+    /// add a generator to the set, it is resumed from the next round on.
+    ///
+    /// a generator already done is recorded as completed at the next round.
+    pub fn insert<G>(&mut self, gen: GenIterReturn<G>) -> CoroutineId
+    where
+        G: Coroutine<Yield = Y, Return = R> + Unpin + 'static,
+    {
+        let id = CoroutineId(self.next_id);
+        self.next_id += 1;
+        let gen = gen.map_inner(|g| Box::pin(g) as BoxedCoroutine<Y, R>);
+        self.gens.push((id, gen));
+        id
+    }
+
+    /// take a generator out of the set, `None` if it is not in the set,
+    /// like once it completed
+    pub fn remove(&mut self, id: CoroutineId) -> Option<GenIterReturn<BoxedCoroutine<Y, R>>> {
+        let i = self.gens.binary_search_by_key(&id, |(id, _)| *id).ok()?;
+        Some(self.gens.remove(i).1)
+    }
+
+    /// whether the generator `id` is in the set
+    #[inline]
+    pub fn contains(&self, id: CoroutineId) -> bool {
+        self.gens.binary_search_by_key(&id, |(id, _)| *id).is_ok()
+    }
+
+    /// the number of generators in the set
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.gens.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.gens.is_empty()
+    }
+
+    /// resume every generator once, giving the items to `sink` with the id of their
+    /// generator, and record the generators which completed.
+    ///
+    /// returns the number of generators still running.
+    pub fn poll_round<F>(&mut self, mut sink: F) -> usize
+    where
+        F: FnMut(CoroutineId, Y),
+    {
+        let mut done = false;
+        for &mut (id, ref mut gen) in self.gens.iter_mut() {
+            match (&mut *gen).next() {
+                Some(y) => sink(id, y),
+                None => done = true,
+            }
+        }
+        if done {
+            for (id, gen) in mem::take(&mut self.gens) {
+                match gen.return_or_self() {
+                    Ok(r) => self.completions.push((id, r)),
+                    Err(gen) => self.gens.push((id, gen)),
+                }
+            }
+        }
+        self.gens.len()
+    }
+
+    /// the ids and return values of the generators which completed, in order
+    #[inline]
+    pub fn completions(&self) -> &[(CoroutineId, R)] {
+        &self.completions
+    }
+
+    /// take the recorded completions out of the set
+    #[inline]
+    pub fn take_completions(&mut self) -> Vec<(CoroutineId, R)> {
+        mem::take(&mut self.completions)
+    }
+}
+
+impl<Y, R> Default for CoroutineSet<Y, R> {
+    #[inline]
+    fn default() -> Self {
+        CoroutineSet::new()
+    }
+}
+
+impl<Y, R: fmt::Debug> fmt::Debug for CoroutineSet<Y, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CoroutineSet")
+            .field("ids", &self.gens.iter().map(|(id, _)| id).collect::<Vec<_>>())
+            .field("completions", &self.completions)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CoroutineSet;
+    use crate::GenIterReturn;
+    use std::vec::Vec;
+
+    /// yields `0..len`, returns `len * 10`
+    fn staggered(len: u32) -> GenIterReturn<impl core::ops::Coroutine<Yield = u32, Return = u32> + Unpin> {
+        GenIterReturn::new(#[coroutine] move || {
+            for i in 0..len {
+                yield i;
+            }
+            len * 10
+        })
+    }
+
+    #[test]
+    fn to_completion() {
+        let mut set = CoroutineSet::new();
+        let ids = [2, 0, 3, 1].map(|len| set.insert(staggered(len)));
+
+        let mut rounds = Vec::new();
+        loop {
+            let mut round = Vec::new();
+            let running = set.poll_round(|id, y| {
+                let src = ids.iter().position(|i| *i == id).unwrap();
+                round.push((src, y));
+            });
+            rounds.push(round);
+            if running == 0 {
+                break;
+            }
+        }
+        assert_eq!(rounds, [
+            Vec::from([(0, 0), (2, 0), (3, 0)]),
+            Vec::from([(0, 1), (2, 1)]),
+            Vec::from([(2, 2)]),
+            Vec::new(),
+        ]);
+        assert_eq!(set.completions(), [
+            (ids[1], 0),
+            (ids[3], 10),
+            (ids[0], 20),
+            (ids[2], 30),
+        ]);
+        assert!(!set.contains(ids[0]));
+        assert_eq!(set.take_completions().len(), 4);
+        assert!(set.completions().is_empty());
+    }
+
+    #[test]
+    fn insert_remove_between_rounds() {
+        let mut set = CoroutineSet::new();
+        let a = set.insert(staggered(3));
+        let b = set.insert(staggered(3));
+        let mut items = Vec::new();
+        set.poll_round(|id, y| items.push((id, y)));
+
+        let c = set.insert(staggered(1));
+        let mut b_gen = set.remove(b).unwrap();
+        assert!(set.remove(b).is_none());
+        assert_eq!((&mut b_gen).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(set.len(), 2);
+
+        while set.poll_round(|id, y| items.push((id, y))) > 0 {}
+        assert_eq!(items, [(a, 0), (b, 0), (a, 1), (c, 0), (a, 2)]);
+        assert_eq!(set.completions(), [(c, 10), (a, 30)]);
+    }
+
+    #[test]
+    fn already_done() {
+        let mut g = staggered(2);
+        assert_eq!((&mut g).count(), 2);
+        let mut set = CoroutineSet::new();
+        let id = set.insert(g);
+        assert_eq!(set.poll_round(|_, _| panic!("no items")), 0);
+        assert_eq!(set.completions(), [(id, 20)]);
+    }
+}
@@ -0,0 +1,206 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::cell::Cell;
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::fmt;
+
+use crate::GenIter;
+
+/// `ResumeCounter` counts how a generator wrapped in [`Counted`] is driven,
+/// readable at any time, even while the iterator is consumed by other adaptors.
+///
+/// Comparing [`resumes`](ResumeCounter::resumes) with the number of items reaching
+/// the consumer shows how many were dropped on the way, or that the generator is
+/// driven twice. A counter shared by several `Counted` generators adds them up.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter, ResumeCounter};
+///
+/// let counter = ResumeCounter::new();
+/// let evens = gen_iter!({
+///     for i in 0..10 {
+///         yield i;
+///     }
+/// }).counted(&counter).filter(|i| i % 2 == 0).take(2);
+///
+/// assert_eq!(evens.collect::<Vec<_>>(), [0, 2]);
+/// assert_eq!(counter.resumes(), 3);
+/// assert!(!counter.completed());
+/// ```
+#[derive(Default)]
+pub struct ResumeCounter {
+    resumes: Cell<usize>,
+    yields: Cell<usize>,
+    completed: Cell<bool>,
+}
+
+impl ResumeCounter {
+    #[inline]
+    pub const fn new() -> Self {
+        ResumeCounter {
+            resumes: Cell::new(0),
+            yields: Cell::new(0),
+            completed: Cell::new(false),
+        }
+    }
+
+    /// how many times the generator was resumed
+    #[inline]
+    pub fn resumes(&self) -> usize {
+        self.resumes.get()
+    }
+
+    /// how many values the generator yielded
+    #[inline]
+    pub fn yields(&self) -> usize {
+        self.yields.get()
+    }
+
+    /// whether the generator completed
+    #[inline]
+    pub fn completed(&self) -> bool {
+        self.completed.get()
+    }
+}
+
+impl fmt::Debug for ResumeCounter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ResumeCounter")
+            .field("resumes", &self.resumes())
+            .field("yields", &self.yields())
+            .field("completed", &self.completed())
+            .finish()
+    }
+}
+
+/// a generator that counts its resumes in a [`ResumeCounter`].
+///
+/// created by [`GenIter::counted`], or `Counted::new` for a generator
+/// wrapped in a [`GenIterReturn`](crate::GenIterReturn) or a
+/// [`GenIterExchange`](crate::GenIterExchange).
+#[derive(Debug)]
+pub struct Counted<'c, G> {
+    gen: G,
+    counter: &'c ResumeCounter,
+}
+
+impl<'c, G> Counted<'c, G> {
+    #[inline]
+    pub fn new(gen: G, counter: &'c ResumeCounter) -> Self {
+        Counted { gen, counter }
+    }
+
+    /// get back the generator
+    #[inline]
+    pub fn into_inner(self) -> G {
+        self.gen
+    }
+}
+
+impl<G, R> Coroutine<R> for Counted<'_, G>
+where
+    G: Coroutine<R> + Unpin,
+{
+    type Yield = G::Yield;
+    type Return = G::Return;
+
+    #[inline]
+    fn resume(self: Pin<&mut Self>, arg: R) -> CoroutineState<Self::Yield, Self::Return> {
+        let this = self.get_mut();
+        let counter = this.counter;
+        counter.resumes.set(counter.resumes.get() + 1);
+        let state = Pin::new(&mut this.gen).resume(arg);
+        match state {
+            CoroutineState::Yielded(_) => counter.yields.set(counter.yields.get() + 1),
+            CoroutineState::Complete(_) => counter.completed.set(true),
+        }
+        state
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// count the resumes of this generator in `counter`, see [`ResumeCounter`]
+    #[inline]
+    pub fn counted(self, counter: &ResumeCounter) -> GenIter<Counted<'_, G>> {
+        GenIter(Counted::new(self.0, counter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Counted, ResumeCounter};
+    use crate::{GenIter, GenIterExchange, GenIterReturn};
+    use std::vec::Vec;
+
+    #[test]
+    fn filter_take() {
+        let counter = ResumeCounter::new();
+        let mut it = GenIter(#[coroutine] || {
+            for i in 0..10 {
+                yield i;
+            }
+        }).counted(&counter).filter(|i| i % 3 == 0).take(3);
+
+        assert_eq!(it.next(), Some(0));
+        assert_eq!((counter.resumes(), counter.yields()), (1, 1));
+        assert_eq!(it.next(), Some(3));
+        assert_eq!((counter.resumes(), counter.yields()), (4, 4));
+        assert_eq!(it.next(), Some(6));
+        // `take` stops without resuming again
+        assert_eq!(it.next(), None);
+        assert_eq!((counter.resumes(), counter.yields()), (7, 7));
+        assert!(!counter.completed());
+    }
+
+    #[test]
+    fn to_completion() {
+        let counter = ResumeCounter::new();
+        let sum: u32 = GenIter(#[coroutine] || {
+            for i in 0..10 {
+                yield i;
+            }
+        }).counted(&counter).map(|i| i * 2).sum();
+        assert_eq!(sum, 90);
+        assert_eq!(counter.resumes(), 11);
+        assert_eq!(counter.yields(), 10);
+        assert!(counter.completed());
+    }
+
+    #[test]
+    fn return_and_exchange() {
+        let counter = ResumeCounter::new();
+        let mut g = GenIterReturn::new(Counted::new(#[coroutine] || {
+            yield 'a';
+            yield 'b';
+            "end"
+        }, &counter));
+        assert_eq!((&mut g).collect::<Vec<_>>(), ['a', 'b']);
+        // `GenIterReturn` does not resume again once it is done
+        assert_eq!((&mut g).next(), None);
+        assert_eq!(g.return_or_self().ok(), Some("end"));
+        assert_eq!((counter.resumes(), counter.yields()), (3, 2));
+        assert!(counter.completed());
+
+        let shared = ResumeCounter::new();
+        let mut g = GenIterExchange::new(Counted::new(#[coroutine] |mut x: u32| {
+            while x != 0 {
+                x = yield x * 2;
+            }
+        }, &shared));
+        assert_eq!(g.next_with(2), Some(4));
+        assert_eq!(g.next_with(0), None);
+        let mut again = GenIter(#[coroutine] || {
+            for i in 0..10 {
+                yield i;
+            }
+        }).counted(&shared);
+        again.next();
+        assert_eq!((shared.resumes(), shared.yields()), (3, 2));
+        assert!(shared.completed());
+    }
+}
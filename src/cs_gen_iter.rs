@@ -0,0 +1,186 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::cell::RefCell;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::pin::Pin;
+
+use critical_section::Mutex;
+
+use crate::GenIter;
+
+/// `CsGenIter<G>` shares one generator among several execution contexts
+/// without `std`, like the main loop and an interrupt handler.
+///
+/// Every [`next_shared`](CsGenIter::next_shared) resumes the generator once
+/// inside a critical section, so each yield goes to exactly one caller.
+/// [`new`](CsGenIter::new) is a `const fn`, so it can live in a `static`
+/// when the generator type can be named.
+///
+/// The whole resume runs inside the critical section: on a single core
+/// target interrupts stay disabled until the generator yields, so the code
+/// between two `yield`s adds directly to the interrupt latency.
+/// Keep it short.
+///
+/// If the generator panics, later calls panic too.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// extern crate critical_section;
+/// extern crate gen_iter;
+///
+/// use gen_iter::CsGenIter;
+///
+/// let ids = CsGenIter::new(#[coroutine] || {
+///     let mut id = 0u32;
+///     loop {
+///         id += 1;
+///         yield id;
+///     }
+/// });
+///
+/// assert_eq!(ids.next_shared(), Some(1));
+/// assert_eq!(ids.next_shared(), Some(2));
+/// ```
+pub struct CsGenIter<G>(Mutex<RefCell<Option<G>>>)
+where
+    G: Coroutine<Return = ()> + Unpin;
+
+impl<G> CsGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    #[inline]
+    pub const fn new(g: G) -> Self {
+        CsGenIter(Mutex::new(RefCell::new(Some(g))))
+    }
+
+    /// resume the generator once inside a critical section,
+    /// `None` once it is done.
+    pub fn next_shared(&self) -> Option<G::Yield> {
+        critical_section::with(|cs| {
+            let mut g = self.0.borrow_ref_mut(cs);
+            match Pin::new(g.as_mut()?).resume(()) {
+                CoroutineState::Yielded(y) => Some(y),
+                CoroutineState::Complete(()) => {
+                    *g = None;
+                    None
+                },
+            }
+        })
+    }
+
+    /// whether the generator completed
+    pub fn is_done(&self) -> bool {
+        critical_section::with(|cs| self.0.borrow_ref(cs).is_none())
+    }
+
+    /// get back the generator, `None` if it completed
+    #[inline]
+    pub fn into_inner(self) -> Option<G> {
+        self.0.into_inner().into_inner()
+    }
+}
+
+/// same as [`CsGenIter::next_shared`]
+impl<G> Iterator for &CsGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    type Item = G::Yield;
+
+    #[inline]
+    fn next(&mut self) -> Option<G::Yield> {
+        self.next_shared()
+    }
+}
+
+impl<G> FusedIterator for &CsGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{}
+
+impl<G> From<G> for CsGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    #[inline]
+    fn from(g: G) -> Self {
+        CsGenIter::new(g)
+    }
+}
+
+impl<G> From<GenIter<G>> for CsGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    #[inline]
+    fn from(g: GenIter<G>) -> Self {
+        CsGenIter::new(g.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CsGenIter;
+    use core::ops::{Coroutine, CoroutineState};
+    use core::pin::Pin;
+    use std::thread;
+    use std::vec::Vec;
+
+    #[test]
+    fn two_threads() {
+        let shared = CsGenIter::new(#[coroutine] || {
+            for i in 0..20_000u32 {
+                yield i;
+            }
+        });
+
+        let (mut a, b) = thread::scope(|s| {
+            let other = s.spawn(|| {
+                let mut seen = Vec::new();
+                while let Some(i) = shared.next_shared() {
+                    seen.push(i);
+                }
+                seen
+            });
+            let mine = (&shared).collect::<Vec<_>>();
+            (mine, other.join().unwrap())
+        });
+
+        a.extend(b);
+        a.sort_unstable();
+        assert_eq!(a, (0..20_000).collect::<Vec<_>>());
+        assert!(shared.is_done());
+        assert_eq!(shared.next_shared(), None);
+        assert!(shared.into_inner().is_none());
+    }
+
+    /// a generator type that can be named in a `static`
+    struct Countdown(u32);
+
+    impl Coroutine for Countdown {
+        type Yield = u32;
+        type Return = ();
+
+        fn resume(mut self: Pin<&mut Self>, _: ()) -> CoroutineState<u32, ()> {
+            if self.0 == 0 {
+                return CoroutineState::Complete(());
+            }
+            self.0 -= 1;
+            CoroutineState::Yielded(self.0)
+        }
+    }
+
+    static SHARED: CsGenIter<Countdown> = CsGenIter::new(Countdown(1000));
+
+    #[test]
+    fn in_static() {
+        let other = thread::spawn(|| (&SHARED).collect::<Vec<_>>());
+        let mut seen = (&SHARED).collect::<Vec<_>>();
+        seen.extend(other.join().unwrap());
+        seen.sort_unstable();
+        assert_eq!(seen, (0..1000).collect::<Vec<_>>());
+        assert!(SHARED.is_done());
+    }
+}
@@ -0,0 +1,196 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::time::Duration;
+use core::fmt;
+
+use std::time::Instant;
+
+use crate::GenIterReturn;
+
+/// how a [`WithDeadline`] ended, from [`WithDeadline::into_outcome`]
+#[derive(Debug)]
+pub enum DeadlineOutcome<G: Coroutine + Unpin> {
+    /// the generator completed with this return value
+    Completed(G::Return),
+    /// the deadline passed, the rest of the generator can still be resumed
+    Expired(GenIterReturn<G>),
+    /// neither, the iteration stopped before
+    Running(GenIterReturn<G>),
+}
+
+/// `WithDeadline<G>` is a [`GenIterReturn`] whose iteration ends at a deadline,
+/// like a batch job with a time budget.
+///
+/// created by [`GenIterReturn::with_deadline`] and [`GenIterReturn::with_deadline_at`].
+/// Like `GenIterReturn`, `&mut WithDeadline<G>` is the iterator.
+///
+/// The deadline is checked before every resume, a resume is never interrupted:
+/// a slow one can end after the deadline, and its item is still returned.
+/// Once the deadline passed, the iteration ends, and
+/// [`into_outcome`](WithDeadline::into_outcome) gives back the rest of the generator.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter_return, DeadlineOutcome};
+/// use std::time::Duration;
+///
+/// let mut job = gen_iter_return!({
+///     for i in 0..3 {
+///         yield i;
+///     }
+///     "done"
+/// }).with_deadline(Duration::from_secs(60));
+///
+/// assert_eq!((&mut job).count(), 3);
+/// match job.into_outcome() {
+///     DeadlineOutcome::Completed(r) => assert_eq!(r, "done"),
+///     _ => unreachable!(),
+/// }
+/// ```
+pub struct WithDeadline<G: Coroutine + Unpin> {
+    gen: GenIterReturn<G>,
+    deadline: Instant,
+    expired: bool,
+}
+
+impl<G: Coroutine + Unpin> WithDeadline<G> {
+    #[inline]
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// whether the iteration ended because of the deadline
+    #[inline]
+    pub fn is_expired(&self) -> bool {
+        self.expired
+    }
+
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.gen.is_done()
+    }
+
+    /// the return value if the generator completed, else the rest of the generator
+    pub fn into_outcome(self) -> DeadlineOutcome<G> {
+        match self.gen.return_or_self() {
+            Ok(r) => DeadlineOutcome::Completed(r),
+            Err(g) if self.expired => DeadlineOutcome::Expired(g),
+            Err(g) => DeadlineOutcome::Running(g),
+        }
+    }
+}
+
+impl<G: Coroutine + Unpin> Iterator for &mut WithDeadline<G> {
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        if self.expired || self.gen.is_done() {
+            return None;
+        }
+        if Instant::now() >= self.deadline {
+            self.expired = true;
+            return None;
+        }
+        (&mut self.gen).next()
+    }
+}
+
+impl<G: Coroutine + Unpin> FusedIterator for &mut WithDeadline<G> {}
+
+impl<G> fmt::Debug for WithDeadline<G>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WithDeadline")
+            .field("gen", &self.gen)
+            .field("deadline", &self.deadline)
+            .field("expired", &self.expired)
+            .finish()
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// end the iteration once `budget` has passed from now, see [`WithDeadline`]
+    #[inline]
+    pub fn with_deadline(self, budget: Duration) -> WithDeadline<G> {
+        self.with_deadline_at(Instant::now() + budget)
+    }
+
+    /// end the iteration at `deadline`, see [`WithDeadline`]
+    #[inline]
+    pub fn with_deadline_at(self, deadline: Instant) -> WithDeadline<G> {
+        WithDeadline { gen: self, deadline, expired: false }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DeadlineOutcome, GenIterReturn};
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use std::vec::Vec;
+
+    const SLOW: Duration = Duration::from_millis(30);
+
+    /// sleeps before every yield
+    fn slow(n: u32) -> GenIterReturn<impl core::ops::Coroutine<Yield = u32, Return = &'static str> + Unpin> {
+        GenIterReturn::new(#[coroutine] move || {
+            for i in 0..n {
+                thread::sleep(SLOW);
+                yield i;
+            }
+            "done"
+        })
+    }
+
+    #[test]
+    fn expired() {
+        let mut job = slow(100).with_deadline(SLOW * 3);
+        let done = (&mut job).collect::<Vec<_>>();
+        // the resume running at the deadline overshoots it
+        assert!((1..=4).contains(&done.len()), "{:?}", done);
+        assert!(Instant::now() >= job.deadline());
+        assert!(job.is_expired());
+        assert!(!job.is_done());
+        assert_eq!((&mut job).next(), None);
+
+        match job.into_outcome() {
+            DeadlineOutcome::Expired(mut rest) => {
+                assert_eq!((&mut rest).next(), Some(done.len() as u32));
+            },
+            _ => panic!("expected the deadline to pass"),
+        }
+    }
+
+    #[test]
+    fn completed() {
+        let mut job = slow(3).with_deadline(Duration::from_secs(60));
+        assert_eq!((&mut job).collect::<Vec<_>>(), [0, 1, 2]);
+        assert!(!job.is_expired());
+        assert!(job.is_done());
+        match job.into_outcome() {
+            DeadlineOutcome::Completed(r) => assert_eq!(r, "done"),
+            _ => panic!("expected a completion"),
+        }
+    }
+
+    #[test]
+    fn running_and_past_deadline() {
+        let mut job = slow(3).with_deadline(Duration::from_secs(60));
+        assert_eq!((&mut job).next(), Some(0));
+        assert!(matches!(job.into_outcome(), DeadlineOutcome::Running(_)));
+
+        // a deadline in the past never resumes the generator
+        let mut job = slow(3).with_deadline_at(Instant::now());
+        assert_eq!((&mut job).next(), None);
+        assert!(job.is_expired());
+        match job.into_outcome() {
+            DeadlineOutcome::Expired(mut rest) => assert_eq!((&mut rest).count(), 3),
+            _ => panic!("expected the deadline to pass"),
+        }
+    }
+}
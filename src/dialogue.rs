@@ -0,0 +1,319 @@
+use core::ops::Coroutine;
+use core::marker::Unpin;
+use core::fmt;
+
+use crate::GenIterExchange;
+
+/// the answer of a [`Dialogue`] to a message
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Reply<Y, R> {
+    /// the generator yielded a message
+    Message(Y),
+    /// the generator is finished, with its return value
+    Finished(R),
+}
+
+/// `Dialogue<G, R>` is a typed send / receive wrapper around a generator
+/// that takes resume arguments of type `R`.
+///
+/// Every [`send`](Dialogue::send) gets a [`Reply`]: either the next message
+/// yielded by the generator, or a reference to its return value.
+/// Sending after the generator is finished keeps returning
+/// [`Reply::Finished`], the message is dropped.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{Dialogue, Reply};
+///
+/// let mut d = Dialogue::new(#[coroutine] |mut name: &'static str| {
+///     while name.is_empty() {
+///         name = yield "what is your name?";
+///     }
+///     name.len()
+/// });
+///
+/// assert_eq!(d.send(""), Reply::Message("what is your name?"));
+/// assert_eq!(d.send("ferris"), Reply::Finished(&6));
+/// assert_eq!(d.send("again"), Reply::Finished(&6));
+/// assert_eq!(d.into_return().ok(), Some(6));
+/// ```
+pub struct Dialogue<G: Coroutine<R> + Unpin, R>(GenIterExchange<G, R>);
+
+impl<G: Coroutine<R> + Unpin, R> Dialogue<G, R> {
+    #[inline]
+    pub fn new(g: G) -> Self {
+        Dialogue(GenIterExchange::new(g))
+    }
+
+    /// resume the generator with `msg`
+    #[inline]
+    pub fn send(&mut self, msg: R) -> Reply<G::Yield, &G::Return> {
+        match self.0.next_with(msg) {
+            Some(y) => Reply::Message(y),
+            None => Reply::Finished(self.0.return_ref().expect("generator is done")),
+        }
+    }
+
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.0.is_complete()
+    }
+
+    /// get the return value of the generator, or `self` back if it is not finished
+    #[inline]
+    pub fn into_return(self) -> Result<G::Return, Self> {
+        self.0.return_or_self().map_err(Dialogue)
+    }
+
+    /// get back the underlying [`GenIterExchange`]
+    #[inline]
+    pub fn into_inner(self) -> GenIterExchange<G, R> {
+        self.0
+    }
+}
+
+impl<G, R> Clone for Dialogue<G, R>
+where
+    G: Coroutine<R> + Unpin + Clone,
+    G::Return: Clone,
+{
+    fn clone(&self) -> Self {
+        Dialogue(self.0.clone())
+    }
+}
+
+impl<G, R> Copy for Dialogue<G, R>
+where
+    G: Coroutine<R> + Unpin + Copy,
+    G::Return: Copy,
+{}
+
+impl<G, R> fmt::Debug for Dialogue<G, R>
+where
+    G: Coroutine<R> + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Dialogue").field(&self.0).finish()
+    }
+}
+
+impl<G: Coroutine<R> + Unpin, R> From<G> for Dialogue<G, R> {
+    #[inline]
+    fn from(g: G) -> Self {
+        Dialogue::new(g)
+    }
+}
+
+impl<G: Coroutine<R> + Unpin, R> From<GenIterExchange<G, R>> for Dialogue<G, R> {
+    #[inline]
+    fn from(g: GenIterExchange<G, R>) -> Self {
+        Dialogue(g)
+    }
+}
+
+/// macro to simplify construction of a [`Dialogue`] from a generator closure,
+/// see [`gen_exchange!`](crate::gen_exchange) for the accepted closures and blocks
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_dialogue, Reply};
+///
+/// enum Cmd {
+///     Add(i32),
+///     Total,
+///     Quit,
+/// }
+///
+/// let mut d = gen_dialogue!(|mut cmd: Cmd| {
+///     let mut total = 0;
+///     loop {
+///         cmd = match cmd {
+///             Cmd::Add(n) => { total += n; yield "ok" },
+///             Cmd::Total => yield if total < 0 { "negative" } else { "positive" },
+///             Cmd::Quit => return total,
+///         };
+///     }
+/// });
+///
+/// assert_eq!(d.send(Cmd::Add(3)), Reply::Message("ok"));
+/// assert_eq!(d.send(Cmd::Add(-5)), Reply::Message("ok"));
+/// assert_eq!(d.send(Cmd::Total), Reply::Message("negative"));
+/// assert_eq!(d.send(Cmd::Quit), Reply::Finished(&-2));
+/// ```
+#[macro_export]
+macro_rules! gen_dialogue {
+    ($block: block) => {
+        $crate::Dialogue::new(#[coroutine] |_| $block)
+    };
+    (move $block: block) => {
+        $crate::Dialogue::new(#[coroutine] move |_| $block)
+    };
+    ($arg: ty => $block: block) => {
+        $crate::Dialogue::new(#[coroutine] |_: $arg| $block)
+    };
+    (move $arg: ty => $block: block) => {
+        $crate::Dialogue::new(#[coroutine] move |_: $arg| $block)
+    };
+    ($($closure: tt)*) => {
+        $crate::Dialogue::new(#[coroutine] $($closure)*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dialogue, Reply};
+
+    #[derive(Debug, PartialEq)]
+    enum Request {
+        Hello,
+        Get(&'static str),
+        Bye,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Response {
+        Welcome,
+        Value(u32),
+        NotFound,
+        Error,
+    }
+
+    /// a tiny key-value protocol, `Hello` must come first,
+    /// the return value is the number of successful lookups
+    #[test]
+    fn conversation() {
+        let mut d = Dialogue::new(#[coroutine] |first: Request| {
+            if first != Request::Hello {
+                let _ = yield Response::Error;
+                return 0;
+            }
+
+            let mut found = 0;
+            let mut req = yield Response::Welcome;
+            loop {
+                req = match req {
+                    Request::Get("answer") => {
+                        found += 1;
+                        yield Response::Value(42)
+                    },
+                    Request::Get(_) => yield Response::NotFound,
+                    Request::Hello => yield Response::Error,
+                    Request::Bye => return found,
+                };
+            }
+        });
+
+        assert_eq!(d.send(Request::Hello), Reply::Message(Response::Welcome));
+        assert_eq!(d.send(Request::Get("answer")), Reply::Message(Response::Value(42)));
+        assert_eq!(d.send(Request::Get("question")), Reply::Message(Response::NotFound));
+        assert_eq!(d.send(Request::Get("answer")), Reply::Message(Response::Value(42)));
+        assert!(!d.is_finished());
+
+        assert_eq!(d.send(Request::Bye), Reply::Finished(&2));
+        assert!(d.is_finished());
+
+        // sending after finish neither panics nor resumes
+        assert_eq!(d.send(Request::Hello), Reply::Finished(&2));
+        assert_eq!(d.into_return().ok(), Some(2));
+    }
+
+    #[test]
+    fn gen_dialogue_macro() {
+        let greeting = "hello";
+        let mut d = gen_dialogue!(move |mut name: &'static str| {
+            let mut count = 0;
+            while !name.is_empty() {
+                count += 1;
+                name = yield (greeting, name);
+            }
+            count
+        });
+
+        assert_eq!(d.send("a"), Reply::Message(("hello", "a")));
+        assert_eq!(d.send("b"), Reply::Message(("hello", "b")));
+        assert_eq!(d.send(""), Reply::Finished(&2));
+        assert!(d.is_finished());
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Cmd {
+        Say(&'static str),
+        Count,
+        Quit,
+    }
+
+    #[test]
+    fn gen_dialogue_block() {
+        let mut d = gen_dialogue!(Cmd => {
+            let mut said = 0;
+            let mut cmd = yield "ready";
+            loop {
+                cmd = match cmd {
+                    Cmd::Say(word) => {
+                        said += 1;
+                        yield word
+                    },
+                    Cmd::Count => yield if said > 1 { "many" } else { "few" },
+                    Cmd::Quit => return said,
+                };
+            }
+        });
+
+        // the first message only starts the dialogue
+        assert_eq!(d.send(Cmd::Quit), Reply::Message("ready"));
+        assert_eq!(d.send(Cmd::Say("hi")), Reply::Message("hi"));
+        assert_eq!(d.send(Cmd::Count), Reply::Message("few"));
+        assert_eq!(d.send(Cmd::Say("yo")), Reply::Message("yo"));
+        assert_eq!(d.send(Cmd::Count), Reply::Message("many"));
+        assert_eq!(d.send(Cmd::Quit), Reply::Finished(&2));
+        assert!(d.is_finished());
+
+        let limit = 2;
+        let mut d = gen_dialogue!(move Cmd => {
+            let mut n = 0;
+            while n < limit {
+                if (yield n) == Cmd::Quit {
+                    break;
+                }
+                n += 1;
+            }
+            n
+        });
+        assert_eq!(d.send(Cmd::Count), Reply::Message(0));
+        assert_eq!(d.send(Cmd::Count), Reply::Message(1));
+        assert_eq!(d.send(Cmd::Count), Reply::Finished(&2));
+
+        let mut d = gen_dialogue!(move {
+            let name: &str = yield "name?";
+            name.len() + limit
+        });
+        assert_eq!(d.send(""), Reply::Message("name?"));
+        assert_eq!(d.send("ferris"), Reply::Finished(&8));
+
+        let mut d = gen_dialogue!({
+            let n: u32 = yield ();
+            n + 1
+        });
+        assert_eq!(d.send(0), Reply::Message(()));
+        assert_eq!(d.send(41), Reply::Finished(&42));
+    }
+
+    #[test]
+    fn stop_early() {
+        let mut d = Dialogue::new(#[coroutine] |mut n: u32| {
+            loop {
+                n = yield n + 1;
+            }
+        });
+
+        assert_eq!(d.send(1), Reply::Message(2));
+
+        let mut g = match d.into_return() {
+            Ok(_) => panic!("generator is done but should not"),
+            Err(d) => d.into_inner(),
+        };
+        assert_eq!(g.next_with(2), Some(3));
+    }
+}
@@ -0,0 +1,144 @@
+use core::ops::Coroutine;
+use core::cell::Cell;
+use core::iter::Iterator;
+use core::marker::Unpin;
+use core::fmt;
+
+use crate::GenIter;
+
+/// `DisplayJoin<'s, G>` formats the yields of a generator, separated by `sep`,
+/// without collecting them first.
+///
+/// created by [`GenIter::display_join`].
+///
+/// The generator is run by `fmt`, so a `DisplayJoin` can only be formatted once:
+/// formatting it again writes nothing. [`write_to`](DisplayJoin::write_to)
+/// is the same, taking the `DisplayJoin` by value.
+pub struct DisplayJoin<'s, G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    gen: Cell<Option<GenIter<G>>>,
+    sep: &'s str,
+}
+
+impl<G> DisplayJoin<'_, G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: fmt::Display,
+{
+    /// write the yields into `w`, separated by `sep`
+    pub fn write_to<W: fmt::Write + ?Sized>(self, w: &mut W) -> fmt::Result {
+        write!(w, "{}", self)
+    }
+}
+
+impl<G> fmt::Display for DisplayJoin<'_, G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut gen = match self.gen.take() {
+            Some(g) => g,
+            None => return Ok(()),
+        };
+        if let Some(first) = gen.next() {
+            fmt::Display::fmt(&first, f)?;
+            for y in gen {
+                f.write_str(self.sep)?;
+                fmt::Display::fmt(&y, f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<G> fmt::Debug for DisplayJoin<'_, G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DisplayJoin")
+            .field("sep", &self.sep)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: fmt::Display,
+{
+    /// format the yields separated by `sep`, see [`DisplayJoin`].
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_iter;
+    ///
+    /// let squares = gen_iter!({
+    ///     for i in 1..=4 {
+    ///         yield i * i;
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(format!("[{}]", squares.display_join(", ")), "[1, 4, 9, 16]");
+    /// ```
+    #[inline]
+    pub fn display_join(self, sep: &str) -> DisplayJoin<'_, G> {
+        DisplayJoin { gen: Cell::new(Some(self)), sep }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIter;
+    use core::fmt::Write;
+    use std::format;
+    use std::string::String;
+
+    #[test]
+    fn separators() {
+        let words = GenIter(#[coroutine] || {
+            yield "a";
+            yield "b";
+            yield "c";
+        });
+        assert_eq!(format!("{}", words.display_join(" | ")), "a | b | c");
+
+        let one = GenIter(#[coroutine] || {
+            yield 1.5;
+        });
+        assert_eq!(format!("{}", one.display_join(", ")), "1.5");
+
+        let empty = GenIter(#[coroutine] || {
+            if false {
+                yield 0;
+            }
+        });
+        assert_eq!(format!("{}", empty.display_join(", ")), "");
+    }
+
+    #[test]
+    fn formatted_once() {
+        let j = GenIter(#[coroutine] || {
+            yield 'x';
+            yield 'y';
+        }).display_join("");
+        assert_eq!(format!("{}", j), "xy");
+        assert_eq!(format!("{}", j), "");
+    }
+
+    #[test]
+    fn write_to() {
+        let mut out = String::from("> ");
+        GenIter(#[coroutine] || {
+            for i in 0..3 {
+                yield i;
+            }
+        }).display_join(", ").write_to(&mut out).unwrap();
+        out.write_char('!').unwrap();
+        assert_eq!(out, "> 0, 1, 2!");
+    }
+}
@@ -0,0 +1,277 @@
+use core::ops::Coroutine;
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use core::fmt;
+
+use futures_core::stream::{FusedStream, Stream};
+use futures_sink::Sink;
+
+use crate::{GenIterExchange, SinkComplete};
+
+/// `GenDuplex<G, R>` is both a [`Sink`] of resume arguments
+/// and a [`Stream`] of the yields of a generator.
+///
+/// created by [`GenIterExchange::into_duplex`].
+///
+/// The ordering contract is one send for one item:
+/// - `start_send` stores the next resume argument, the sink is not ready again
+///   until the stream used it. `poll_flush` does not wait for that,
+///   the stored argument counts as delivered
+/// - `poll_next` resumes the generator with the stored argument,
+///   it is pending until an argument was sent
+///
+/// Once the generator completes, the stream ends and the sink refuses items
+/// with [`SinkComplete`]. Closing the sink ends the stream after the last
+/// argument was used, the generator stays incomplete.
+pub struct GenDuplex<G: Coroutine<R> + Unpin, R> {
+    exchange: GenIterExchange<G, R>,
+    arg: Option<R>,
+    closed: bool,
+    stream_waker: Option<Waker>,
+    sink_waker: Option<Waker>,
+}
+
+// the argument is never pinned
+impl<G: Coroutine<R> + Unpin, R> Unpin for GenDuplex<G, R> {}
+
+impl<G: Coroutine<R> + Unpin, R> GenDuplex<G, R> {
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.exchange.is_complete()
+    }
+
+    /// get back the underlying [`GenIterExchange`],
+    /// and the argument that was sent but not used yet
+    #[inline]
+    pub fn into_parts(self) -> (GenIterExchange<G, R>, Option<R>) {
+        (self.exchange, self.arg)
+    }
+
+    fn wake_sink(&mut self) {
+        if let Some(w) = self.sink_waker.take() {
+            w.wake();
+        }
+    }
+
+    /// the sink is ready when the last argument was used
+    fn poll_used(&mut self, cx: &mut Context) -> Poll<Result<(), SinkComplete>> {
+        if self.is_complete() {
+            Poll::Ready(Err(SinkComplete))
+        } else if self.arg.is_some() {
+            self.sink_waker = Some(cx.waker().clone());
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
+
+impl<G: Coroutine<R> + Unpin, R> Stream for GenDuplex<G, R> {
+    type Item = G::Yield;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<G::Yield>> {
+        let this = self.get_mut();
+        let arg = match this.arg.take() {
+            Some(arg) => arg,
+            None if this.closed || this.is_complete() => return Poll::Ready(None),
+            None => {
+                this.stream_waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            },
+        };
+
+        let y = this.exchange.next_with(arg);
+        this.wake_sink();
+        Poll::Ready(y)
+    }
+}
+
+impl<G: Coroutine<R> + Unpin, R> FusedStream for GenDuplex<G, R> {
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.arg.is_none() && (self.closed || self.is_complete())
+    }
+}
+
+impl<G: Coroutine<R> + Unpin, R> Sink<R> for GenDuplex<G, R> {
+    type Error = SinkComplete;
+
+    #[inline]
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), SinkComplete>> {
+        self.get_mut().poll_used(cx)
+    }
+
+    /// # Panics
+    /// if the last argument was not used yet, call `poll_ready` first
+    fn start_send(self: Pin<&mut Self>, item: R) -> Result<(), SinkComplete> {
+        let this = self.get_mut();
+        if this.is_complete() {
+            return Err(SinkComplete);
+        }
+        assert!(this.arg.is_none(), "GenDuplex::start_send called before poll_ready");
+
+        this.arg = Some(item);
+        if let Some(w) = this.stream_waker.take() {
+            w.wake();
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<Result<(), SinkComplete>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context) -> Poll<Result<(), SinkComplete>> {
+        let this = self.get_mut();
+        this.closed = true;
+        if let Some(w) = this.stream_waker.take() {
+            w.wake();
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<G, R> fmt::Debug for GenDuplex<G, R>
+where
+    G: Coroutine<R> + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GenDuplex")
+            .field("exchange", &self.exchange)
+            .field("arg", &self.arg)
+            .field("closed", &self.closed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G: Coroutine<R> + Unpin, R> GenIterExchange<G, R> {
+    /// turn the generator into a [`GenDuplex`], a `Sink` of resume arguments
+    /// and a `Stream` of yields.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// extern crate futures;
+    /// extern crate gen_iter;
+    ///
+    /// use futures::executor::block_on;
+    /// use futures::{SinkExt, StreamExt};
+    /// use gen_iter::gen_exchange;
+    ///
+    /// let mut d = gen_exchange!(|mut x: u32| {
+    ///     loop {
+    ///         x = yield x * 10;
+    ///     }
+    /// }).into_duplex();
+    ///
+    /// block_on(d.send(1)).unwrap();
+    /// assert_eq!(block_on(d.next()), Some(10));
+    /// block_on(d.send(2)).unwrap();
+    /// assert_eq!(block_on(d.next()), Some(20));
+    /// ```
+    #[inline]
+    pub fn into_duplex(self) -> GenDuplex<G, R> {
+        GenDuplex {
+            exchange: self,
+            arg: None,
+            closed: false,
+            stream_waker: None,
+            sink_waker: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIterExchange, SinkComplete};
+    use core::pin::Pin;
+    use core::task::{Context, Poll, Waker};
+    use futures::executor::block_on;
+    use futures::future::join;
+    use futures::sink::{Sink, SinkExt};
+    use futures::stream::{self, FusedStream, Stream, StreamExt};
+    use std::vec::Vec;
+
+    #[test]
+    fn one_send_one_item() {
+        let mut d = GenIterExchange::new(#[coroutine] |mut x: u32| {
+            loop {
+                x = yield x + 1;
+            }
+        }).into_duplex();
+        let mut cx = Context::from_waker(Waker::noop());
+
+        // nothing sent yet
+        assert_eq!(Pin::new(&mut d).poll_next(&mut cx), Poll::Pending);
+
+        assert_eq!(Pin::new(&mut d).poll_ready(&mut cx), Poll::Ready(Ok(())));
+        Pin::new(&mut d).start_send(1).unwrap();
+        // the argument was not used yet
+        assert_eq!(Pin::new(&mut d).poll_ready(&mut cx), Poll::Pending);
+        assert_eq!(Pin::new(&mut d).poll_flush(&mut cx), Poll::Ready(Ok(())));
+
+        assert_eq!(Pin::new(&mut d).poll_next(&mut cx), Poll::Ready(Some(2)));
+        assert_eq!(Pin::new(&mut d).poll_next(&mut cx), Poll::Pending);
+        assert_eq!(Pin::new(&mut d).poll_ready(&mut cx), Poll::Ready(Ok(())));
+    }
+
+    #[test]
+    fn split_halves() {
+        let d = GenIterExchange::new(#[coroutine] |mut x: u32| {
+            let mut sum = 0;
+            while x != 0 {
+                sum += x;
+                x = yield sum;
+            }
+            sum
+        }).into_duplex();
+        let (tx, rx) = d.split();
+
+        // `forward` closes the sink after the last input
+        let inputs = stream::iter([1, 2, 3, 4]).map(Ok);
+        let (sent, sums) = block_on(join(inputs.forward(tx), rx.collect::<Vec<_>>()));
+        assert_eq!(sent, Ok(()));
+        assert_eq!(sums, [1, 3, 6, 10]);
+    }
+
+    #[test]
+    fn completion() {
+        let mut d = GenIterExchange::new(#[coroutine] |x: u32| {
+            let y = yield x;
+            x + y
+        }).into_duplex();
+
+        block_on(d.send(1)).unwrap();
+        assert_eq!(block_on(d.next()), Some(1));
+        block_on(d.send(2)).unwrap();
+        assert_eq!(block_on(d.next()), None);
+
+        assert!(d.is_complete());
+        assert!(d.is_terminated());
+        assert_eq!(block_on(d.send(3)), Err(SinkComplete));
+        assert_eq!(block_on(d.next()), None);
+
+        let (g, arg) = d.into_parts();
+        assert_eq!(arg, None);
+        assert_eq!(g.return_or_self().ok(), Some(3));
+    }
+
+    #[test]
+    fn close_ends_stream() {
+        let mut d = GenIterExchange::new(#[coroutine] |mut x: u32| {
+            loop {
+                x = yield x;
+            }
+        }).into_duplex();
+
+        block_on(d.feed(5)).unwrap();
+        assert_eq!(block_on(d.next()), Some(5));
+        block_on(SinkExt::close(&mut d)).unwrap();
+        assert_eq!(block_on(d.next()), None);
+        assert!(!d.is_complete());
+    }
+}
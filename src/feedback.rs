@@ -0,0 +1,223 @@
+use core::ops::Coroutine;
+use core::iter::{Iterator, FusedIterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use crate::GenIterExchange;
+
+/// an iterator that resumes a generator with a function of its previous yield.
+///
+/// created by [`GenIterExchange::feedback`].
+pub struct Feedback<G, R, F>
+where
+    G: Coroutine<R> + Unpin,
+{
+    exchange: GenIterExchange<G, R>,
+    arg: Option<R>,
+    f: F,
+}
+
+impl<G, R, F> Feedback<G, R, F>
+where
+    G: Coroutine<R> + Unpin,
+{
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.exchange.is_complete()
+    }
+
+    #[inline]
+    pub fn return_or_self(self) -> Result<G::Return, Self> {
+        let Feedback { exchange, arg, f } = self;
+        exchange.return_or_self().map_err(|exchange| Feedback { exchange, arg, f })
+    }
+
+    /// get back the generator, and the argument prepared for its next resume
+    #[inline]
+    pub fn into_parts(self) -> (GenIterExchange<G, R>, Option<R>) {
+        (self.exchange, self.arg)
+    }
+}
+
+impl<G, R, F> fmt::Debug for Feedback<G, R, F>
+where
+    G: Coroutine<R> + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Feedback")
+            .field("exchange", &self.exchange)
+            .field("arg", &self.arg)
+            .finish()
+    }
+}
+
+impl<G, R, F> Iterator for Feedback<G, R, F>
+where
+    G: Coroutine<R> + Unpin,
+    F: FnMut(&G::Yield) -> R,
+{
+    type Item = G::Yield;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let arg = self.arg.take()?;
+        let y = self.exchange.next_with(arg)?;
+        self.arg = Some((self.f)(&y));
+        Some(y)
+    }
+}
+
+impl<G, R, F> FusedIterator for Feedback<G, R, F>
+where
+    G: Coroutine<R> + Unpin,
+    F: FnMut(&G::Yield) -> R,
+{}
+
+impl<G: Coroutine<R> + Unpin, R> GenIterExchange<G, R> {
+    /// iterate over the yields of the generator, resuming it with `initial` first
+    /// and with `f(&previous_yield)` after that.
+    ///
+    /// `f` is called as soon as a value is yielded, before the value is
+    /// handed out by `next()`, so it also runs for the last value taken
+    /// by a consumer that stops early.
+    /// the return value can be got once the generator is complete.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::GenIterExchange;
+    ///
+    /// // the generator counts down while the responses keep getting smaller
+    /// let mut g = GenIterExchange::new(#[coroutine] |mut x: u32| {
+    ///     loop {
+    ///         let next = yield x;
+    ///         if next >= x {
+    ///             return x;
+    ///         }
+    ///         x = next;
+    ///     }
+    /// }).feedback(10, |x| x / 2);
+    ///
+    /// assert_eq!(g.by_ref().collect::<Vec<_>>(), [10, 5, 2, 1, 0]);
+    /// assert_eq!(g.return_or_self().ok(), Some(0));
+    /// ```
+    #[inline]
+    pub fn feedback<F>(self, initial: R, f: F) -> Feedback<G, R, F>
+    where
+        F: FnMut(&G::Yield) -> R,
+    {
+        Feedback {
+            exchange: self,
+            arg: Some(initial),
+            f,
+        }
+    }
+
+    /// an iterator that answers every yield of the generator with `respond`,
+    /// for use as an `impl Iterator` return type.
+    ///
+    /// this is [`feedback`](GenIterExchange::feedback) without the return value:
+    /// it is dropped together with the iterator. use `feedback` to keep it.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::GenIterExchange;
+    ///
+    /// fn countdown(from: u32) -> impl Iterator<Item = u32> {
+    ///     GenIterExchange::new(#[coroutine] |mut n: u32| {
+    ///         while n > 0 {
+    ///             n = yield n;
+    ///         }
+    ///     }).auto_respond(from, |n| n - 1)
+    /// }
+    ///
+    /// assert_eq!(countdown(3).collect::<Vec<_>>(), [3, 2, 1]);
+    /// ```
+    #[inline]
+    pub fn auto_respond<F>(self, initial: R, respond: F) -> impl Iterator<Item = G::Yield>
+    where
+        F: FnMut(&G::Yield) -> R,
+    {
+        self.feedback(initial, respond)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIterExchange;
+    use std::vec::Vec;
+
+    /// integer square root by newton's method, the generator checks
+    /// for convergence and the feedback function computes the next step
+    #[test]
+    fn newton() {
+        let n = 100;
+        let mut g = GenIterExchange::new(#[coroutine] |mut x: u64| {
+            loop {
+                let next = yield x;
+                if next >= x {
+                    return x;
+                }
+                x = next;
+            }
+        }).feedback(n, |&x| (x + n / x) / 2);
+
+        assert_eq!(g.by_ref().collect::<Vec<_>>(), [100, 50, 26, 14, 10]);
+        assert!(g.is_complete());
+        assert_eq!(g.next(), None);
+        assert_eq!(g.return_or_self().ok(), Some(10));
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Ball {
+        Ping(u32),
+        Pong(u32),
+    }
+
+    #[test]
+    fn ping_pong() {
+        let rally = GenIterExchange::new(#[coroutine] |mut ball: Ball| {
+            loop {
+                ball = match ball {
+                    Ball::Ping(n) => yield Ball::Pong(n + 1),
+                    Ball::Pong(n) => yield Ball::Ping(n + 1),
+                };
+            }
+        }).auto_respond(Ball::Ping(0), |&ball| ball);
+
+        let hits: Vec<_> = rally
+            .take(4)
+            .map(|ball| match ball {
+                Ball::Ping(n) => n,
+                Ball::Pong(n) => n * 10,
+            })
+            .collect();
+        assert_eq!(hits, [10, 2, 30, 4]);
+    }
+
+    #[test]
+    fn stop_early() {
+        let mut calls = 0;
+        let mut g = GenIterExchange::new(#[coroutine] |mut x: u32| {
+            loop {
+                x = yield x;
+            }
+        }).feedback(1, |x| {
+            calls += 1;
+            x + 1
+        });
+
+        assert_eq!(g.by_ref().take(3).collect::<Vec<_>>(), [1, 2, 3]);
+
+        let (mut exchange, arg) = match g.return_or_self() {
+            Ok(_) => panic!("generator is done but should not"),
+            Err(g) => g.into_parts(),
+        };
+        assert_eq!(arg, Some(4));
+        assert_eq!(exchange.next_with(7), Some(7));
+        assert_eq!(calls, 3);
+    }
+}
@@ -0,0 +1,268 @@
+use core::ops::Coroutine;
+use core::ffi::c_void;
+use core::iter::{Iterator, Map};
+use core::marker::Unpin;
+use core::fmt;
+
+use std::boxed::Box;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::GenIter;
+
+/// the result of the `next` callback of an [`FfiGenIter`]
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FfiStatus {
+    /// the generator completed, nothing was written to `out`
+    Done = 0,
+    /// the next value was written to `out`
+    Yielded = 1,
+    /// the generator panicked, nothing was written to `out`
+    Panicked = 2,
+}
+
+/// `FfiGenIter<T>` exports a generator to C as a "pull" interface:
+/// an opaque context pointer, a `next` callback writing the next value
+/// to `out`, and a destructor.
+///
+/// created by [`GenIter::into_ffi`], or [`GenIter::into_ffi_with`] converting
+/// the yields with a closure. `T` is written to C as is, so it should be `#[repr(C)]`
+/// and have no destructor: `next` hands it over to C, which never drops it.
+///
+/// In C, `FfiGenIter<T>` is
+/// ```c
+/// struct gen_iter {
+///     void *ctx;
+///     enum ffi_status (*next)(void *ctx, T *out); /* 0 done, 1 yielded, 2 panicked */
+///     void (*drop)(void *ctx);
+/// };
+/// ```
+///
+/// Ownership rules:
+/// - `ctx` owns the boxed generator: `drop(ctx)` must be called exactly once,
+///   and neither `ctx` nor the callbacks are used after that. Dropping an
+///   `FfiGenIter` in Rust does nothing, so forgetting `drop` leaks the generator
+/// - `drop(NULL)` does nothing, like `free`
+/// - `next` needs a `ctx` that was not dropped yet, and an `out` valid for a write
+///   of a `T`, it is only written when `next` returns [`FfiStatus::Yielded`]
+/// - `next` and `drop` must not be called concurrently on the same `ctx`,
+///   nor from another thread than the one which created it
+///
+/// A panic of the generator, or of the closure of `into_ffi_with`, never unwinds
+/// into C: `next` returns [`FfiStatus::Panicked`] and drops the generator.
+/// After [`Done`](FfiStatus::Done) or `Panicked`, `next` keeps returning `Done`,
+/// and `drop` only frees the context.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter, FfiStatus};
+///
+/// #[repr(C)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let it = gen_iter!({
+///     for i in 0..3 {
+///         yield (i, i * i);
+///     }
+/// }).into_ffi_with(|(x, y)| Point { x, y });
+///
+/// // what the C library does
+/// let mut out = Point { x: 0, y: 0 };
+/// let mut sum = 0;
+/// unsafe {
+///     while (it.next)(it.ctx, &mut out) == FfiStatus::Yielded {
+///         sum += out.y;
+///     }
+///     (it.drop)(it.ctx);
+/// }
+/// assert_eq!(sum, 5);
+/// ```
+#[repr(C)]
+pub struct FfiGenIter<T> {
+    /// the boxed generator
+    pub ctx: *mut c_void,
+    /// write the next value to `out`
+    pub next: unsafe extern "C" fn(ctx: *mut c_void, out: *mut T) -> FfiStatus,
+    /// drop the generator and free `ctx`
+    pub drop: unsafe extern "C" fn(ctx: *mut c_void),
+}
+
+impl<T> FfiGenIter<T> {
+    /// box an iterator, `next` and `drop` are monomorphized for `I`
+    fn new<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = T> + 'static,
+    {
+        let ctx: Box<Option<I>> = Box::new(Some(iter));
+        FfiGenIter {
+            ctx: Box::into_raw(ctx) as *mut c_void,
+            next: ffi_next::<I>,
+            drop: ffi_drop::<I>,
+        }
+    }
+}
+
+/// drop an iterator, ignoring a panic of its destructor
+fn drop_caught<I>(iter: Option<I>) {
+    let _ = panic::catch_unwind(AssertUnwindSafe(move || drop(iter)));
+}
+
+unsafe extern "C" fn ffi_next<I: Iterator>(ctx: *mut c_void, out: *mut I::Item) -> FfiStatus {
+    // `ctx` comes from `FfiGenIter::new::<I>` and was not dropped yet
+    let ctx = unsafe { &mut *(ctx as *mut Option<I>) };
+    let iter = match ctx.as_mut() {
+        Some(iter) => iter,
+        None => return FfiStatus::Done,
+    };
+    match panic::catch_unwind(AssertUnwindSafe(|| iter.next())) {
+        Ok(Some(y)) => {
+            // `out` is valid for a write, and `y` is handed over to the caller
+            unsafe { out.write(y) };
+            FfiStatus::Yielded
+        },
+        Ok(None) => {
+            drop_caught(ctx.take());
+            FfiStatus::Done
+        },
+        Err(_) => {
+            drop_caught(ctx.take());
+            FfiStatus::Panicked
+        },
+    }
+}
+
+unsafe extern "C" fn ffi_drop<I>(ctx: *mut c_void) {
+    if ctx.is_null() {
+        return;
+    }
+    // `ctx` comes from `FfiGenIter::new::<I>` and is dropped only once
+    let ctx = unsafe { Box::from_raw(ctx as *mut Option<I>) };
+    drop_caught(*ctx);
+}
+
+impl<T> fmt::Debug for FfiGenIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FfiGenIter").field("ctx", &self.ctx).finish_non_exhaustive()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin + 'static,
+{
+    /// export this generator to C, see [`FfiGenIter`]
+    #[inline]
+    pub fn into_ffi(self) -> FfiGenIter<G::Yield> {
+        FfiGenIter::new(self)
+    }
+
+    /// export this generator to C, converting the yields with `f`, see [`FfiGenIter`]
+    #[inline]
+    pub fn into_ffi_with<T, F>(self, f: F) -> FfiGenIter<T>
+    where
+        F: FnMut(G::Yield) -> T + 'static,
+    {
+        FfiGenIter::new::<Map<Self, F>>(self.map(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FfiGenIter, FfiStatus};
+    use crate::GenIter;
+    use std::cell::Cell;
+    use std::ptr;
+    use std::rc::Rc;
+    use std::vec;
+    use std::vec::Vec;
+
+    /// sets the flag when dropped
+    struct Guard(Rc<Cell<bool>>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    /// call `next` like C would, until it is not `Yielded`
+    fn drain<T: Copy + Default>(it: &FfiGenIter<T>) -> (Vec<T>, FfiStatus) {
+        let mut values = Vec::new();
+        let mut out = T::default();
+        loop {
+            match unsafe { (it.next)(it.ctx, &mut out) } {
+                FfiStatus::Yielded => values.push(out),
+                status => return (values, status),
+            }
+        }
+    }
+
+    #[test]
+    fn yields_then_done() {
+        let dropped = Rc::new(Cell::new(false));
+        let guard = Guard(dropped.clone());
+        let it = GenIter(#[coroutine] move || {
+            let _guard = guard;
+            for i in 1..=3u64 {
+                yield i;
+            }
+        }).into_ffi();
+
+        assert_eq!(drain(&it), (vec![1, 2, 3], FfiStatus::Done));
+        // the generator is dropped as soon as it completes
+        assert!(dropped.get());
+
+        let mut out = 7;
+        assert_eq!(unsafe { (it.next)(it.ctx, &mut out) }, FfiStatus::Done);
+        assert_eq!(out, 7);
+        unsafe { (it.drop)(it.ctx) };
+    }
+
+    #[test]
+    fn drop_before_completion() {
+        let dropped = Rc::new(Cell::new(false));
+        let guard = Guard(dropped.clone());
+        let it = GenIter(#[coroutine] move || {
+            let _guard = guard;
+            loop {
+                yield 0u8;
+            }
+        }).into_ffi_with(|b| b as i32 - 1);
+
+        let mut out = 0;
+        assert_eq!(unsafe { (it.next)(it.ctx, &mut out) }, FfiStatus::Yielded);
+        assert_eq!(out, -1);
+        assert!(!dropped.get());
+        unsafe { (it.drop)(it.ctx) };
+        assert!(dropped.get());
+
+        unsafe { (it.drop)(ptr::null_mut()) };
+    }
+
+    #[test]
+    fn panics_are_caught() {
+        let dropped = Rc::new(Cell::new(false));
+        let guard = Guard(dropped.clone());
+        let it = GenIter(#[coroutine] move || {
+            let _guard = guard;
+            yield 1i32;
+            panic!("boom");
+        }).into_ffi();
+
+        assert_eq!(drain(&it), (vec![1], FfiStatus::Panicked));
+        assert!(dropped.get());
+        assert_eq!(drain(&it), (vec![], FfiStatus::Done));
+        unsafe { (it.drop)(it.ctx) };
+
+        let it = GenIter(#[coroutine] || {
+            yield 1u32;
+            yield 0;
+        }).into_ffi_with(|n| 10 / n);
+        assert_eq!(drain(&it), (vec![10], FfiStatus::Panicked));
+        unsafe { (it.drop)(it.ctx) };
+    }
+}
@@ -0,0 +1,165 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::iter::Iterator;
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::{fmt, mem};
+
+use alloc::vec::Vec;
+
+use crate::GenIterReturn;
+
+/// a generator yielding the items of the generators yielded by `O`, one after the other,
+/// and returning their return values with the one of `O`.
+///
+/// created by [`flatten_gen`], with the `alloc` feature.
+pub struct FlattenGen<O>
+where
+    O: Coroutine + Unpin,
+    O::Yield: Coroutine + Unpin,
+{
+    // `None` once completed
+    outer: Option<GenIterReturn<O>>,
+    inner: Option<O::Yield>,
+    returns: Vec<<O::Yield as Coroutine>::Return>,
+}
+
+// the return values are never pinned
+impl<O> Unpin for FlattenGen<O>
+where
+    O: Coroutine + Unpin,
+    O::Yield: Coroutine + Unpin,
+{}
+
+impl<O> Coroutine for FlattenGen<O>
+where
+    O: Coroutine + Unpin,
+    O::Yield: Coroutine + Unpin,
+{
+    type Yield = <O::Yield as Coroutine>::Yield;
+    type Return = (Vec<<O::Yield as Coroutine>::Return>, O::Return);
+
+    fn resume(self: Pin<&mut Self>, _: ()) -> CoroutineState<Self::Yield, Self::Return> {
+        let this = self.get_mut();
+        loop {
+            if let Some(ref mut inner) = this.inner {
+                match Pin::new(inner).resume(()) {
+                    CoroutineState::Yielded(y) => return CoroutineState::Yielded(y),
+                    CoroutineState::Complete(r) => {
+                        this.inner = None;
+                        this.returns.push(r);
+                    },
+                }
+            }
+            let mut outer = this.outer.as_mut().expect("`FlattenGen` resumed after completion");
+            match outer.next() {
+                Some(inner) => this.inner = Some(inner),
+                None => match this.outer.take().unwrap().into_result() {
+                    Ok(r) => return CoroutineState::Complete((mem::take(&mut this.returns), r)),
+                    Err(_) => unreachable!(),
+                },
+            }
+        }
+    }
+}
+
+impl<O> fmt::Debug for FlattenGen<O>
+where
+    O: Coroutine + Unpin + fmt::Debug,
+    O::Return: fmt::Debug,
+    O::Yield: Coroutine + Unpin + fmt::Debug,
+    <O::Yield as Coroutine>::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FlattenGen")
+            .field("outer", &self.outer)
+            .field("inner", &self.inner)
+            .field("returns", &self.returns)
+            .finish()
+    }
+}
+
+/// the items of the generators yielded by `outer`, one after the other.
+///
+/// an inner generator runs until it completes before `outer` is resumed again.
+/// the return value is the return values of the inner generators, in order,
+/// with the one of `outer`.
+///
+/// ```
+/// #![feature(coroutines, coroutine_trait, stmt_expr_attributes)]
+///
+/// use gen_iter::{flatten_gen, gen_iter_return};
+/// use std::ops::Coroutine;
+///
+/// fn words(line: &'static str) -> impl Coroutine<Yield = &'static str, Return = usize> + Unpin {
+///     #[coroutine] move || {
+///         let mut count = 0;
+///         for w in line.split(' ') {
+///             count += 1;
+///             yield w;
+///         }
+///         count
+///     }
+/// }
+///
+/// let mut g = flatten_gen(gen_iter_return!({
+///     yield words("hello world");
+///     yield words("bye");
+///     "eof"
+/// }));
+///
+/// assert_eq!((&mut g).collect::<Vec<_>>(), ["hello", "world", "bye"]);
+/// assert_eq!(g.return_or_self().ok(), Some((vec![2, 1], "eof")));
+/// ```
+#[inline]
+pub fn flatten_gen<O>(outer: GenIterReturn<O>) -> GenIterReturn<FlattenGen<O>>
+where
+    O: Coroutine + Unpin,
+    O::Yield: Coroutine + Unpin,
+{
+    GenIterReturn::new(FlattenGen { outer: Some(outer), inner: None, returns: Vec::new() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::flatten_gen;
+    use crate::GenIterReturn;
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    #[test]
+    fn varying_lengths() {
+        let outer_resumes = Cell::new(0);
+        let mut g = flatten_gen(GenIterReturn::new(#[coroutine] || {
+            for len in [3, 0, 1, 0, 2] {
+                outer_resumes.set(outer_resumes.get() + 1);
+                yield #[coroutine] move || {
+                    for i in 0..len {
+                        yield len * 10 + i;
+                    }
+                    len
+                };
+            }
+            "files"
+        }));
+
+        assert_eq!((&mut g).take(3).collect::<Vec<_>>(), [30, 31, 32]);
+        // the first inner generator did not complete yet
+        assert_eq!(outer_resumes.get(), 1);
+        assert_eq!((&mut g).collect::<Vec<_>>(), [10, 20, 21]);
+        assert_eq!(g.return_or_self().ok(), Some((Vec::from([3, 0, 1, 0, 2]), "files")));
+    }
+
+    #[test]
+    fn no_inner() {
+        let mut g = flatten_gen(GenIterReturn::new(#[coroutine] || {
+            if false {
+                yield #[coroutine] || {
+                    yield 0;
+                };
+            }
+            7
+        }));
+        assert_eq!((&mut g).next(), None);
+        assert_eq!(g.return_or_self().ok(), Some((Vec::new(), 7)));
+    }
+}
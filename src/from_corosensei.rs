@@ -0,0 +1,200 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::fmt;
+
+use corosensei::stack::{DefaultStack, Stack};
+use corosensei::CoroutineResult;
+
+/// `FromCorosensei<I, Y, R>` turns a stackful `corosensei::Coroutine` into a
+/// [`Coroutine`], so it can be driven by [`GenIterReturn`](crate::GenIterReturn)
+/// (an iterator with the return value afterwards), by
+/// [`GenIterExchange`](crate::GenIterExchange) (resume arguments of type `I`)
+/// and the other wrappers of this crate.
+///
+/// Unlike a generator closure, a stackful coroutine can suspend from
+/// anywhere below it on its own stack, like deep inside nested function calls
+/// or from a callback called by FFI code.
+///
+/// Like a generator closure, the first resume argument is the second parameter
+/// of the coroutine function, the later ones are returned by `Yielder::suspend`.
+///
+/// # Panics
+/// a panic inside the coroutine unwinds out of `resume`, and so out of
+/// `next` or `next_with` of the wrapper driving it. The coroutine is finished
+/// after that, resuming it again panics.
+///
+/// Dropping a coroutine that is suspended unwinds its stack,
+/// so that the values living on it are dropped too.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// extern crate corosensei;
+/// extern crate gen_iter;
+///
+/// use corosensei::{Coroutine, Yielder};
+/// use gen_iter::{FromCorosensei, GenIterReturn};
+///
+/// fn walk(y: &Yielder<(), u32>, depth: u32) {
+///     if depth > 0 {
+///         walk(y, depth - 1);
+///     }
+///     y.suspend(depth);
+/// }
+///
+/// let co = Coroutine::new(|y, ()| {
+///     walk(y, 3);
+///     "done"
+/// });
+///
+/// let mut g = GenIterReturn::new(FromCorosensei(co));
+/// assert_eq!((&mut g).collect::<Vec<_>>(), [0, 1, 2, 3]);
+/// assert_eq!(g.return_or_self().ok(), Some("done"));
+/// ```
+pub struct FromCorosensei<I, Y, R, S: Stack = DefaultStack>(pub corosensei::Coroutine<I, Y, R, S>);
+
+// the coroutine lives on its own stack, it is never pinned
+impl<I, Y, R, S: Stack> Unpin for FromCorosensei<I, Y, R, S> {}
+
+impl<I, Y, R, S: Stack> FromCorosensei<I, Y, R, S> {
+    /// whether the coroutine has finished, by returning or by panicking
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.0.done()
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> corosensei::Coroutine<I, Y, R, S> {
+        self.0
+    }
+}
+
+impl<I, Y, R, S: Stack> Coroutine<I> for FromCorosensei<I, Y, R, S> {
+    type Yield = Y;
+    type Return = R;
+
+    #[inline]
+    fn resume(self: Pin<&mut Self>, arg: I) -> CoroutineState<Y, R> {
+        match self.get_mut().0.resume(arg) {
+            CoroutineResult::Yield(y) => CoroutineState::Yielded(y),
+            CoroutineResult::Return(r) => CoroutineState::Complete(r),
+        }
+    }
+}
+
+impl<I, Y, R, S: Stack> From<corosensei::Coroutine<I, Y, R, S>> for FromCorosensei<I, Y, R, S> {
+    #[inline]
+    fn from(co: corosensei::Coroutine<I, Y, R, S>) -> Self {
+        FromCorosensei(co)
+    }
+}
+
+impl<I, Y, R, S: Stack> fmt::Debug for FromCorosensei<I, Y, R, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FromCorosensei")
+            .field("started", &self.0.started())
+            .field("done", &self.0.done())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FromCorosensei;
+    use crate::{GenIterExchange, GenIterReturn};
+    use corosensei::{Coroutine, Yielder};
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
+    use std::vec;
+    use std::vec::Vec;
+
+    enum Tree {
+        Leaf(u32),
+        Node(Vec<Tree>),
+    }
+
+    fn visit(y: &Yielder<(), u32>, t: &Tree) -> u32 {
+        match *t {
+            Tree::Leaf(n) => {
+                y.suspend(n);
+                1
+            },
+            Tree::Node(ref children) => children.iter().map(|c| visit(y, c)).sum(),
+        }
+    }
+
+    #[test]
+    fn yield_from_nested_calls() {
+        use self::Tree::*;
+        let tree = Node(vec![
+            Leaf(1),
+            Node(vec![Leaf(2), Node(vec![Leaf(3)])]),
+            Node(vec![]),
+            Leaf(4),
+        ]);
+
+        let mut g = GenIterReturn::new(FromCorosensei(Coroutine::new(move |y, ()| visit(y, &tree))));
+        assert_eq!((&mut g).collect::<Vec<_>>(), [1, 2, 3, 4]);
+        // number of leaves
+        assert_eq!(g.return_or_self().ok(), Some(4));
+    }
+
+    #[test]
+    fn resume_args() {
+        fn add_all(y: &Yielder<u32, u32>, mut x: u32, sum: &mut u32) {
+            while x != 0 {
+                *sum += x;
+                x = y.suspend(*sum);
+            }
+        }
+
+        let mut g = GenIterExchange::new(FromCorosensei(Coroutine::new(|y, first| {
+            let mut sum = 0;
+            add_all(y, first, &mut sum);
+            sum
+        })));
+
+        assert_eq!(g.next_with(1), Some(1));
+        assert_eq!(g.next_with(2), Some(3));
+        assert_eq!(g.next_with(3), Some(6));
+        assert_eq!(g.next_with(0), None);
+        assert_eq!(g.return_or_self().ok(), Some(6));
+    }
+
+    #[test]
+    fn panic_propagates() {
+        fn deep(y: &Yielder<(), u32>, n: u32) {
+            if n == 0 {
+                panic!("boom");
+            }
+            y.suspend(n);
+            deep(y, n - 1);
+        }
+
+        let mut g = GenIterReturn::new(FromCorosensei(Coroutine::new(|y, ()| deep(y, 2))));
+        assert_eq!((&mut g).next(), Some(2));
+        assert_eq!((&mut g).next(), Some(1));
+
+        let err = panic::catch_unwind(AssertUnwindSafe(|| (&mut g).next())).unwrap_err();
+        assert_eq!(err.downcast_ref::<&str>(), Some(&"boom"));
+        assert!(!g.is_done());
+        // the coroutine is finished, resuming it again panics too
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| (&mut g).next())).is_err());
+    }
+
+    #[test]
+    fn drop_unwinds_stack() {
+        let rc = Rc::new(());
+        let held = rc.clone();
+        let mut g = GenIterReturn::new(FromCorosensei(Coroutine::new(move |y: &Yielder<(), ()>, ()| {
+            let _held = held;
+            y.suspend(());
+        })));
+
+        assert_eq!((&mut g).next(), Some(()));
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(g);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
@@ -0,0 +1,171 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::fmt;
+
+use either::Either;
+
+use crate::GenIter;
+
+/// `FromEither<L, R>` is a [`Coroutine`] running either of two generators
+/// with the same resume argument, yield and return types.
+///
+/// Two generator closures always have different types, so a function
+/// returning one of them from each branch of an `if` cannot name
+/// its return type. Wrapping both in `FromEither` unifies them,
+/// [`GenIter::left`] and [`GenIter::right`] do that for a [`GenIter`].
+///
+/// `either::Either` itself cannot implement `Coroutine`, both are foreign
+/// to this crate, but `Either<GenIter<L>, GenIter<R>>` is already an iterator.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter;
+///
+/// fn numbers(odd: bool) -> impl Iterator<Item = u32> {
+///     if odd {
+///         gen_iter!({
+///             yield 1;
+///             yield 3;
+///         }).left()
+///     } else {
+///         gen_iter!({
+///             for i in 0..3 {
+///                 yield i * 2;
+///             }
+///         }).right()
+///     }
+/// }
+///
+/// assert_eq!(numbers(true).collect::<Vec<_>>(), [1, 3]);
+/// assert_eq!(numbers(false).collect::<Vec<_>>(), [0, 2, 4]);
+/// ```
+pub struct FromEither<L, R>(pub Either<L, R>);
+
+impl<L, R> FromEither<L, R> {
+    /// get back the `Either`
+    #[inline]
+    pub fn into_inner(self) -> Either<L, R> {
+        self.0
+    }
+}
+
+impl<A, L, R> Coroutine<A> for FromEither<L, R>
+where
+    L: Coroutine<A> + Unpin,
+    R: Coroutine<A, Yield = L::Yield, Return = L::Return> + Unpin,
+{
+    type Yield = L::Yield;
+    type Return = L::Return;
+
+    #[inline]
+    fn resume(self: Pin<&mut Self>, arg: A) -> CoroutineState<L::Yield, L::Return> {
+        match self.get_mut().0 {
+            Either::Left(ref mut l) => Pin::new(l).resume(arg),
+            Either::Right(ref mut r) => Pin::new(r).resume(arg),
+        }
+    }
+}
+
+impl<L, R> From<Either<L, R>> for FromEither<L, R> {
+    #[inline]
+    fn from(e: Either<L, R>) -> Self {
+        FromEither(e)
+    }
+}
+
+impl<L: fmt::Debug, R: fmt::Debug> fmt::Debug for FromEither<L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("FromEither").field(&self.0).finish()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// this generator as the left side of a [`FromEither`]
+    #[inline]
+    pub fn left<R>(self) -> GenIter<FromEither<G, R>>
+    where
+        R: Coroutine<Yield = G::Yield, Return = ()> + Unpin,
+    {
+        GenIter(FromEither(Either::Left(self.0)))
+    }
+
+    /// this generator as the right side of a [`FromEither`]
+    #[inline]
+    pub fn right<L>(self) -> GenIter<FromEither<L, G>>
+    where
+        L: Coroutine<Yield = G::Yield, Return = ()> + Unpin,
+    {
+        GenIter(FromEither(Either::Right(self.0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FromEither;
+    use crate::{GenIter, GenIterExchange};
+    use either::Either;
+    use std::vec::Vec;
+
+    fn countdown(from: u32, squares: bool) -> GenIter<impl core::ops::Coroutine<Yield = u32, Return = ()> + Unpin> {
+        if squares {
+            GenIter(#[coroutine] move || {
+                for i in (0..from).rev() {
+                    yield i * i;
+                }
+            }).left()
+        } else {
+            GenIter(#[coroutine] move || {
+                let mut i = from;
+                while i > 0 {
+                    i -= 1;
+                    yield i;
+                }
+            }).right()
+        }
+    }
+
+    #[test]
+    fn runtime_branch() {
+        for squares in [true, false] {
+            let expected = (0..4).rev().map(|i| if squares { i * i } else { i }).collect::<Vec<_>>();
+            assert_eq!(countdown(4, squares).collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn return_and_resume_args() {
+        let pick = |double: bool| {
+            let e = if double {
+                Either::Left(#[coroutine] |mut x: u32| {
+                    while x != 0 {
+                        x = yield x * 2;
+                    }
+                    "doubled"
+                })
+            } else {
+                Either::Right(#[coroutine] |mut x: u32| {
+                    while x != 0 {
+                        x = yield x + 1;
+                    }
+                    "incremented"
+                })
+            };
+            GenIterExchange::new(FromEither::from(e))
+        };
+
+        let mut g = pick(true);
+        assert_eq!(g.next_with(3), Some(6));
+        assert_eq!(g.next_with(0), None);
+        assert_eq!(g.return_or_self().ok(), Some("doubled"));
+
+        let mut g = pick(false);
+        assert_eq!(g.next_with(3), Some(4));
+        assert_eq!(g.next_with(0), None);
+        assert_eq!(g.return_or_self().ok(), Some("incremented"));
+    }
+}
@@ -0,0 +1,138 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::fmt;
+
+use crate::GenIter;
+
+/// `FnCoroutine<F>` is a generator yielding what a closure returns, until it returns `None`,
+/// to use a `FnMut() -> Option<T>` where the crate expects a `Coroutine`.
+///
+/// created by [`from_fn`], or [`FnCoroutine::new`] for the generator alone.
+///
+/// The generator completes when the closure returns `None`, the closure is then
+/// dropped and never called again: resuming it again completes it again.
+pub struct FnCoroutine<F> {
+    // `None` once the generator completed
+    f: Option<F>,
+}
+
+impl<F> FnCoroutine<F> {
+    #[inline]
+    pub fn new(f: F) -> Self {
+        FnCoroutine { f: Some(f) }
+    }
+}
+
+// the closure is never pinned
+impl<F> Unpin for FnCoroutine<F> {}
+
+impl<F, T> Coroutine for FnCoroutine<F>
+where
+    F: FnMut() -> Option<T>,
+{
+    type Yield = T;
+    type Return = ();
+
+    fn resume(self: Pin<&mut Self>, _: ()) -> CoroutineState<T, ()> {
+        let this = self.get_mut();
+        match this.f.as_mut().and_then(|f| f()) {
+            Some(y) => CoroutineState::Yielded(y),
+            None => {
+                this.f = None;
+                CoroutineState::Complete(())
+            },
+        }
+    }
+}
+
+impl<F> fmt::Debug for FnCoroutine<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FnCoroutine")
+            .field("done", &self.f.is_none())
+            .finish_non_exhaustive()
+    }
+}
+
+/// an iterator over a [`FnCoroutine`] calling `f`, like `core::iter::from_fn`
+///
+/// ```
+/// #![feature(coroutines)]
+///
+/// use gen_iter::from_fn;
+///
+/// let mut n = 0;
+/// let it = from_fn(|| {
+///     n += 1;
+///     if n <= 3 { Some(n) } else { None }
+/// });
+/// assert_eq!(it.collect::<Vec<_>>(), [1, 2, 3]);
+/// ```
+#[inline]
+pub fn from_fn<T, F>(f: F) -> GenIter<FnCoroutine<F>>
+where
+    F: FnMut() -> Option<T>,
+{
+    GenIter(FnCoroutine::new(f))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_fn, FnCoroutine};
+    use crate::GenIterReturn;
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    #[test]
+    fn not_called_after_none() {
+        let calls = Cell::new(0);
+        let mut it = from_fn(|| {
+            calls.set(calls.get() + 1);
+            if calls.get() <= 2 { Some(calls.get()) } else { None }
+        });
+        assert_eq!(it.by_ref().collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(calls.get(), 3);
+
+        // resuming a completed `FnCoroutine` completes it again
+        assert_eq!(it.next(), None);
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn as_coroutine() {
+        let mut chars = "ab".chars();
+        let mut g = GenIterReturn::new(FnCoroutine::new(move || chars.next()));
+        assert_eq!((&mut g).collect::<Vec<_>>(), ['a', 'b']);
+        assert_eq!(g.return_or_self().ok(), Some(()));
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod alloc_tests {
+    use super::FnCoroutine;
+    use crate::GenIter;
+    use alloc::boxed::Box;
+    use core::ops::Coroutine;
+    use core::pin::Pin;
+    use std::vec;
+    use std::vec::Vec;
+
+    type BoxedGenIter<'a> = GenIter<Pin<Box<dyn Coroutine<Yield = u32, Return = ()> + 'a>>>;
+
+    #[test]
+    fn mixed_with_generators() {
+        let mut n = 0;
+        let gens: Vec<BoxedGenIter> = vec![
+            GenIter(Box::pin(FnCoroutine::new(move || {
+                n += 1;
+                if n <= 2 { Some(n) } else { None }
+            }))),
+            GenIter(Box::pin(#[coroutine] || {
+                yield 10;
+                yield 20;
+            })),
+        ];
+        let all = gens.into_iter().flatten().collect::<Vec<_>>();
+        assert_eq!(all, [1, 2, 10, 20]);
+    }
+}
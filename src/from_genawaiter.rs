@@ -0,0 +1,292 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::future::Future;
+use core::marker::Unpin;
+use core::pin::Pin;
+
+use genawaiter::GeneratorState;
+use genawaiter::{rc, sync};
+
+use crate::{GenIterExchange, GenIterReturn};
+
+/// `FromGenawaiter<C>` turns a `genawaiter` generator, like `genawaiter::rc::Gen`
+/// or `genawaiter::sync::Gen`, into a [`Coroutine`], so it can be driven by
+/// [`GenIterReturn`], [`GenIterExchange`] and the other wrappers of this crate.
+///
+/// The two crates don't agree on the first resume argument:
+/// a `Coroutine` gets it as the parameter of its closure, while a `genawaiter`
+/// generator starts before its first `yield_` and drops it. So the argument
+/// of the first `resume` is lost, the producer only sees the later ones,
+/// as the values of `co.yield_(..).await`.
+///
+/// A `genawaiter` generator panics when resumed after it completed,
+/// [`GenIterReturn`] and [`GenIterExchange`] never do that.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// extern crate genawaiter;
+/// extern crate gen_iter;
+///
+/// use gen_iter::{FromGenawaiter, GenIterReturn};
+///
+/// let gen = genawaiter::rc::Gen::new(|co| async move {
+///     co.yield_(1).await;
+///     co.yield_(2).await;
+///     "done"
+/// });
+///
+/// let mut g = GenIterReturn::new(FromGenawaiter(gen));
+/// assert_eq!((&mut g).collect::<Vec<_>>(), [1, 2]);
+/// assert_eq!(g.return_or_self().ok(), Some("done"));
+/// ```
+#[derive(Debug)]
+pub struct FromGenawaiter<C>(pub C)
+where
+    C: genawaiter::Coroutine + Unpin;
+
+impl<C> Coroutine<C::Resume> for FromGenawaiter<C>
+where
+    C: genawaiter::Coroutine + Unpin,
+{
+    type Yield = C::Yield;
+    type Return = C::Return;
+
+    #[inline]
+    fn resume(self: Pin<&mut Self>, arg: C::Resume) -> CoroutineState<C::Yield, C::Return> {
+        match Pin::new(&mut self.get_mut().0).resume_with(arg) {
+            GeneratorState::Yielded(y) => CoroutineState::Yielded(y),
+            GeneratorState::Complete(r) => CoroutineState::Complete(r),
+        }
+    }
+}
+
+impl<C> From<C> for FromGenawaiter<C>
+where
+    C: genawaiter::Coroutine + Unpin,
+{
+    #[inline]
+    fn from(c: C) -> Self {
+        FromGenawaiter(c)
+    }
+}
+
+/// an `async fn` resuming the generator until it completes, and passing
+/// its yields to `co`: `rc::Co` and `sync::Co` share no trait for `yield_`
+macro_rules! drive {
+    ($name:ident, $co:ty) => {
+        async fn $name<G, R>(g: Result<G::Return, G>, first: R, co: $co) -> G::Return
+        where
+            G: Coroutine<R> + Unpin,
+        {
+            let mut g = match g {
+                Ok(r) => return r,
+                Err(g) => g,
+            };
+            let mut arg = first;
+            loop {
+                match Pin::new(&mut g).resume(arg) {
+                    CoroutineState::Yielded(y) => arg = co.yield_(y).await,
+                    CoroutineState::Complete(r) => return r,
+                }
+            }
+        }
+    };
+}
+
+drive!(drive_rc, rc::Co<G::Yield, R>);
+drive!(drive_sync, sync::Co<G::Yield, R>);
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// turn the generator into a `genawaiter::rc::Gen` with the same
+    /// yields and return value.
+    ///
+    /// if the generator is already done, the `genawaiter` generator
+    /// completes with the return value on its first resume.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// extern crate genawaiter;
+    /// extern crate gen_iter;
+    ///
+    /// use genawaiter::GeneratorState;
+    /// use gen_iter::gen_iter_return;
+    ///
+    /// let mut gen = gen_iter_return!({
+    ///     yield 1;
+    ///     return "done";
+    /// }).into_genawaiter();
+    ///
+    /// assert_eq!(gen.resume(), GeneratorState::Yielded(1));
+    /// assert_eq!(gen.resume(), GeneratorState::Complete("done"));
+    /// ```
+    #[inline]
+    pub fn into_genawaiter(self) -> rc::Gen<G::Yield, (), impl Future<Output = G::Return>> {
+        rc::Gen::new(|co| drive_rc(self.into_result(), (), co))
+    }
+
+    /// the `genawaiter::sync::Gen` counterpart of
+    /// [`into_genawaiter`](GenIterReturn::into_genawaiter)
+    #[inline]
+    pub fn into_genawaiter_sync(self) -> sync::Gen<G::Yield, (), impl Future<Output = G::Return> + Send>
+    where
+        G: Send,
+        G::Yield: Send,
+        G::Return: Send,
+    {
+        sync::Gen::new(|co| drive_sync(self.into_result(), (), co))
+    }
+}
+
+impl<G: Coroutine<R> + Unpin, R> GenIterExchange<G, R> {
+    /// turn the generator into a `genawaiter::rc::Gen` taking the same
+    /// resume arguments.
+    ///
+    /// `genawaiter` drops the argument of the first resume, so the
+    /// first argument of the generator is given here instead.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// extern crate genawaiter;
+    /// extern crate gen_iter;
+    ///
+    /// use genawaiter::GeneratorState;
+    /// use gen_iter::gen_exchange;
+    ///
+    /// let mut gen = gen_exchange!(|mut x: u32| {
+    ///     while x != 0 {
+    ///         x = yield x * 10;
+    ///     }
+    ///     "done"
+    /// }).into_genawaiter(1);
+    ///
+    /// assert_eq!(gen.resume_with(99), GeneratorState::Yielded(10)); // 99 is dropped
+    /// assert_eq!(gen.resume_with(2), GeneratorState::Yielded(20));
+    /// assert_eq!(gen.resume_with(0), GeneratorState::Complete("done"));
+    /// ```
+    #[inline]
+    pub fn into_genawaiter(self, first: R) -> rc::Gen<G::Yield, R, impl Future<Output = G::Return>> {
+        rc::Gen::new(|co| drive_rc(self.into_result(), first, co))
+    }
+
+    /// the `genawaiter::sync::Gen` counterpart of
+    /// [`into_genawaiter`](GenIterExchange::into_genawaiter)
+    #[inline]
+    pub fn into_genawaiter_sync(self, first: R) -> sync::Gen<G::Yield, R, impl Future<Output = G::Return> + Send>
+    where
+        G: Send,
+        G::Yield: Send,
+        G::Return: Send,
+        R: Send,
+    {
+        sync::Gen::new(|co| drive_sync(self.into_result(), first, co))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FromGenawaiter;
+    use crate::{GenIterExchange, GenIterReturn};
+    use genawaiter::GeneratorState;
+    use genawaiter::{rc, sync};
+    use std::thread;
+    use std::vec::Vec;
+
+    #[test]
+    fn from_rc() {
+        let gen = rc::Gen::new(|co| async move {
+            for i in 0..3 {
+                co.yield_(i).await;
+            }
+            "done"
+        });
+
+        let mut g = GenIterReturn::new(FromGenawaiter(gen));
+        assert_eq!((&mut g).collect::<Vec<_>>(), [0, 1, 2]);
+        assert!(g.is_done());
+        // not resumed again
+        assert_eq!((&mut g).next(), None);
+        assert_eq!(g.return_or_self().ok(), Some("done"));
+    }
+
+    #[test]
+    fn from_sync_with_args() {
+        let gen = sync::Gen::new(|co| async move {
+            let mut sum = 0;
+            loop {
+                let x: u32 = co.yield_(sum).await;
+                if x == 0 {
+                    return sum;
+                }
+                sum += x;
+            }
+        });
+
+        let mut g = GenIterExchange::new(FromGenawaiter(gen));
+        // the first argument is dropped by genawaiter
+        assert_eq!(g.next_with(100), Some(0));
+        assert_eq!(g.next_with(1), Some(1));
+        assert_eq!(g.next_with(2), Some(3));
+        assert_eq!(g.next_with(0), None);
+        assert_eq!(g.return_or_self().ok(), Some(3));
+    }
+
+    #[test]
+    fn round_trip() {
+        let g = GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            yield 2;
+            "done"
+        });
+
+        // gen_iter -> genawaiter -> gen_iter
+        let mut g = GenIterReturn::new(FromGenawaiter(g.into_genawaiter()));
+        assert_eq!((&mut g).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(g.return_or_self().ok(), Some("done"));
+
+        // genawaiter -> gen_iter -> genawaiter
+        let gen = rc::Gen::new(|co| async move {
+            co.yield_('a').await;
+            co.yield_('b').await;
+            7
+        });
+        let mut gen = GenIterReturn::new(FromGenawaiter(gen)).into_genawaiter();
+        assert_eq!(gen.resume(), GeneratorState::Yielded('a'));
+        assert_eq!(gen.resume(), GeneratorState::Yielded('b'));
+        assert_eq!(gen.resume(), GeneratorState::Complete(7));
+    }
+
+    #[test]
+    fn exchange_round_trip() {
+        let g = GenIterExchange::new(#[coroutine] |mut x: u32| {
+            while x != 0 {
+                x = yield x + 1;
+            }
+            "done"
+        });
+
+        let gen = g.into_genawaiter_sync(1);
+        let mut g = thread::spawn(move || {
+            let mut g = GenIterExchange::new(FromGenawaiter(gen));
+            // the argument of the first resume is dropped, `1` is used instead
+            assert_eq!(g.next_with(99), Some(2));
+            g
+        }).join().unwrap();
+        assert_eq!(g.next_with(41), Some(42));
+        assert_eq!(g.next_with(0), None);
+        assert_eq!(g.return_or_self().ok(), Some("done"));
+    }
+
+    #[test]
+    fn done_before_conversion() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield ();
+            5
+        });
+        assert_eq!((&mut g).count(), 1);
+
+        let mut gen = g.into_genawaiter();
+        assert_eq!(gen.resume(), GeneratorState::Complete(5));
+    }
+}
@@ -0,0 +1,156 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::fmt;
+
+use crate::GenIter;
+
+/// `GenCycle<G>` repeats the yields of a generator forever,
+/// like `Iterator::cycle` for generators.
+///
+/// created by [`GenIter::cycle_gen`], with the `coroutine_clone` feature.
+///
+/// It keeps a clone of the generator as it was when `cycle_gen` was called,
+/// and clones it again whenever the running copy completes. A generator is only
+/// `Clone` with the nightly `#![feature(coroutine_clone)]` in the crate defining it,
+/// and if everything it keeps across its yields is `Clone`.
+///
+/// A generator completing without yielding anything would loop forever,
+/// so the iteration ends if a whole pass yields nothing.
+///
+/// ```
+/// #![feature(coroutines, coroutine_clone, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter;
+///
+/// let it = gen_iter!({
+///     yield 'a';
+///     yield 'b';
+/// }).cycle_gen();
+///
+/// assert_eq!(it.take(5).collect::<String>(), "ababa");
+/// ```
+pub struct GenCycle<G> {
+    orig: G,
+    gen: G,
+    // whether the running copy yielded, `None` once the iteration ended
+    yielded: Option<bool>,
+}
+
+impl<G, T> Iterator for GenCycle<G>
+where
+    G: Coroutine<Yield = T, Return = ()> + Unpin + Clone,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            let yielded = self.yielded?;
+            match Pin::new(&mut self.gen).resume(()) {
+                CoroutineState::Yielded(y) => {
+                    self.yielded = Some(true);
+                    return Some(y);
+                },
+                CoroutineState::Complete(()) if yielded => {
+                    self.gen = self.orig.clone();
+                    self.yielded = Some(false);
+                },
+                CoroutineState::Complete(()) => {
+                    self.yielded = None;
+                    return None;
+                },
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.yielded {
+            Some(_) => (0, None),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<G, T> FusedIterator for GenCycle<G>
+where
+    G: Coroutine<Yield = T, Return = ()> + Unpin + Clone,
+{}
+
+impl<G: fmt::Debug> fmt::Debug for GenCycle<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GenCycle")
+            .field("orig", &self.orig)
+            .field("gen", &self.gen)
+            .field("ended", &self.yielded.is_none())
+            .finish()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin + Clone,
+{
+    /// repeat the yields of this generator forever, see [`GenCycle`]
+    #[inline]
+    pub fn cycle_gen(self) -> GenCycle<G> {
+        GenCycle { orig: self.0.clone(), gen: self.0, yielded: Some(false) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIter;
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    #[test]
+    fn ten_of_three() {
+        let it = GenIter(#[coroutine] || {
+            for i in 0..3 {
+                yield i;
+            }
+        }).cycle_gen();
+        assert_eq!(it.take(10).collect::<Vec<_>>(), [0, 1, 2, 0, 1, 2, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn from_current_state() {
+        let mut g = GenIter(#[coroutine] || {
+            for i in 0..3 {
+                yield i;
+            }
+        });
+        assert_eq!(g.next(), Some(0));
+        // the clone starts where `g` was
+        let it = g.cycle_gen();
+        assert_eq!(it.take(5).collect::<Vec<_>>(), [1, 2, 1, 2, 1]);
+    }
+
+    #[test]
+    fn empty() {
+        let passes = Cell::new(0);
+        let mut it = GenIter(#[coroutine] || {
+            passes.set(passes.get() + 1);
+            if false {
+                yield 0;
+            }
+        }).cycle_gen();
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next(), None);
+        assert_eq!(passes.get(), 1);
+
+        // a later pass yielding nothing ends it too
+        let passes = Cell::new(0);
+        let it = GenIter(#[coroutine] || {
+            let pass = passes.get();
+            passes.set(pass + 1);
+            if pass < 2 {
+                yield pass;
+            }
+        }).cycle_gen();
+        assert_eq!(it.collect::<Vec<_>>(), [0, 1]);
+        assert_eq!(passes.get(), 3);
+    }
+}
@@ -0,0 +1,202 @@
+use core::ops::{Coroutine, Deref};
+use core::marker::Unpin;
+use core::{fmt, str};
+
+use crate::GenSink;
+
+/// a piece of text of at most [`FmtChunk::CAPACITY`] bytes,
+/// the resume argument of the generator behind a [`GenFmtWrite`]
+#[derive(Copy, Clone)]
+pub struct FmtChunk {
+    buf: [u8; FmtChunk::CAPACITY],
+    len: u8,
+}
+
+impl FmtChunk {
+    pub const CAPACITY: usize = 64;
+
+    /// copy the longest prefix of `s` that fits, returns the chunk and the rest of `s`
+    fn split(s: &str) -> (FmtChunk, &str) {
+        let mut len = s.len().min(FmtChunk::CAPACITY);
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+
+        let mut buf = [0; FmtChunk::CAPACITY];
+        buf[..len].copy_from_slice(&s.as_bytes()[..len]);
+        (FmtChunk { buf, len: len as u8 }, &s[len..])
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // `buf[..len]` is always copied from a `str` at a char boundary
+        unsafe { str::from_utf8_unchecked(&self.buf[..self.len as usize]) }
+    }
+}
+
+impl Deref for FmtChunk {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for FmtChunk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("FmtChunk").field(&self.as_str()).finish()
+    }
+}
+
+/// `GenFmtWrite<G>` implements [`fmt::Write`] with a consuming generator,
+/// so `write!` can feed it without any allocation.
+///
+/// `write_str` cannot hand out its borrowed `&str` as a resume argument,
+/// so the text is copied into [`FmtChunk`]s, and the generator is resumed
+/// with `Some(chunk)` once per chunk. Like [`GenSink`], the generator yields `()`
+/// when it is ready for the next chunk, and [`finish`](GenFmtWrite::finish)
+/// resumes it with `None` until it completes.
+///
+/// Writing after the generator completed returns [`fmt::Error`].
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{FmtChunk, GenFmtWrite};
+/// use std::fmt::Write;
+///
+/// let mut w = GenFmtWrite::new(#[coroutine] |mut chunk: Option<FmtChunk>| {
+///     let mut bytes = 0;
+///     while let Some(c) = chunk {
+///         bytes += c.len();
+///         chunk = yield;
+///     }
+///     bytes
+/// });
+///
+/// write!(w, "{} + {} = {}", 1, 2, 1 + 2).unwrap();
+/// assert_eq!(w.finish(), 9);
+/// ```
+pub struct GenFmtWrite<G>(GenSink<G, FmtChunk>)
+where
+    G: Coroutine<Option<FmtChunk>, Yield = ()> + Unpin;
+
+impl<G> GenFmtWrite<G>
+where
+    G: Coroutine<Option<FmtChunk>, Yield = ()> + Unpin,
+{
+    #[inline]
+    pub fn new(g: G) -> Self {
+        GenFmtWrite(GenSink::new(g))
+    }
+
+    /// whether the generator is complete and refuses further text
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.0.is_complete()
+    }
+
+    /// signal the end of the text, and get the return value of the generator
+    #[inline]
+    pub fn finish(self) -> G::Return {
+        self.0.finish()
+    }
+}
+
+impl<G> fmt::Write for GenFmtWrite<G>
+where
+    G: Coroutine<Option<FmtChunk>, Yield = ()> + Unpin,
+{
+    fn write_str(&mut self, mut s: &str) -> fmt::Result {
+        if self.is_complete() {
+            return Err(fmt::Error);
+        }
+        while !s.is_empty() {
+            let (chunk, rest) = FmtChunk::split(s);
+            self.0.push(chunk).map_err(|_| fmt::Error)?;
+            s = rest;
+        }
+        Ok(())
+    }
+}
+
+impl<G> fmt::Debug for GenFmtWrite<G>
+where
+    G: Coroutine<Option<FmtChunk>, Yield = ()> + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("GenFmtWrite").field(&self.0).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FmtChunk, GenFmtWrite};
+    use core::fmt::Write;
+    use std::string::String;
+    use std::vec::Vec;
+
+    #[test]
+    fn write_formatted() {
+        let mut w = GenFmtWrite::new(#[coroutine] |mut chunk: Option<FmtChunk>| {
+            let mut text = String::new();
+            let mut chunks = 0;
+            while let Some(c) = chunk {
+                text.push_str(&c);
+                chunks += 1;
+                chunk = yield;
+            }
+            (text, chunks)
+        });
+
+        write!(w, "x = {:>4}", 42).unwrap();
+        writeln!(w, ", y = {:?}", "é").unwrap();
+        w.write_str("").unwrap();
+
+        let (text, chunks) = w.finish();
+        assert_eq!(text, "x =   42, y = \"é\"\n");
+        assert!(chunks > 0);
+    }
+
+    /// every chunk is at most `CAPACITY` bytes, and multibyte chars are not split
+    #[test]
+    fn long_text() {
+        let mut w = GenFmtWrite::new(#[coroutine] |mut chunk: Option<FmtChunk>| {
+            let mut chunks = Vec::new();
+            while let Some(c) = chunk {
+                chunks.push(String::from(c.as_str()));
+                chunk = yield;
+            }
+            chunks
+        });
+
+        let text = "aé€".repeat(50);
+        w.write_str(&text).unwrap();
+
+        let chunks = w.finish();
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| !c.is_empty() && c.len() <= FmtChunk::CAPACITY));
+        assert_eq!(chunks.concat(), text);
+    }
+
+    #[test]
+    fn write_after_complete() {
+        let mut w = GenFmtWrite::new(#[coroutine] |first: Option<FmtChunk>| {
+            let second = yield;
+            (first.map(|c| c.len()), second.map(|c| c.len()))
+        });
+
+        w.write_str("ab").unwrap();
+        w.write_str("cde").unwrap();
+        assert!(w.is_complete());
+        assert!(write!(w, "{}", 1).is_err());
+        assert_eq!(w.finish(), (Some(2), Some(3)));
+
+        // completes in the middle of a long `str`
+        let mut w = GenFmtWrite::new(#[coroutine] |_: Option<FmtChunk>| {});
+        let text = "x".repeat(FmtChunk::CAPACITY + 1);
+        assert!(w.write_str(&text).is_err());
+    }
+}
@@ -0,0 +1,194 @@
+/// macro to write a function returning an iterator over a generator, like a `gen fn`.
+///
+/// `gen_fn!(fn name(params) yields Y { ... })` expands to a function with these
+/// parameters returning a [`GenIter`](crate::GenIter) over the block, a `move`
+/// generator owning the parameters. With `yields Y returns R`, the block gives
+/// a return value of type `R` and the function returns a
+/// [`GenIterReturn`](crate::GenIterReturn).
+///
+/// The elided lifetimes of the parameters and of the `yields` and `returns` types,
+/// `&T` and `'_`, are all given one lifetime, which the returned iterator captures
+/// with the named lifetimes and the type parameters. Only the outermost
+/// references of a type are named: `&[&str]` is fine, `[&str; 2]` is not.
+///
+/// The return type names the `Coroutine` trait, so the crate using it needs the
+/// `coroutine_trait` feature too.
+///
+/// ```
+/// #![feature(coroutines, coroutine_trait, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_fn;
+///
+/// gen_fn!(
+///     /// the words of `text` longer than `min`
+///     fn long_words(text: &str, min: usize) yields &str {
+///         for word in text.split(' ') {
+///             if word.len() > min {
+///                 yield word;
+///             }
+///         }
+///     }
+/// );
+///
+/// gen_fn!(fn count_down(from: u32) yields u32 returns &'static str {
+///     for i in (1..=from).rev() {
+///         yield i;
+///     }
+///     "liftoff"
+/// });
+///
+/// assert_eq!(long_words("a generator as a function", 2).collect::<Vec<_>>(), ["generator", "function"]);
+///
+/// let mut g = count_down(3);
+/// assert_eq!((&mut g).collect::<Vec<_>>(), [3, 2, 1]);
+/// assert_eq!(g.return_or_self().ok(), Some("liftoff"));
+/// ```
+#[macro_export]
+macro_rules! gen_fn {
+    (
+        $(#[$attr: meta])*
+        $vis: vis fn $name: ident $(<$($lt: lifetime),* $(,)? $($ty: ident $(: $bound: path)?),* $(,)?>)?
+        ($($params: tt)*) yields $($rest: tt)+
+    ) => {
+        $crate::gen_fn! { @munch params
+            [
+                [$(#[$attr])* $vis fn $name]
+                [$($($lt,)* $($ty $(: $bound)?,)*)?]
+                [$($($lt,)* $($ty,)*)?]
+            ]
+            [] $($params)* @end yields $($rest)+
+        }
+    };
+
+    // the parameters are done, then the `yields` type
+    (@munch params [$($s: tt)*] [$($done: tt)*] @end yields $($rest: tt)+) => {
+        $crate::gen_fn! { @munch yields [$($s)* [$($done)*]] [] $($rest)+ }
+    };
+    (@munch yields [$($s: tt)*] [$($done: tt)*] returns $($rest: tt)+) => {
+        $crate::gen_fn! { @munch returns [$($s)* [$($done)*]] [] $($rest)+ }
+    };
+    (@munch yields
+        [[$($head: tt)*] [$($gen: tt)*] [$($cap: tt)*] [$($param: tt)*]]
+        [$($y: tt)*] $body: block
+    ) => {
+        $($head)* <'__gen_fn, $($gen)*>($($param)*) -> $crate::GenIter<
+            impl ::core::ops::Coroutine<Yield = $($y)*, Return = ()>
+                + ::core::marker::Unpin
+                + use<'__gen_fn, $($cap)*>
+        > {
+            $crate::GenIter(#[coroutine] move || $body)
+        }
+    };
+    (@munch returns
+        [[$($head: tt)*] [$($gen: tt)*] [$($cap: tt)*] [$($param: tt)*] [$($y: tt)*]]
+        [$($r: tt)*] $body: block
+    ) => {
+        $($head)* <'__gen_fn, $($gen)*>($($param)*) -> $crate::GenIterReturn<
+            impl ::core::ops::Coroutine<Yield = $($y)*, Return = $($r)*>
+                + ::core::marker::Unpin
+                + use<'__gen_fn, $($cap)*>
+        > {
+            $crate::GenIterReturn::new(#[coroutine] move || $body)
+        }
+    };
+
+    // name the elided lifetimes
+    (@munch $stage: ident $s: tt [$($done: tt)*] & '_ $($rest: tt)*) => {
+        $crate::gen_fn! { @munch $stage $s [$($done)* &'__gen_fn] $($rest)* }
+    };
+    (@munch $stage: ident $s: tt [$($done: tt)*] & $lt: lifetime $($rest: tt)*) => {
+        $crate::gen_fn! { @munch $stage $s [$($done)* & $lt] $($rest)* }
+    };
+    (@munch $stage: ident $s: tt [$($done: tt)*] && $($rest: tt)*) => {
+        $crate::gen_fn! { @munch $stage $s [$($done)* &'__gen_fn] & $($rest)* }
+    };
+    (@munch $stage: ident $s: tt [$($done: tt)*] & $($rest: tt)*) => {
+        $crate::gen_fn! { @munch $stage $s [$($done)* &'__gen_fn] $($rest)* }
+    };
+    (@munch $stage: ident $s: tt [$($done: tt)*] '_ $($rest: tt)*) => {
+        $crate::gen_fn! { @munch $stage $s [$($done)* '__gen_fn] $($rest)* }
+    };
+    (@munch $stage: ident $s: tt [$($done: tt)*] $t: tt $($rest: tt)*) => {
+        $crate::gen_fn! { @munch $stage $s [$($done)* $t] $($rest)* }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::String;
+    use std::vec::Vec;
+
+    gen_fn!(fn evens(data: &[u32], skip: &u32) yields &u32 {
+        for x in data {
+            if x % 2 == 0 && x != skip {
+                yield x;
+            }
+        }
+    });
+
+    gen_fn!(fn pairs<'a, T: Clone>(left: &'a [T], right: &[T]) yields (T, T) {
+        for (l, r) in left.iter().zip(right) {
+            yield (l.clone(), r.clone());
+        }
+    });
+
+    gen_fn!(pub(crate) fn count(from: u32, to: u32) yields u32 {
+        for i in from..to {
+            yield i;
+        }
+    });
+
+    gen_fn!(fn split_sum(text: &str) yields &str returns u32 {
+        let mut sum = 0;
+        for word in text.split(',') {
+            match word.trim().parse::<u32>() {
+                Ok(n) => sum += n,
+                Err(_) => yield word,
+            }
+        }
+        sum
+    });
+
+    gen_fn!(fn lengths(words: Vec<String>) yields usize returns usize {
+        let mut total = 0;
+        for word in words {
+            total += word.len();
+            yield word.len();
+        }
+        total
+    });
+
+    #[test]
+    fn borrowed_params() {
+        let data = Vec::from([1, 2, 4, 5, 6]);
+        let skip = 4;
+        assert_eq!(evens(&data, &skip).collect::<Vec<_>>(), [&2, &6]);
+
+        let left = ["a", "b"];
+        let right = Vec::from(["x", "y", "z"]);
+        assert_eq!(pairs(&left, &right).collect::<Vec<_>>(), [("a", "x"), ("b", "y")]);
+    }
+
+    #[test]
+    fn owned_params() {
+        // the iterator outlives the arguments it was made from
+        let it = {
+            let (from, to) = (2, 5);
+            count(from, to)
+        };
+        assert_eq!(it.collect::<Vec<_>>(), [2, 3, 4]);
+
+        let mut g = lengths(Vec::from([String::from("ab"), String::from("cde")]));
+        assert_eq!((&mut g).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(g.return_or_self().ok(), Some(5));
+    }
+
+    #[test]
+    fn returns() {
+        let text = String::from("1, a, 2, b, 3");
+        let mut g = split_sum(&text);
+        assert_eq!((&mut g).collect::<Vec<_>>(), [" a", " b"]);
+        assert!(g.is_done());
+        assert_eq!(g.return_or_self().ok(), Some(6));
+    }
+}
@@ -0,0 +1,165 @@
+use core::ops::Coroutine;
+use core::marker::Unpin;
+
+use std::io::{self, BufRead, Read};
+use std::string::String;
+use std::vec;
+use std::vec::Vec;
+
+use crate::{GenIter, GenIterReturn};
+
+/// a generator reading `r` in chunks of at most `chunk` bytes.
+///
+/// every successful read is yielded as `Ok(bytes)`, reads interrupted by
+/// [`io::ErrorKind::Interrupted`] are retried. any other error is yielded
+/// as `Err`, and the generator completes right after it.
+/// the generator returns the number of bytes it yielded.
+///
+/// # Panics
+/// if `chunk` is `0`.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_from_read;
+///
+/// let mut g = gen_from_read(&b"hello world"[..], 4);
+///
+/// let chunks = (&mut g).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(chunks, [&b"hell"[..], b"o wo", b"rld"]);
+/// assert_eq!(g.return_or_self().ok(), Some(11));
+/// ```
+pub fn gen_from_read<R: Read>(
+    mut r: R,
+    chunk: usize,
+) -> GenIterReturn<impl Coroutine<Yield = io::Result<Vec<u8>>, Return = u64> + Unpin> {
+    assert!(chunk > 0, "chunk size must not be 0");
+    GenIterReturn::new(#[coroutine] move || {
+        let mut total = 0;
+        loop {
+            let mut buf = vec![0; chunk];
+            match r.read(&mut buf) {
+                Ok(0) => return total,
+                Ok(n) => {
+                    buf.truncate(n);
+                    total += n as u64;
+                    yield Ok(buf);
+                },
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+                Err(e) => {
+                    yield Err(e);
+                    return total;
+                },
+            }
+        }
+    })
+}
+
+/// a generator over the lines of `r`, like [`BufRead::lines`].
+///
+/// the lines are yielded without their `\n` or `\r\n`. an error is yielded
+/// as `Err`, and the generator completes right after it.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_lines;
+///
+/// let lines = gen_lines(&b"one\r\ntwo\n\nfour"[..]).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(lines, ["one", "two", "", "four"]);
+/// ```
+pub fn gen_lines<R: BufRead>(mut r: R) -> GenIter<impl Coroutine<Yield = io::Result<String>, Return = ()> + Unpin> {
+    GenIter(#[coroutine] move || {
+        loop {
+            let mut line = String::new();
+            match r.read_line(&mut line) {
+                Ok(0) => return,
+                Ok(_) => {
+                    if line.ends_with('\n') {
+                        line.pop();
+                        if line.ends_with('\r') {
+                            line.pop();
+                        }
+                    }
+                    yield Ok(line);
+                },
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                },
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gen_from_read, gen_lines};
+    use std::io::{self, BufRead, Cursor, Read};
+    use std::string::String;
+    use std::vec::Vec;
+
+    /// reads `data`, then fails once, interrupted reads in between
+    struct Flaky {
+        data: Cursor<Vec<u8>>,
+        reads: usize,
+    }
+
+    impl Read for Flaky {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.reads += 1;
+            if self.reads.is_multiple_of(2) {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            match self.data.read(buf)? {
+                0 => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection lost")),
+                n => Ok(n),
+            }
+        }
+    }
+
+    #[test]
+    fn chunks() {
+        let data = (0..=255u8).cycle().take(1000).collect::<Vec<_>>();
+        let mut g = gen_from_read(Cursor::new(&data), 300);
+
+        let chunks = (&mut g).collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(chunks.iter().map(Vec::len).collect::<Vec<_>>(), [300, 300, 300, 100]);
+        assert_eq!(chunks.concat(), data);
+        assert_eq!(g.return_or_self().ok(), Some(1000));
+    }
+
+    #[test]
+    fn error_partway() {
+        let mut g = gen_from_read(Flaky { data: Cursor::new(b"abcde".to_vec()), reads: 0 }, 2);
+
+        assert_eq!((&mut g).next().unwrap().unwrap(), b"ab");
+        assert_eq!((&mut g).next().unwrap().unwrap(), b"cd");
+        assert_eq!((&mut g).next().unwrap().unwrap(), b"e");
+        assert_eq!((&mut g).next().unwrap().unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        assert!((&mut g).next().is_none());
+        assert_eq!(g.return_or_self().ok(), Some(5));
+
+        let lines = gen_lines(io::BufReader::new(Flaky { data: Cursor::new(b"a\nb".to_vec()), reads: 0 }))
+            .map(|l| l.map_err(|e| e.kind()))
+            .collect::<Vec<_>>();
+        assert_eq!(lines, [Ok(String::from("a")), Err(io::ErrorKind::UnexpectedEof)]);
+    }
+
+    #[test]
+    fn empty_reader() {
+        let mut g = gen_from_read(io::empty(), 16);
+        assert!((&mut g).next().is_none());
+        assert_eq!(g.return_or_self().ok(), Some(0));
+
+        assert_eq!(gen_lines(io::empty()).count(), 0);
+    }
+
+    #[test]
+    fn lines_like_std() {
+        let text = "first\n\r\nthird\r\nlast without newline";
+        let lines = gen_lines(Cursor::new(text)).collect::<io::Result<Vec<_>>>().unwrap();
+        let expected = Cursor::new(text).lines().collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(lines, expected);
+    }
+}
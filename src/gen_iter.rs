@@ -1,4 +1,4 @@
-use core::ops::{Generator, GeneratorState};
+use core::ops::{Coroutine, CoroutineState};
 use core::iter::Iterator;
 use core::marker::Unpin;
 use core::pin::Pin;
@@ -8,26 +8,81 @@ use core::pin::Pin;
 #[derive(Copy, Clone, Debug)]
 pub struct GenIter<T>(pub T)
 where
-    T: Generator<Return = ()> + Unpin;
+    T: Coroutine<Return = ()> + Unpin;
+
+impl<T> GenIter<T>
+where
+    T: Coroutine<Return = ()> + Unpin,
+{
+    /// check at compile time that this iterator can be sent to another thread.
+    ///
+    /// this is a no-op at runtime. it exists so that capturing a `!Send` value
+    /// (like an `Rc`) is reported right where the iterator is built,
+    /// instead of deep inside a later `thread::spawn` call.
+    ///
+    /// the bound is `Send` itself, without a `#[diagnostic::on_unimplemented]` message:
+    /// the attribute can't be put on `Send`, and a helper trait implemented for every
+    /// `T: Send` doesn't get its message shown either, rustc reports the unmet `Send`
+    /// bound of the blanket impl instead. That report already points at the value
+    /// captured by the generator, or held across a `yield`, which is not `Send`.
+    ///
+    /// ```compile_fail
+    /// # #![feature(coroutines, stmt_expr_attributes)]
+    /// # use gen_iter::gen_iter;
+    /// let rc = std::rc::Rc::new(1);
+    /// let g = gen_iter!(move {
+    ///     yield *rc;
+    /// }).assert_send(); // error: `Rc<i32>` cannot be sent between threads safely
+    /// ```
+    #[inline(always)]
+    pub fn assert_send(self) -> Self
+    where
+        Self: Send,
+    {
+        self
+    }
+
+    /// check at compile time that this iterator can be shared between threads.
+    ///
+    /// the `Sync` counterpart of [`assert_send`](GenIter::assert_send), with
+    /// the same error for a value which is not `Sync`.
+    ///
+    /// ```compile_fail
+    /// # #![feature(coroutines, stmt_expr_attributes)]
+    /// # use gen_iter::gen_iter;
+    /// # use std::cell::Cell;
+    /// let cell = Cell::new(1);
+    /// let g = gen_iter!(move {
+    ///     yield cell.get();
+    /// }).assert_sync(); // error: `Cell<i32>` cannot be shared between threads safely
+    /// ```
+    #[inline(always)]
+    pub fn assert_sync(self) -> Self
+    where
+        Self: Sync,
+    {
+        self
+    }
+}
 
 impl<T> Iterator for GenIter<T>
 where
-    T: Generator<Return = ()> + Unpin,
+    T: Coroutine<Return = ()> + Unpin,
 {
     type Item = T::Yield;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         match Pin::new(&mut self.0).resume(()) {
-            GeneratorState::Yielded(n) => Some(n),
-            GeneratorState::Complete(()) => None,
+            CoroutineState::Yielded(n) => Some(n),
+            CoroutineState::Complete(()) => None,
         }
     }
 }
 
 impl<G> From<G> for GenIter<G>
 where
-    G: Generator<Return = ()> + Unpin,
+    G: Coroutine<Return = ()> + Unpin,
 {
     #[inline]
     fn from(gen: G) -> Self {
@@ -39,7 +94,7 @@ where
 /// macro to simplify iterator - via - generator construction
 ///
 /// ```
-/// #![feature(generators)]
+/// #![feature(coroutines, stmt_expr_attributes)]
 ///
 /// use gen_iter::gen_iter;
 ///
@@ -56,10 +111,43 @@ where
 #[macro_export]
 macro_rules! gen_iter {
     ($block: block) => {
-        $crate::GenIter(|| $block)
+        $crate::GenIter(#[coroutine] || $block)
     };
     (move $block: block) => {
-        $crate::GenIter(move || $block)
+        $crate::GenIter(#[coroutine] move || $block)
+    }
+}
+
+/// macro to build one [`GenIter`] from several generator blocks, run in order
+///
+/// the blocks are the phases of a single generator, as if written in one block,
+/// each with its own scope: a block is only started once the one before completed.
+/// a `return` in any block ends the whole generator.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter_chain;
+///
+/// let g = gen_iter_chain!({
+///     yield "setup";
+/// }, {
+///     for _ in 0..2 {
+///         yield "work";
+///     }
+/// }, {
+///     yield "teardown";
+/// });
+///
+/// assert_eq!(g.collect::<Vec<_>>(), ["setup", "work", "work", "teardown"]);
+/// ```
+#[macro_export]
+macro_rules! gen_iter_chain {
+    ($($block: block),+ $(,)?) => {
+        $crate::GenIter(#[coroutine] || { $($block;)+ })
+    };
+    (move $($block: block),+ $(,)?) => {
+        $crate::GenIter(#[coroutine] move || { $($block;)+ })
     }
 }
 
@@ -82,7 +170,7 @@ mod tests {
 
     #[test]
     fn into_gen_iter() {
-        let mut g: GenIter<_> = (|| {
+        let mut g: GenIter<_> = (#[coroutine] || {
             yield 1;
             yield 2;
         }).into();
@@ -92,6 +180,19 @@ mod tests {
         assert_eq!(g.next(), None);
     }
 
+    #[test]
+    fn assert_send_sync() {
+        let mut g = gen_iter!({
+            for x in 1..3 {
+                yield x;
+            }
+        }).assert_send().assert_sync();
+
+        assert_eq!(g.next(), Some(1));
+        assert_eq!(g.next(), Some(2));
+        assert_eq!(g.next(), None);
+    }
+
     #[test]
     fn gen_iter_macro() {
         let mut g = gen_iter!(move {
@@ -103,4 +204,58 @@ mod tests {
         assert_eq!(g.next(), Some(2));
         assert_eq!(g.next(), None);
     }
+
+    #[test]
+    fn gen_iter_chain_macro() {
+        let g = gen_iter_chain!({
+            yield 1;
+        }, {
+            for x in 2..4 {
+                yield x;
+            }
+        }, {
+            yield 4;
+        });
+        assert_eq!(g.collect::<std::vec::Vec<_>>(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn gen_iter_chain_in_order() {
+        use core::cell::Cell;
+
+        let started = Cell::new(0);
+        let mut g = gen_iter_chain!({
+            started.set(1);
+            yield 1;
+            yield 2;
+        }, {
+            started.set(2);
+            yield 3;
+        }, {
+            started.set(3);
+        });
+
+        assert_eq!(g.next(), Some(1));
+        assert_eq!(g.next(), Some(2));
+        assert_eq!(started.get(), 1);
+        assert_eq!(g.next(), Some(3));
+        assert_eq!(started.get(), 2);
+        assert_eq!(g.next(), None);
+        assert_eq!(started.get(), 3);
+    }
+
+    #[test]
+    fn gen_iter_chain_move() {
+        fn phases(name: std::string::String) -> GenIter<impl core::ops::Coroutine<Yield = usize, Return = ()> + Unpin> {
+            gen_iter_chain!(move {
+                yield name.len();
+            }, {
+                // the blocks share what the generator owns
+                let mut name = name;
+                name.push('!');
+                yield name.len();
+            })
+        }
+        assert_eq!(phases("ab".into()).collect::<std::vec::Vec<_>>(), [2, 3]);
+    }
 }
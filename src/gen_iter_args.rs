@@ -0,0 +1,255 @@
+use core::ops::Coroutine;
+use core::iter::Iterator;
+use core::marker::Unpin;
+use core::fmt;
+
+use crate::GenIterExchange;
+
+// what `GenIterArgs` does when its argument iterator runs dry
+// before the generator is complete
+#[derive(Copy, Clone, Debug)]
+enum Fallback<R> {
+    Stop,
+    Default(fn() -> R),
+    RepeatLast(fn(&R) -> R, Option<R>),
+}
+
+/// `GenIterArgs<G, I>` drives a generator `G` that takes a resume argument,
+/// pulling one argument from the iterator `I` per resume.
+///
+/// `GenIterArgs<G, I>` is an iterator over the yields of `G`,
+/// the return value of `G` can be got like with a [`GenIterReturn`](crate::GenIterReturn).
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::GenIterArgs;
+///
+/// let mut g = GenIterArgs::new(#[coroutine] |mut line: &'static str| {
+///     let mut count = 0;
+///     while line != "quit" {
+///         count += 1;
+///         line = yield line.len();
+///     }
+///     count
+/// }, ["a", "bb", "quit", "ccc"].iter().copied());
+///
+/// assert_eq!(g.by_ref().collect::<Vec<_>>(), [1, 2]);
+/// assert_eq!(g.return_or_self().ok(), Some(2));
+/// ```
+pub struct GenIterArgs<G, I>
+where
+    I: Iterator,
+    G: Coroutine<I::Item> + Unpin,
+{
+    exchange: GenIterExchange<G, I::Item>,
+    args: I,
+    fallback: Fallback<I::Item>,
+}
+
+impl<G, I> GenIterArgs<G, I>
+where
+    I: Iterator,
+    G: Coroutine<I::Item> + Unpin,
+{
+    /// iteration stops when either the generator or `args` is done,
+    /// the generator is left unfinished if `args` runs dry first
+    #[inline]
+    pub fn new(g: G, args: I) -> Self {
+        GenIterArgs::with_fallback(g, args, Fallback::Stop)
+    }
+
+    /// when `args` is exhausted before the generator is complete,
+    /// keep resuming the generator with `I::Item::default()`
+    #[inline]
+    pub fn with_default(g: G, args: I) -> Self
+    where
+        I::Item: Default,
+    {
+        GenIterArgs::with_fallback(g, args, Fallback::Default(I::Item::default))
+    }
+
+    /// when `args` is exhausted before the generator is complete,
+    /// keep resuming the generator with a clone of the last argument,
+    /// stops like [`new`](GenIterArgs::new) if there never was one
+    #[inline]
+    pub fn repeat_last(g: G, args: I) -> Self
+    where
+        I::Item: Clone,
+    {
+        GenIterArgs::with_fallback(g, args, Fallback::RepeatLast(I::Item::clone, None))
+    }
+
+    #[inline]
+    fn with_fallback(g: G, args: I, fallback: Fallback<I::Item>) -> Self {
+        GenIterArgs {
+            exchange: GenIterExchange::new(g),
+            args,
+            fallback,
+        }
+    }
+
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.exchange.is_complete()
+    }
+
+    #[inline]
+    pub fn return_or_self(self) -> Result<G::Return, Self> {
+        let GenIterArgs { exchange, args, fallback } = self;
+        exchange.return_or_self().map_err(|exchange| GenIterArgs { exchange, args, fallback })
+    }
+
+    /// split into the generator and the remaining arguments
+    #[inline]
+    pub fn into_parts(self) -> (GenIterExchange<G, I::Item>, I) {
+        (self.exchange, self.args)
+    }
+
+    fn next_arg(&mut self) -> Option<I::Item> {
+        let arg = self.args.next();
+        match self.fallback {
+            Fallback::Stop => arg,
+            Fallback::Default(default) => Some(arg.unwrap_or_else(default)),
+            Fallback::RepeatLast(clone, ref mut last) => {
+                if let Some(arg) = arg {
+                    *last = Some(clone(&arg));
+                    Some(arg)
+                } else {
+                    last.as_ref().map(clone)
+                }
+            },
+        }
+    }
+}
+
+impl<G, I> Clone for GenIterArgs<G, I>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+    G: Coroutine<I::Item> + Unpin + Clone,
+    G::Return: Clone,
+{
+    fn clone(&self) -> Self {
+        GenIterArgs {
+            exchange: self.exchange.clone(),
+            args: self.args.clone(),
+            fallback: self.fallback.clone(),
+        }
+    }
+}
+
+impl<G, I> fmt::Debug for GenIterArgs<G, I>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+    G: Coroutine<I::Item> + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GenIterArgs")
+            .field("exchange", &self.exchange)
+            .field("args", &self.args)
+            .field("fallback", &self.fallback)
+            .finish()
+    }
+}
+
+impl<G, I> Iterator for GenIterArgs<G, I>
+where
+    I: Iterator,
+    G: Coroutine<I::Item> + Unpin,
+{
+    type Item = G::Yield;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.exchange.is_complete() {
+            return None;
+        }
+
+        let arg = self.next_arg()?;
+        self.exchange.next_with(arg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GenIterArgs;
+    use std::vec::Vec;
+
+    /// yields every argument it receives, returns after `n` resumes
+    macro_rules! take_n {
+        ($n: expr) => {
+            #[coroutine] |mut x: u32| {
+                for _ in 1..$n {
+                    x = yield x;
+                }
+                x
+            }
+        };
+    }
+
+    #[test]
+    fn stop() {
+        let mut g = GenIterArgs::new(take_n!(10), 1..4);
+
+        assert_eq!(g.by_ref().collect::<Vec<_>>(), [1, 2, 3]);
+        assert!(!g.is_complete());
+
+        let (mut exchange, mut args) = match g.return_or_self() {
+            Ok(_) => panic!("generator is done but should not"),
+            Err(g) => g.into_parts(),
+        };
+        assert_eq!(args.next(), None);
+        assert_eq!(exchange.next_with(7), Some(7));
+    }
+
+    #[test]
+    fn default() {
+        let mut g = GenIterArgs::with_default(take_n!(5), 1..3);
+
+        assert_eq!(g.by_ref().collect::<Vec<_>>(), [1, 2, 0, 0]);
+        assert!(g.is_complete());
+        assert_eq!(g.return_or_self().ok(), Some(0));
+    }
+
+    #[test]
+    fn default_without_clone() {
+        #[derive(Default, Debug, PartialEq)]
+        struct Token(u32);
+
+        let mut g = GenIterArgs::with_default(#[coroutine] |mut t: Token| {
+            for _ in 0..2 {
+                t = yield t.0;
+            }
+        }, core::iter::once(Token(4)));
+
+        assert_eq!(g.by_ref().collect::<Vec<_>>(), [4, 0]);
+        assert!(g.is_complete());
+    }
+
+    #[test]
+    fn repeat_last() {
+        let mut g = GenIterArgs::repeat_last(take_n!(5), 1..3);
+
+        assert_eq!(g.by_ref().collect::<Vec<_>>(), [1, 2, 2, 2]);
+        assert_eq!(g.return_or_self().ok(), Some(2));
+
+        let mut g = GenIterArgs::repeat_last(take_n!(5), 1..1);
+        assert_eq!(g.next(), None);
+        assert!(!g.is_complete());
+    }
+
+    #[test]
+    fn generator_finishes_first() {
+        let mut g = GenIterArgs::with_default(take_n!(3), 1..10);
+
+        assert_eq!(g.by_ref().collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(g.next(), None);
+
+        let (exchange, mut args) = g.into_parts();
+        assert_eq!(exchange.return_or_self().ok(), Some(3));
+        assert_eq!(args.next(), Some(4));
+    }
+}
@@ -0,0 +1,417 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::iter::{Iterator, FusedIterator};
+use core::marker::{PhantomData, Unpin};
+use core::pin::Pin;
+use core::fmt;
+
+/// `GenIterExchange<G, R>` holds a generator `G` that takes a resume argument
+/// of type `R`, or the return value of `G` once it is done.
+///
+/// Each call to [`next_with`](GenIterExchange::next_with) resumes the generator
+/// with the given argument.
+///
+/// The first argument is passed to the first resume, before the generator
+/// has yielded anything. In the generator body it is the parameter of the closure;
+/// every later argument is the value of the `yield` expression
+/// that suspended the generator:
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::GenIterExchange;
+///
+/// let mut g = GenIterExchange::new(#[coroutine] |first: i32| {
+///     let second = yield first * 10; // `first` comes from the first `next_with`
+///     let _third = yield second * 10; // `second` from the second one
+///     "done"
+/// });
+///
+/// assert_eq!(g.next_with(1), Some(10));
+/// assert_eq!(g.next_with(2), Some(20));
+/// assert_eq!(g.next_with(3), None);
+/// assert_eq!(g.return_or_self().ok(), Some("done"));
+/// ```
+///
+/// Like `GenIterReturn<G>`, `GenIterExchange<G, R>` is invariant in `G`,
+/// and in `R` too, because it stores `<G as Coroutine<R>>::Return`:
+/// ```compile_fail
+/// # #![feature(coroutine_trait)]
+/// # use gen_iter::GenIterExchange;
+/// # use std::ops::Coroutine;
+/// fn lengthen<'s, 'l: 's, C>(g: GenIterExchange<C, &'s str>) -> GenIterExchange<C, &'l str>
+/// where
+///     C: for<'a> Coroutine<&'a str> + Unpin,
+/// {
+///     g // error: lifetime may not live long enough
+/// }
+/// ```
+pub struct GenIterExchange<G: Coroutine<R> + Unpin, R>(Result<G::Return, G>, PhantomData<fn(R)>);
+
+impl<G: Coroutine<R> + Unpin, R> GenIterExchange<G, R> {
+    #[inline]
+    pub fn new(g: G) -> Self {
+        GenIterExchange(Err(g), PhantomData)
+    }
+
+    /// resume the generator with `arg`.
+    ///
+    /// returns `None` once the generator is complete,
+    /// it is safe to keep calling after that (`arg` is dropped).
+    #[inline]
+    pub fn next_with(&mut self, arg: R) -> Option<G::Yield> {
+        match self.0 {
+            Ok(_) => None,
+            Err(ref mut g) => match Pin::new(g).resume(arg) {
+                CoroutineState::Yielded(y) => Some(y),
+                CoroutineState::Complete(r) => {
+                    self.0 = Ok(r);
+                    None
+                },
+            }
+        }
+    }
+
+    /// resume the generator with `R::default()`,
+    /// same as `(&mut g).next()`
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<G::Yield>
+    where
+        R: Default,
+    {
+        self.next_with(R::default())
+    }
+
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.0.is_ok()
+    }
+
+    #[inline]
+    pub fn return_or_self(self) -> Result<G::Return, Self> {
+        match self.0 {
+            Ok(r) => Ok(r),
+            Err(_) => Err(self),
+        }
+    }
+
+    /// the return value, or the generator if it is not done
+    #[cfg(feature = "genawaiter")]
+    #[inline]
+    pub(crate) fn into_result(self) -> Result<G::Return, G> {
+        self.0
+    }
+
+    /// the return value, if the generator is done
+    #[inline]
+    pub(crate) fn return_ref(&self) -> Option<&G::Return> {
+        self.0.as_ref().ok()
+    }
+
+    /// replace the generator with `f(generator)`, keeping the return value if done
+    #[inline]
+    pub(crate) fn map_inner<H, R2, F>(self, f: F) -> GenIterExchange<H, R2>
+    where
+        H: Coroutine<R2, Return = G::Return> + Unpin,
+        F: FnOnce(G) -> H,
+    {
+        GenIterExchange(self.0.map_err(f), PhantomData)
+    }
+}
+
+// implemented by hand, so that `R` does not need to be `Clone` / `Debug`
+impl<G, R> Clone for GenIterExchange<G, R>
+where
+    G: Coroutine<R> + Unpin + Clone,
+    G::Return: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        GenIterExchange(self.0.clone(), PhantomData)
+    }
+}
+
+impl<G, R> Copy for GenIterExchange<G, R>
+where
+    G: Coroutine<R> + Unpin + Copy,
+    G::Return: Copy,
+{}
+
+// neither the generator nor the return value is ever pinned
+impl<G: Coroutine<R> + Unpin, R> Unpin for GenIterExchange<G, R> {}
+
+impl<G, R> fmt::Debug for GenIterExchange<G, R>
+where
+    G: Coroutine<R> + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("GenIterExchange").field(&self.0).finish()
+    }
+}
+
+/// When `R: Default`, `&mut GenIterExchange<G, R>` is an iterator
+/// that resumes the generator with `R::default()`.
+///
+/// Like with `GenIterReturn<G>`, only `&mut g` is an iterator,
+/// so that the return value can still be got after the loop.
+impl<G: Coroutine<R> + Unpin, R: Default> Iterator for &mut GenIterExchange<G, R> {
+    type Item = G::Yield;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with(R::default())
+    }
+}
+
+impl<G: Coroutine<R> + Unpin, R: Default> FusedIterator for &mut GenIterExchange<G, R> {}
+
+impl<G: Coroutine<R> + Unpin, R> From<G> for GenIterExchange<G, R> {
+    #[inline]
+    fn from(g: G) -> Self {
+        GenIterExchange::new(g)
+    }
+}
+
+/// macro to simplify construction of a [`GenIterExchange`] from a generator closure
+///
+/// the closure parameter receives the first resume argument,
+/// annotate its type to help inference. `move` closures are supported too.
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_exchange;
+///
+/// enum Cmd {
+///     Push(u32),
+///     Pop,
+///     Stop,
+/// }
+///
+/// let mut g = gen_exchange!(|mut cmd: Cmd| {
+///     let mut stack = Vec::new();
+///     loop {
+///         let reply = match cmd {
+///             Cmd::Push(n) => { stack.push(n); None },
+///             Cmd::Pop => stack.pop(),
+///             Cmd::Stop => return stack.len(),
+///         };
+///         cmd = yield reply;
+///     }
+/// });
+///
+/// assert_eq!(g.next_with(Cmd::Push(1)), Some(None));
+/// assert_eq!(g.next_with(Cmd::Push(2)), Some(None));
+/// assert_eq!(g.next_with(Cmd::Pop), Some(Some(2)));
+/// assert_eq!(g.next_with(Cmd::Stop), None);
+/// assert_eq!(g.return_or_self().ok(), Some(1));
+/// ```
+///
+/// like [`gen_iter!`](crate::gen_iter), a block, or `move` and a block, makes the
+/// generator. Each `yield` evaluates to the next argument, `Arg => { ... }` gives
+/// its type. The first argument only starts the block, and is dropped.
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_exchange;
+///
+/// #[derive(Default)]
+/// enum Cmd {
+///     #[default]
+///     Start,
+///     Add(u32),
+///     Stop,
+/// }
+///
+/// let mut g = gen_exchange!(Cmd => {
+///     let mut sum = 0;
+///     loop {
+///         match (yield sum) {
+///             Cmd::Add(n) => sum += n,
+///             Cmd::Start => {},
+///             Cmd::Stop => return sum,
+///         }
+///     }
+/// });
+///
+/// assert_eq!(g.next(), Some(0));
+/// assert_eq!(g.next_with(Cmd::Add(2)), Some(2));
+/// assert_eq!(g.next_with(Cmd::Add(3)), Some(5));
+/// assert_eq!(g.next_with(Cmd::Stop), None);
+/// assert_eq!(g.return_or_self().ok(), Some(5));
+/// ```
+#[macro_export]
+macro_rules! gen_exchange {
+    ($block: block) => {
+        $crate::GenIterExchange::new(#[coroutine] |_| $block)
+    };
+    (move $block: block) => {
+        $crate::GenIterExchange::new(#[coroutine] move |_| $block)
+    };
+    ($arg: ty => $block: block) => {
+        $crate::GenIterExchange::new(#[coroutine] |_: $arg| $block)
+    };
+    (move $arg: ty => $block: block) => {
+        $crate::GenIterExchange::new(#[coroutine] move |_: $arg| $block)
+    };
+    ($($closure: tt)*) => {
+        $crate::GenIterExchange::new(#[coroutine] $($closure)*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GenIterExchange;
+
+    /// echoes every argument doubled, and returns how many it received
+    #[test]
+    fn echo() {
+        let mut g = GenIterExchange::new(#[coroutine] |mut x: i32| {
+            let mut count = 0;
+            while x >= 0 {
+                count += 1;
+                x = yield x * 2;
+            }
+            count
+        });
+
+        assert_eq!(g.next_with(1), Some(2));
+        assert_eq!(g.next_with(5), Some(10));
+        assert_eq!(g.next_with(0), Some(0));
+        assert!(!g.is_complete());
+
+        g = match g.return_or_self() {
+            Ok(_) => panic!("generator is done but should not"),
+            Err(g) => g
+        };
+
+        assert_eq!(g.next_with(-1), None);
+        assert!(g.is_complete());
+        assert_eq!(g.next_with(7), None);
+
+        assert_eq!(g.return_or_self().ok(), Some(3));
+    }
+
+    #[test]
+    fn from_generator() {
+        let mut g = GenIterExchange::from(#[coroutine] |s: &'static str| {
+            yield s.len()
+        });
+
+        assert_eq!(g.next_with("four"), Some(4));
+        assert_eq!(g.next_with("end"), None);
+        assert_eq!(g.return_or_self().ok(), Some("end"));
+    }
+
+    #[test]
+    fn gen_exchange_macro() {
+        let offset = 10;
+        let mut g = gen_exchange!(move |mut x: u32| {
+            while x != 0 {
+                x = yield x + offset;
+            }
+            offset
+        });
+
+        assert_eq!(g.next_with(1), Some(11));
+        assert_eq!(g.next_with(2), Some(12));
+        assert_eq!(g.next_with(0), None);
+        assert_eq!(g.return_or_self().ok(), Some(10));
+
+        let mut g = gen_exchange!(|s| {
+            let t: &str = yield s;
+            t.len()
+        });
+
+        assert_eq!(g.next_with("one"), Some("one"));
+        assert_eq!(g.next_with("three"), None);
+        assert_eq!(g.return_or_self().ok(), Some(5));
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Cmd {
+        Inc,
+        Double,
+        Get,
+        Quit,
+    }
+
+    #[test]
+    fn gen_exchange_block() {
+        let mut g = gen_exchange!(Cmd => {
+            let mut n = 1;
+            let mut cmd = yield None;
+            while cmd != Cmd::Quit {
+                match cmd {
+                    Cmd::Inc => n += 1,
+                    Cmd::Double => n *= 2,
+                    Cmd::Get => {},
+                    Cmd::Quit => unreachable!(),
+                }
+                cmd = yield if cmd == Cmd::Get { Some(n) } else { None };
+            }
+            n
+        });
+
+        // the first argument only starts the generator
+        assert_eq!(g.next_with(Cmd::Quit), Some(None));
+        assert_eq!(g.next_with(Cmd::Inc), Some(None));
+        assert_eq!(g.next_with(Cmd::Double), Some(None));
+        assert_eq!(g.next_with(Cmd::Get), Some(Some(4)));
+        assert_eq!(g.next_with(Cmd::Quit), None);
+        assert!(g.is_complete());
+        assert_eq!(g.return_or_self().ok(), Some(4));
+
+        let step = 3;
+        let mut g = gen_exchange!(move Cmd => {
+            let mut n = 0;
+            while (yield n) != Cmd::Quit {
+                n += step;
+            }
+        });
+        assert_eq!(g.next_with(Cmd::Get), Some(0));
+        assert_eq!(g.next_with(Cmd::Inc), Some(3));
+        assert_eq!(g.next_with(Cmd::Quit), None);
+
+        let mut g = gen_exchange!(move {
+            let s: &str = yield step;
+            s.len() + step
+        });
+        assert_eq!(g.next_with("start"), Some(3));
+        assert_eq!(g.next_with("four"), None);
+        assert_eq!(g.return_or_self().ok(), Some(7));
+
+        let mut g = gen_exchange!({
+            let x: usize = yield ();
+            x * step
+        });
+        assert_eq!(g.next(), Some(()));
+        assert_eq!(g.next_with(5), None);
+        assert_eq!(g.return_or_self().ok(), Some(15));
+    }
+
+    /// counts the default (`None`) and explicit arguments it receives
+    #[test]
+    fn default_args() {
+        let mut g = GenIterExchange::new(#[coroutine] |mut arg: Option<u32>| {
+            let (mut defaults, mut explicit) = (0, 0);
+            for _ in 0..6 {
+                match arg {
+                    Some(_) => explicit += 1,
+                    None => defaults += 1,
+                }
+                arg = yield (defaults, explicit);
+            }
+            (defaults, explicit)
+        });
+
+        assert_eq!(g.next(), Some((1, 0)));
+        assert_eq!(g.next_with(Some(5)), Some((1, 1)));
+        assert_eq!(g.next(), Some((2, 1)));
+        assert_eq!((&mut g).take(2).last(), Some((4, 1)));
+        assert_eq!(g.next_with(Some(1)), Some((4, 2)));
+
+        assert_eq!(g.next(), None);
+        assert_eq!(g.next(), None);
+        assert_eq!(g.return_or_self().ok(), Some((4, 2)));
+    }
+}
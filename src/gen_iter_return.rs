@@ -1,4 +1,4 @@
-use core::ops::{Generator, GeneratorState};
+use core::ops::{Coroutine, CoroutineState};
 use core::iter::{Iterator, FusedIterator};
 use core::marker::Unpin;
 use core::pin::Pin;
@@ -10,10 +10,25 @@ use core::pin::Pin;
 /// 1. able to get return value of a generator
 /// 2. safe to call `next()` after generator is done without panic
 /// 3. maybe less efficient than `GenIter<G>`
+///
+/// Unlike `GenIter<G>`, `GenIterReturn<G>` is invariant in `G`,
+/// because it stores `G::Return`:
+/// ```compile_fail
+/// # #![feature(coroutine_trait)]
+/// # use gen_iter::GenIterReturn;
+/// # use std::ops::Coroutine;
+/// # use std::pin::Pin;
+/// fn shorten<'s, 'l: 's, C>(g: GenIterReturn<Pin<&'l mut C>>) -> GenIterReturn<Pin<&'s mut C>>
+/// where
+///     C: Coroutine + Unpin,
+/// {
+///     g // error: lifetime may not live long enough
+/// }
+/// ```
 #[derive(Copy, Clone, Debug)]
-pub struct GenIterReturn<G: Generator + Unpin>(Result<G::Return, G>);
+pub struct GenIterReturn<G: Coroutine + Unpin>(Result<G::Return, G>);
 
-impl<G: Generator + Unpin> GenIterReturn<G> {
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
     #[inline]
     pub fn new(g: G) -> Self {
         GenIterReturn(Err(g))
@@ -31,19 +46,82 @@ impl<G: Generator + Unpin> GenIterReturn<G> {
             Err(_) => Err(self),
         }
     }
+
+    /// the return value, or the generator if it is not done
+    #[inline]
+    pub(crate) fn into_result(self) -> Result<G::Return, G> {
+        self.0
+    }
+
+    /// a reference to the return value, or to the generator if it is not done
+    #[inline]
+    pub(crate) fn as_result(&self) -> Result<&G::Return, &G> {
+        self.0.as_ref()
+    }
+
+    /// replace the generator with `f(generator)`, keeping the return value if done
+    #[inline]
+    pub(crate) fn map_inner<H, F>(self, f: F) -> GenIterReturn<H>
+    where
+        H: Coroutine<Return = G::Return> + Unpin,
+        F: FnOnce(G) -> H,
+    {
+        GenIterReturn(self.0.map_err(f))
+    }
+
+    /// check at compile time that this value can be sent to another thread,
+    /// see [`GenIter::assert_send`](crate::GenIter::assert_send).
+    ///
+    /// ```compile_fail
+    /// # #![feature(coroutines, stmt_expr_attributes)]
+    /// # use gen_iter::gen_iter_return;
+    /// let rc = std::rc::Rc::new(1);
+    /// let g = gen_iter_return!(move {
+    ///     yield *rc;
+    ///     return "done";
+    /// }).assert_send(); // error: `Rc<i32>` cannot be sent between threads safely
+    /// ```
+    #[inline(always)]
+    pub fn assert_send(self) -> Self
+    where
+        Self: Send,
+    {
+        self
+    }
+
+    /// check at compile time that this value can be shared between threads,
+    /// see [`GenIter::assert_sync`](crate::GenIter::assert_sync).
+    ///
+    /// ```compile_fail
+    /// # #![feature(coroutines, stmt_expr_attributes)]
+    /// # use gen_iter::gen_iter_return;
+    /// # use std::cell::Cell;
+    /// let cell = Cell::new(1);
+    /// let g = gen_iter_return!(move {
+    ///     yield cell.get();
+    ///     return "done";
+    /// }).assert_sync(); // error: `Cell<i32>` cannot be shared between threads safely
+    /// ```
+    #[inline(always)]
+    pub fn assert_sync(self) -> Self
+    where
+        Self: Sync,
+    {
+        self
+    }
 }
 
 /// Force use `&mut g` as iterator to prevent the code below,
 /// in which return value cannot be got.
 /// ```compile_fail
 /// // !!INVALID CODE!!
-/// # #![feature(generators)]
+/// # #![feature(coroutines, stmt_expr_attributes)]
 /// # use gen_iter::gen_iter_return;
 /// let mut g = gen_iter_return!({ yield 1; return "done"; });
 /// for v in g {} // invalid, because `GenIterReturn<G>` is not `Iterator`
 /// let ret = g.return_or_self(); // g is dropped after for loop
 /// ```
-impl<G: Generator + Unpin> Iterator for &mut GenIterReturn<G> {
+impl<G: Coroutine + Unpin> Iterator for &mut GenIterReturn<G> {
     type Item = G::Yield;
 
     #[inline]
@@ -51,8 +129,8 @@ impl<G: Generator + Unpin> Iterator for &mut GenIterReturn<G> {
         match self.0 {
             Ok(_) => None,
             Err(ref mut g) => match Pin::new(g).resume(()) {
-                GeneratorState::Yielded(y) => Some(y),
-                GeneratorState::Complete(r) => {
+                CoroutineState::Yielded(y) => Some(y),
+                CoroutineState::Complete(r) => {
                     self.0 = Ok(r);
                     None
                 },
@@ -62,9 +140,9 @@ impl<G: Generator + Unpin> Iterator for &mut GenIterReturn<G> {
 }
 
 /// `GenIterReturn<G>` satisfies the trait `FusedIterator`
-impl<G: Generator + Unpin> FusedIterator for &mut GenIterReturn<G> {}
+impl<G: Coroutine + Unpin> FusedIterator for &mut GenIterReturn<G> {}
 
-impl<G: Generator + Unpin> From<G> for GenIterReturn<G> {
+impl<G: Coroutine + Unpin> From<G> for GenIterReturn<G> {
     #[inline]
     fn from(g: G) -> Self {
         GenIterReturn::new(g)
@@ -73,7 +151,7 @@ impl<G: Generator + Unpin> From<G> for GenIterReturn<G> {
 
 /// macro to simplify iterator - via - generator with return value construction
 /// ```
-/// #![feature(generators)]
+/// #![feature(coroutines, stmt_expr_attributes)]
 ///
 /// use gen_iter::gen_iter_return;
 ///
@@ -91,14 +169,15 @@ impl<G: Generator + Unpin> From<G> for GenIterReturn<G> {
 #[macro_export]
 macro_rules! gen_iter_return {
     ($block: block) => {
-        $crate::GenIterReturn::new(|| $block)
+        $crate::GenIterReturn::new(#[coroutine] || $block)
     };
     (move $block: block) => {
-        $crate::GenIterReturn::new(move || $block)
+        $crate::GenIterReturn::new(#[coroutine] move || $block)
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::needless_return)]
 mod tests {
     use super::GenIterReturn;
 
@@ -106,7 +185,7 @@ mod tests {
     /// and show that it won't panic when call `next()` even exhausted.
     #[test]
     fn it_works() {
-        let mut g = GenIterReturn::new(|| {
+        let mut g = GenIterReturn::new(#[coroutine] || {
             yield 1;
             return "done";
         });
@@ -129,7 +208,7 @@ mod tests {
 
     #[test]
     fn from_generator() {
-        let mut g = GenIterReturn::from(|| {
+        let mut g = GenIterReturn::from(#[coroutine] || {
             yield 1;
             return "done";
         });
@@ -141,6 +220,18 @@ mod tests {
         assert_eq!(g.return_or_self().ok(), Some("done"));
     }
 
+    #[test]
+    fn assert_send_sync() {
+        let mut g = gen_iter_return!({
+            yield 1;
+            "done"
+        }).assert_send().assert_sync();
+
+        assert_eq!((&mut g).next(), Some(1));
+        assert_eq!((&mut g).next(), None);
+        assert_eq!(g.return_or_self().ok(), Some("done"));
+    }
+
     /// normal usage using macro `gen_iter_return`
     #[test]
     fn macro_usage() {
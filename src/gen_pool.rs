@@ -0,0 +1,305 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::iter::Iterator;
+use core::alloc::Layout;
+use core::cell::RefCell;
+use core::marker::PhantomData;
+use core::ptr::{self, NonNull};
+use core::pin::Pin;
+use core::fmt;
+
+use alloc::alloc::{alloc, dealloc, handle_alloc_error};
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+
+/// the freed blocks of a [`GenPool`], shared with its iterators
+struct FreeList(RefCell<Vec<(Layout, NonNull<u8>)>>);
+
+impl FreeList {
+    /// a block for `layout`, reused if one is free
+    fn take(&self, layout: Layout) -> NonNull<u8> {
+        if layout.size() == 0 {
+            // a dangling pointer aligned for `layout`, nothing to allocate
+            return NonNull::new(ptr::without_provenance_mut(layout.align())).unwrap();
+        }
+        let mut free = self.0.borrow_mut();
+        if let Some(i) = free.iter().position(|(l, _)| *l == layout) {
+            return free.swap_remove(i).1;
+        }
+        drop(free);
+        // `layout` has a non zero size
+        match NonNull::new(unsafe { alloc(layout) }) {
+            Some(ptr) => ptr,
+            None => handle_alloc_error(layout),
+        }
+    }
+
+    fn give_back(&self, layout: Layout, ptr: NonNull<u8>) {
+        if layout.size() != 0 {
+            self.0.borrow_mut().push((layout, ptr));
+        }
+    }
+
+    fn clear(&self) {
+        for (layout, ptr) in self.0.borrow_mut().drain(..) {
+            // every free block was allocated with its layout by `take`
+            unsafe { dealloc(ptr.as_ptr(), layout) };
+        }
+    }
+}
+
+impl Drop for FreeList {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// `GenPool` keeps the memory of the generators it made, to make the next
+/// ones in it instead of allocating, like boxed generators made and dropped
+/// in a hot loop.
+///
+/// [`create`](GenPool::create) makes a [`PooledGenIter`], in a block of memory freed
+/// by a previous one with the same size and alignment if there is one. Dropping
+/// a `PooledGenIter` gives its block back to the pool instead of freeing it. The blocks
+/// are freed when the pool and all of its iterators are dropped, or by
+/// [`clear`](GenPool::clear).
+///
+/// The pool and its iterators can only be used on one thread.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::GenPool;
+///
+/// let pool = GenPool::new();
+/// for n in 0..100 {
+///     let it = pool.create(|| #[coroutine] move || {
+///         yield n;
+///         yield n + 1;
+///     });
+///     assert_eq!(it.sum::<u32>(), 2 * n + 1);
+/// }
+/// // every generator reused the block of the first one
+/// assert_eq!(pool.free_blocks(), 1);
+/// ```
+pub struct GenPool {
+    free: Rc<FreeList>,
+}
+
+impl GenPool {
+    #[inline]
+    pub fn new() -> Self {
+        GenPool { free: Rc::new(FreeList(RefCell::new(Vec::new()))) }
+    }
+
+    /// make a generator with `f`, in a free block of the pool if there is one
+    pub fn create<G, F>(&self, f: F) -> PooledGenIter<G>
+    where
+        G: Coroutine<Return = ()>,
+        F: FnOnce() -> G,
+    {
+        // before taking a block, which would leak if `f` panics
+        let gen = f();
+        let ptr = self.free.take(Layout::new::<G>()).cast::<G>();
+        // the block is valid for a `G`, and not used by anything else
+        unsafe { ptr.as_ptr().write(gen) };
+        PooledGenIter { ptr, free: self.free.clone(), _gen: PhantomData }
+    }
+
+    /// the number of blocks freed by the iterators, ready to be reused
+    #[inline]
+    pub fn free_blocks(&self) -> usize {
+        self.free.0.borrow().len()
+    }
+
+    /// free the blocks not used by an iterator
+    #[inline]
+    pub fn clear(&self) {
+        self.free.clear();
+    }
+}
+
+impl Default for GenPool {
+    #[inline]
+    fn default() -> Self {
+        GenPool::new()
+    }
+}
+
+impl fmt::Debug for GenPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GenPool")
+            .field("free_blocks", &self.free_blocks())
+            .finish()
+    }
+}
+
+/// `PooledGenIter<G>` iterates over a generator in the memory of a [`GenPool`],
+/// created by [`GenPool::create`].
+///
+/// The generator is never moved, it does not need to be `Unpin`.
+/// It is not fused, like [`GenIter`](crate::GenIter).
+pub struct PooledGenIter<G: Coroutine<Return = ()>> {
+    // a `G` written by `GenPool::create`, dropped with the iterator
+    ptr: NonNull<G>,
+    free: Rc<FreeList>,
+    _gen: PhantomData<G>,
+}
+
+impl<G: Coroutine<Return = ()>> Iterator for PooledGenIter<G> {
+    type Item = G::Yield;
+
+    #[inline]
+    fn next(&mut self) -> Option<G::Yield> {
+        // `ptr` is valid and only used by this iterator, and the generator is
+        // never moved until it is dropped
+        let gen = unsafe { Pin::new_unchecked(self.ptr.as_mut()) };
+        match gen.resume(()) {
+            CoroutineState::Yielded(y) => Some(y),
+            CoroutineState::Complete(()) => None,
+        }
+    }
+}
+
+impl<G: Coroutine<Return = ()>> Drop for PooledGenIter<G> {
+    fn drop(&mut self) {
+        struct GiveBack<'a>(&'a FreeList, Layout, NonNull<u8>);
+
+        impl Drop for GiveBack<'_> {
+            fn drop(&mut self) {
+                self.0.give_back(self.1, self.2);
+            }
+        }
+
+        // even if the destructor of the generator panics
+        let _give_back = GiveBack(&self.free, Layout::new::<G>(), self.ptr.cast());
+        // the generator was written by `GenPool::create` and is dropped only once
+        unsafe { self.ptr.as_ptr().drop_in_place() };
+    }
+}
+
+impl<G: Coroutine<Return = ()>> fmt::Debug for PooledGenIter<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PooledGenIter").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GenPool;
+    use crate::FnCoroutine;
+    use std::cell::Cell;
+    use std::panic;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    #[test]
+    fn reuse_same_layout() {
+        let pool = GenPool::new();
+        let a = pool.create(|| #[coroutine] || {
+            yield 1u64;
+        });
+        let b = pool.create(|| #[coroutine] || {
+            yield 2u64;
+        });
+        assert_eq!(pool.free_blocks(), 0);
+        drop(a);
+        drop(b);
+        assert_eq!(pool.free_blocks(), 2);
+
+        let c = pool.create(|| #[coroutine] || {
+            yield 3u64;
+        });
+        assert_eq!(pool.free_blocks(), 1);
+        pool.clear();
+        assert_eq!(pool.free_blocks(), 0);
+        assert_eq!(c.collect::<Vec<_>>(), [3]);
+        assert_eq!(pool.free_blocks(), 1);
+    }
+
+    #[test]
+    fn other_layout() {
+        let pool = GenPool::new();
+        drop(pool.create(|| #[coroutine] || {
+            yield 0u8;
+        }));
+        assert_eq!(pool.free_blocks(), 1);
+        let big = [7u64; 8];
+        let it = pool.create(|| #[coroutine] move || {
+            for x in big {
+                yield x as u8;
+            }
+        });
+        // the free block is too small
+        assert_eq!(pool.free_blocks(), 1);
+        assert_eq!(it.count(), 8);
+    }
+
+    #[test]
+    fn drops_generator() {
+        let pool = GenPool::new();
+        let data = Rc::new(());
+        let held = data.clone();
+        let mut it = pool.create(|| #[coroutine] move || {
+            let _held = held;
+            yield 1;
+            yield 2;
+        });
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(Rc::strong_count(&data), 2);
+        drop(it);
+        assert_eq!(Rc::strong_count(&data), 1);
+    }
+
+    #[test]
+    fn outlives_pool() {
+        let pool = GenPool::new();
+        let mut it = pool.create(|| #[coroutine] || {
+            yield 'a';
+            yield 'b';
+        });
+        drop(pool);
+        assert_eq!(it.next(), Some('a'));
+        assert_eq!(it.next(), Some('b'));
+    }
+
+    #[test]
+    fn self_borrowing() {
+        let pool = GenPool::new();
+        // a `static` generator borrowing its own data, not `Unpin`
+        let it = pool.create(|| #[coroutine] static || {
+            let words = ["a", "bc"];
+            for w in words.iter() {
+                yield w.len();
+            }
+        });
+        assert_eq!(it.collect::<Vec<_>>(), [1, 2]);
+    }
+
+    #[test]
+    fn panics() {
+        let pool = GenPool::new();
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            pool.create::<FnCoroutine<fn() -> Option<u32>>, _>(|| panic!("in `f`"))
+        }));
+        assert!(result.is_err());
+        assert_eq!(pool.free_blocks(), 0);
+
+        let dropped = Cell::new(false);
+        struct PanicOnDrop<'a>(&'a Cell<bool>);
+        impl Drop for PanicOnDrop<'_> {
+            fn drop(&mut self) {
+                self.0.set(true);
+                panic!("in drop");
+            }
+        }
+        let mut it = pool.create(|| #[coroutine] || {
+            let _p = PanicOnDrop(&dropped);
+            yield 1;
+        });
+        assert_eq!(it.next(), Some(1));
+        assert!(panic::catch_unwind(panic::AssertUnwindSafe(move || drop(it))).is_err());
+        // the block went back to the pool anyway
+        assert!(dropped.get());
+        assert_eq!(pool.free_blocks(), 1);
+    }
+}
@@ -0,0 +1,618 @@
+use core::ops::Coroutine;
+use core::iter::{Fuse, Iterator};
+use core::marker::Unpin;
+#[cfg(all(feature = "futures", feature = "std"))]
+use core::pin::Pin;
+#[cfg(all(feature = "futures", feature = "std"))]
+use core::task::{Context, Poll};
+#[cfg(feature = "embedded-io")]
+use core::convert::Infallible;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(all(feature = "futures", feature = "std"))]
+use futures_io::AsyncRead;
+
+use crate::GenIter;
+
+/// `GenReader<G>` reads the bytes yielded by a generator, through
+/// - `std::io::Read` with the `std` feature, see [`GenIter::into_read`]
+/// - `futures_io::AsyncRead` with the `futures` and `std` features
+/// - `embedded_io::Read` with the `embedded-io` feature, without `std`
+///
+/// Every read fills as much of the buffer as the generator yields,
+/// and reads `0` bytes once the generator is done.
+/// The generator is synchronous, so reads never fail and async reads are never pending.
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use std::io::Read;
+/// use gen_iter::{gen_iter, GenReader};
+///
+/// let mut r = GenReader::new(gen_iter!({
+///     for b in b"hello" {
+///         yield *b;
+///     }
+/// }));
+///
+/// let mut s = String::new();
+/// r.read_to_string(&mut s).unwrap();
+/// assert_eq!(s, "hello");
+/// ```
+pub struct GenReader<G>(Fuse<GenIter<G>>)
+where
+    G: Coroutine<Yield = u8, Return = ()> + Unpin;
+
+impl<G> GenReader<G>
+where
+    G: Coroutine<Yield = u8, Return = ()> + Unpin,
+{
+    #[inline]
+    pub fn new(g: GenIter<G>) -> Self {
+        GenReader(g.fuse())
+    }
+
+    /// copy the next bytes into `buf`, `0` once the generator is done
+    fn read_bytes(&mut self, buf: &mut [u8]) -> usize {
+        let mut n = 0;
+        for slot in buf.iter_mut() {
+            match self.0.next() {
+                Some(b) => *slot = b,
+                None => break,
+            }
+            n += 1;
+        }
+        n
+    }
+}
+
+#[cfg(feature = "std")]
+impl<G> io::Read for GenReader<G>
+where
+    G: Coroutine<Yield = u8, Return = ()> + Unpin,
+{
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(self.read_bytes(buf))
+    }
+}
+
+#[cfg(all(feature = "futures", feature = "std"))]
+impl<G> AsyncRead for GenReader<G>
+where
+    G: Coroutine<Yield = u8, Return = ()> + Unpin,
+{
+    #[inline]
+    fn poll_read(self: Pin<&mut Self>, _: &mut Context, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(self.get_mut().read_bytes(buf)))
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<G> embedded_io::ErrorType for GenReader<G>
+where
+    G: Coroutine<Yield = u8, Return = ()> + Unpin,
+{
+    type Error = Infallible;
+}
+
+#[cfg(feature = "embedded-io")]
+impl<G> embedded_io::Read for GenReader<G>
+where
+    G: Coroutine<Yield = u8, Return = ()> + Unpin,
+{
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Infallible> {
+        Ok(self.read_bytes(buf))
+    }
+}
+
+impl<G> From<GenIter<G>> for GenReader<G>
+where
+    G: Coroutine<Yield = u8, Return = ()> + Unpin,
+{
+    #[inline]
+    fn from(g: GenIter<G>) -> Self {
+        GenReader::new(g)
+    }
+}
+
+impl<G> fmt::Debug for GenReader<G>
+where
+    G: Coroutine<Yield = u8, Return = ()> + Unpin + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("GenReader").field(&self.0).finish()
+    }
+}
+
+/// `GenChunkReader<G>` reads the chunks of bytes yielded by a generator,
+/// like `Vec<u8>` or `&[u8]`.
+///
+/// A chunk larger than the read buffer is kept, and the rest of it
+/// is copied by the next reads. Empty chunks are skipped.
+/// It implements the same traits as [`GenReader`], and the `BufRead`
+/// of `std::io` and `embedded_io`.
+pub struct GenChunkReader<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: AsRef<[u8]>,
+{
+    gen: Fuse<GenIter<G>>,
+    chunk: Option<G::Yield>,
+    pos: usize,
+}
+
+impl<G> GenChunkReader<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: AsRef<[u8]>,
+{
+    #[inline]
+    pub fn new(g: GenIter<G>) -> Self {
+        GenChunkReader { gen: g.fuse(), chunk: None, pos: 0 }
+    }
+
+    /// the unread part of the current chunk, pulling the next non empty chunk
+    /// if needed. empty once the generator is done
+    fn fill(&mut self) -> &[u8] {
+        while self.chunk.as_ref().is_none_or(|c| self.pos == c.as_ref().len()) {
+            self.pos = 0;
+            self.chunk = self.gen.next();
+            if self.chunk.is_none() {
+                return &[];
+            }
+        }
+        match self.chunk {
+            Some(ref c) => &c.as_ref()[self.pos..],
+            None => &[],
+        }
+    }
+
+    /// mark `amt` bytes of the current chunk as read
+    fn consume_bytes(&mut self, amt: usize) {
+        let len = self.chunk.as_ref().map_or(0, |c| c.as_ref().len());
+        self.pos = (self.pos + amt).min(len);
+    }
+
+    fn read_bytes(&mut self, buf: &mut [u8]) -> usize {
+        if buf.is_empty() {
+            return 0;
+        }
+        let available = self.fill();
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.pos += n;
+        n
+    }
+}
+
+#[cfg(feature = "std")]
+impl<G> io::Read for GenChunkReader<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: AsRef<[u8]>,
+{
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(self.read_bytes(buf))
+    }
+}
+
+/// `fill_buf` only resumes the generator once the current chunk is consumed,
+/// and gives the rest of that chunk without copying it
+#[cfg(feature = "std")]
+impl<G> io::BufRead for GenChunkReader<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: AsRef<[u8]>,
+{
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.fill())
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.consume_bytes(amt)
+    }
+}
+
+#[cfg(all(feature = "futures", feature = "std"))]
+impl<G> AsyncRead for GenChunkReader<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: AsRef<[u8]>,
+{
+    #[inline]
+    fn poll_read(self: Pin<&mut Self>, _: &mut Context, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(self.get_mut().read_bytes(buf)))
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<G> embedded_io::ErrorType for GenChunkReader<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: AsRef<[u8]>,
+{
+    type Error = Infallible;
+}
+
+#[cfg(feature = "embedded-io")]
+impl<G> embedded_io::Read for GenChunkReader<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: AsRef<[u8]>,
+{
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Infallible> {
+        Ok(self.read_bytes(buf))
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<G> embedded_io::BufRead for GenChunkReader<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: AsRef<[u8]>,
+{
+    #[inline]
+    fn fill_buf(&mut self) -> Result<&[u8], Infallible> {
+        Ok(self.fill())
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.consume_bytes(amt)
+    }
+}
+
+// the chunk is never pinned
+impl<G> Unpin for GenChunkReader<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: AsRef<[u8]>,
+{}
+
+#[cfg(feature = "std")]
+impl<G> GenIter<G>
+where
+    G: Coroutine<Yield = u8, Return = ()> + Unpin,
+{
+    /// read the yielded bytes through `std::io::Read`, see [`GenReader`].
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_iter;
+    /// use std::io;
+    ///
+    /// let mut r = gen_iter!({
+    ///     for i in 0..4u8 {
+    ///         yield b'a' + i;
+    ///     }
+    /// }).into_read();
+    ///
+    /// let mut out = Vec::new();
+    /// assert_eq!(io::copy(&mut r, &mut out).unwrap(), 4);
+    /// assert_eq!(out, b"abcd");
+    /// ```
+    #[inline]
+    pub fn into_read(self) -> GenReader<G> {
+        GenReader::new(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: AsRef<[u8]>,
+{
+    /// read the yielded chunks of bytes through `std::io::Read`,
+    /// see [`GenChunkReader`].
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_iter;
+    /// use std::io::Read;
+    ///
+    /// let mut r = gen_iter!({
+    ///     yield b"hello".to_vec();
+    ///     yield Vec::new();
+    ///     yield b" world".to_vec();
+    /// }).into_chunked_read();
+    ///
+    /// let mut s = String::new();
+    /// r.read_to_string(&mut s).unwrap();
+    /// assert_eq!(s, "hello world");
+    /// ```
+    #[inline]
+    pub fn into_chunked_read(self) -> GenChunkReader<G> {
+        GenChunkReader::new(self)
+    }
+}
+
+impl<G> From<GenIter<G>> for GenChunkReader<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: AsRef<[u8]>,
+{
+    #[inline]
+    fn from(g: GenIter<G>) -> Self {
+        GenChunkReader::new(g)
+    }
+}
+
+impl<G> fmt::Debug for GenChunkReader<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+    G::Yield: AsRef<[u8]> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GenChunkReader")
+            .field("gen", &self.gen)
+            .field("chunk", &self.chunk)
+            .field("pos", &self.pos)
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::GenChunkReader;
+    use crate::GenIter;
+    use std::io::{self, BufRead, Read};
+    use std::string::String;
+    use std::vec::Vec;
+
+    fn counting(len: usize) -> GenIter<impl core::ops::Coroutine<Yield = u8, Return = ()> + Unpin> {
+        GenIter(#[coroutine] move || {
+            for i in 0..len {
+                yield i as u8;
+            }
+        })
+    }
+
+    #[test]
+    fn read_to_end() {
+        let mut out = Vec::new();
+        let n = counting(1000).into_read().read_to_end(&mut out).unwrap();
+        assert_eq!(n, 1000);
+        assert_eq!(out, counting(1000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn small_buffers() {
+        let mut r = counting(10).into_read();
+        let mut buf = [0; 4];
+        assert_eq!(r.read(&mut buf).unwrap(), 4);
+        assert_eq!(buf, [0, 1, 2, 3]);
+        assert_eq!(r.read(&mut buf).unwrap(), 4);
+        assert_eq!(buf, [4, 5, 6, 7]);
+        assert_eq!(r.read(&mut buf).unwrap(), 2);
+        assert_eq!(buf[..2], [8, 9]);
+        assert_eq!(r.read(&mut []).unwrap(), 0);
+
+        // done, and it stays done
+        for _ in 0..3 {
+            assert_eq!(r.read(&mut buf).unwrap(), 0);
+        }
+    }
+
+    #[test]
+    fn std_copy() {
+        let mut out = Vec::new();
+        assert_eq!(io::copy(&mut counting(20_000).into_read(), &mut out).unwrap(), 20_000);
+        assert_eq!(out, counting(20_000).collect::<Vec<_>>());
+
+        let mut chunks = GenChunkReader::new(GenIter(#[coroutine] || {
+            yield &b"abc"[..];
+            yield &b""[..];
+            yield &b"de"[..];
+        }));
+        let mut out = Vec::new();
+        io::copy(&mut chunks, &mut out).unwrap();
+        assert_eq!(out, b"abcde");
+    }
+
+    fn mixed_chunks() -> GenIter<impl core::ops::Coroutine<Yield = Vec<u8>, Return = ()> + Unpin> {
+        GenIter(#[coroutine] || {
+            for len in [0, 1, 0, 0, 7, 300, 2, 0, 4096, 3, 0] {
+                yield (0..len).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+            }
+        })
+    }
+
+    #[test]
+    fn chunks_concatenated() {
+        let expected = mixed_chunks().flatten().collect::<Vec<_>>();
+
+        for size in [1, 2, 5, 64, 8192] {
+            let mut r = mixed_chunks().into_chunked_read();
+            let mut buf = std::vec![0; size];
+            let mut out = Vec::new();
+            loop {
+                let n = r.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                out.extend_from_slice(&buf[..n]);
+            }
+            assert_eq!(out, expected, "buffer of {}", size);
+            assert_eq!(r.read(&mut buf).unwrap(), 0);
+        }
+    }
+
+    #[test]
+    fn chunk_larger_than_buffer() {
+        let mut r = GenIter(#[coroutine] || {
+            yield &b""[..];
+            yield &b"abcdefg"[..];
+            yield &b""[..];
+            yield &b""[..];
+            yield &b"hi"[..];
+        }).into_chunked_read();
+
+        let mut buf = [0; 3];
+        let mut reads = Vec::new();
+        loop {
+            let n = r.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            reads.push(buf[..n].to_vec());
+        }
+        // empty chunks are not an end of file
+        assert_eq!(reads, [&b"abc"[..], b"def", b"g", b"hi"]);
+    }
+
+    #[test]
+    fn lines_across_chunks() {
+        let text = "first line\n\nthird, a longer line\r\nno newline at the end";
+        let pieces = ["fir", "st line", "\n", "", "\nthird, a", " longer line\r", "\nno newline", " at the end"];
+        assert_eq!(pieces.concat(), text);
+
+        let lines = GenIter(#[coroutine] move || {
+            for p in pieces {
+                yield p;
+            }
+        }).into_chunked_read().lines().collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(lines, text.lines().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn read_until_and_consume() {
+        let mut r = GenIter(#[coroutine] || {
+            yield &b"a,bc"[..];
+            yield &b""[..];
+            yield &b",d"[..];
+        }).into_chunked_read();
+
+        let mut field = Vec::new();
+        r.read_until(b',', &mut field).unwrap();
+        assert_eq!(field, b"a,");
+
+        // the rest of the first chunk, the generator is not resumed yet
+        assert_eq!(r.fill_buf().unwrap(), b"bc");
+        assert_eq!(r.fill_buf().unwrap(), b"bc");
+        r.consume(1);
+        assert_eq!(r.fill_buf().unwrap(), b"c");
+        r.consume(1);
+        // skips the empty chunk
+        assert_eq!(r.fill_buf().unwrap(), b",d");
+
+        let mut rest = String::new();
+        r.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, ",d");
+        assert_eq!(r.fill_buf().unwrap(), b"");
+    }
+
+    #[cfg(feature = "futures")]
+    mod async_read {
+        use crate::{GenChunkReader, GenIter, GenReader};
+        use futures::executor::block_on;
+        use futures::io::{copy, AsyncReadExt};
+        use std::vec::Vec;
+
+        #[test]
+        fn copy_bytes() {
+            let bytes = || GenIter(#[coroutine] || {
+                for i in 0..=255u8 {
+                    for _ in 0..100 {
+                        yield i;
+                    }
+                }
+            });
+
+            let mut out = Vec::new();
+            let n = block_on(copy(GenReader::new(bytes()), &mut out)).unwrap();
+            assert_eq!(n, 25600);
+            assert_eq!(out, bytes().collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn copy_chunks() {
+            let chunks = || GenIter(#[coroutine] || {
+                for len in 0..200usize {
+                    yield [len as u8].repeat(len);
+                }
+            });
+
+            let mut out = Vec::new();
+            block_on(copy(GenChunkReader::new(chunks()), &mut out)).unwrap();
+            assert_eq!(out, chunks().flatten().collect::<Vec<_>>());
+        }
+
+        /// a chunk larger than the buffer is read in pieces
+        #[test]
+        fn partial_chunks() {
+            let mut r = GenChunkReader::new(GenIter(#[coroutine] || {
+                yield &b"abcdefg"[..];
+                yield &b""[..];
+                yield &b"hi"[..];
+            }));
+
+            let mut buf = [0; 3];
+            let mut reads = Vec::new();
+            loop {
+                let n = block_on(r.read(&mut buf)).unwrap();
+                if n == 0 {
+                    break;
+                }
+                reads.push(buf[..n].to_vec());
+            }
+            assert_eq!(reads, [&b"abc"[..], b"def", b"g", b"hi"]);
+            assert_eq!(block_on(r.read(&mut buf)).unwrap(), 0);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "embedded-io"))]
+mod embedded_io_tests {
+    use super::{GenChunkReader, GenReader};
+    use crate::GenIter;
+    use embedded_io::{BufRead, Read, ReadExactError};
+
+    #[test]
+    fn read_exact() {
+        let mut r = GenReader::new(GenIter(#[coroutine] || {
+            for i in 0..10u8 {
+                yield i;
+            }
+        }));
+
+        let mut buf = [0; 4];
+        r.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [0, 1, 2, 3]);
+        assert_eq!(r.read(&mut buf), Ok(4));
+        assert_eq!(buf, [4, 5, 6, 7]);
+        assert_eq!(r.read_exact(&mut buf), Err(ReadExactError::UnexpectedEof));
+        assert_eq!(r.read(&mut buf), Ok(0));
+    }
+
+    #[test]
+    fn chunks() {
+        let mut r = GenChunkReader::new(GenIter(#[coroutine] || {
+            yield &b"abc"[..];
+            yield &b""[..];
+            yield &b"defg"[..];
+        }));
+
+        let mut buf = [0; 5];
+        r.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"abcde");
+        assert_eq!(r.fill_buf(), Ok(&b"fg"[..]));
+        r.consume(1);
+        assert_eq!(r.fill_buf(), Ok(&b"g"[..]));
+        r.consume(1);
+        assert_eq!(r.fill_buf(), Ok(&b""[..]));
+        assert_eq!(r.read(&mut buf), Ok(0));
+    }
+}
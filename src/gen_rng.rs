@@ -0,0 +1,262 @@
+use core::ops::Coroutine;
+use core::iter::{Fuse, Iterator};
+use core::marker::Unpin;
+use core::num::NonZeroU32;
+use core::fmt;
+
+use rand_core::{Error, RngCore};
+
+use crate::GenIter;
+
+/// the words a [`GenRng`] can be built from: `u32` and `u64`
+pub trait RngWord: Copy + sealed::Sealed {
+    #[doc(hidden)]
+    const ZERO: Self;
+    #[doc(hidden)]
+    fn fill(self, dest: &mut [u8]) -> usize;
+    #[doc(hidden)]
+    fn next_u32<F: FnMut() -> Self>(next: F) -> u32;
+    #[doc(hidden)]
+    fn next_u64<F: FnMut() -> Self>(next: F) -> u64;
+}
+
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+}
+
+impl RngWord for u32 {
+    const ZERO: u32 = 0;
+
+    #[inline]
+    fn fill(self, dest: &mut [u8]) -> usize {
+        let n = dest.len().min(4);
+        dest[..n].copy_from_slice(&self.to_le_bytes()[..n]);
+        n
+    }
+
+    #[inline]
+    fn next_u32<F: FnMut() -> u32>(mut next: F) -> u32 {
+        next()
+    }
+
+    #[inline]
+    fn next_u64<F: FnMut() -> u32>(mut next: F) -> u64 {
+        let lo = next() as u64;
+        let hi = next() as u64;
+        (hi << 32) | lo
+    }
+}
+
+impl RngWord for u64 {
+    const ZERO: u64 = 0;
+
+    #[inline]
+    fn fill(self, dest: &mut [u8]) -> usize {
+        let n = dest.len().min(8);
+        dest[..n].copy_from_slice(&self.to_le_bytes()[..n]);
+        n
+    }
+
+    #[inline]
+    fn next_u32<F: FnMut() -> u64>(mut next: F) -> u32 {
+        next() as u32
+    }
+
+    #[inline]
+    fn next_u64<F: FnMut() -> u64>(mut next: F) -> u64 {
+        next()
+    }
+}
+
+/// `GenRng<G>` is a `rand_core::RngCore` over a generator yielding `u32`s or `u64`s,
+/// like a deterministic source for tests and simulations.
+///
+/// - `next_u32` and `next_u64` take one yield of their size, a `u64` from `u32`s
+///   takes two, low half first, and a `u32` from `u64`s is the low half of one
+/// - `fill_bytes` copies successive yields in little endian, the bytes of the last
+///   yield that do not fit are dropped
+///
+/// When the generator completes, a `GenRng` built with [`new`](GenRng::new) panics,
+/// `try_fill_bytes` returns an error with the code `rand_core::Error::CUSTOM_START`
+/// instead. One built with [`zero_filled`](GenRng::zero_filled) goes on with zeros.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// extern crate gen_iter;
+/// extern crate rand;
+///
+/// use gen_iter::{gen_iter, GenRng};
+/// use rand::Rng;
+///
+/// // a linear congruential generator
+/// let lcg = |seed: u64| GenRng::new(gen_iter!(move {
+///     let mut x = seed;
+///     loop {
+///         x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+///         yield x;
+///     }
+/// }));
+///
+/// let rolls = |mut rng: GenRng<_>| (0..10).map(|_| rng.gen_range(1..=6)).collect::<Vec<u8>>();
+/// assert_eq!(rolls(lcg(7)), rolls(lcg(7)));
+/// ```
+pub struct GenRng<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: RngWord,
+{
+    gen: Fuse<GenIter<G>>,
+    zero_filled: bool,
+}
+
+impl<G> GenRng<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: RngWord,
+{
+    /// a `GenRng` panicking once the generator completes
+    #[inline]
+    pub fn new(g: GenIter<G>) -> Self {
+        GenRng { gen: g.fuse(), zero_filled: false }
+    }
+
+    /// a `GenRng` giving zeros once the generator completes
+    #[inline]
+    pub fn zero_filled(g: GenIter<G>) -> Self {
+        GenRng { gen: g.fuse(), zero_filled: true }
+    }
+
+    fn try_word(&mut self) -> Option<G::Yield> {
+        match self.gen.next() {
+            Some(w) => Some(w),
+            None if self.zero_filled => Some(RngWord::ZERO),
+            None => None,
+        }
+    }
+
+    fn word(&mut self) -> G::Yield {
+        match self.try_word() {
+            Some(w) => w,
+            None => panic!("the generator of the GenRng is done"),
+        }
+    }
+}
+
+impl<G> RngCore for GenRng<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: RngWord,
+{
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        RngWord::next_u32(|| self.word())
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        RngWord::next_u64(|| self.word())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if self.try_fill_bytes(dest).is_err() {
+            panic!("the generator of the GenRng is done");
+        }
+    }
+
+    fn try_fill_bytes(&mut self, mut dest: &mut [u8]) -> Result<(), Error> {
+        while !dest.is_empty() {
+            let w = match self.try_word() {
+                Some(w) => w,
+                None => return Err(Error::from(NonZeroU32::new(Error::CUSTOM_START).unwrap())),
+            };
+            let n = w.fill(dest);
+            dest = &mut dest[n..];
+        }
+        Ok(())
+    }
+}
+
+impl<G> fmt::Debug for GenRng<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+    G::Yield: RngWord,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GenRng")
+            .field("gen", &self.gen)
+            .field("zero_filled", &self.zero_filled)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GenRng;
+    use crate::GenIter;
+    use rand::Rng;
+    use rand_core::RngCore;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::vec::Vec;
+
+    /// xorshift32
+    fn xorshift(seed: u32, len: usize) -> GenIter<impl core::ops::Coroutine<Yield = u32, Return = ()> + Unpin> {
+        GenIter(#[coroutine] move || {
+            let mut x = seed;
+            for _ in 0..len {
+                x ^= x << 13;
+                x ^= x >> 17;
+                x ^= x << 5;
+                yield x;
+            }
+        })
+    }
+
+    #[test]
+    fn gen_range_is_deterministic() {
+        let rolls = |mut rng: GenRng<_>| (0..100).map(|_| rng.gen_range(0..10u32)).collect::<Vec<_>>();
+        let a = rolls(GenRng::new(xorshift(1, 1000)));
+        assert_eq!(a, rolls(GenRng::new(xorshift(1, 1000))));
+        assert!(a.iter().all(|&r| r < 10));
+        assert_ne!(a, rolls(GenRng::new(xorshift(2, 1000))));
+    }
+
+    #[test]
+    fn raw_yields() {
+        let raw = xorshift(9, 6).collect::<Vec<_>>();
+        let mut rng = GenRng::new(xorshift(9, 6));
+
+        assert_eq!(rng.next_u32(), raw[0]);
+        assert_eq!(rng.next_u64(), (raw[2] as u64) << 32 | raw[1] as u64);
+
+        let mut bytes = [0; 7];
+        rng.fill_bytes(&mut bytes);
+        assert_eq!(bytes[..4], raw[3].to_le_bytes());
+        assert_eq!(bytes[4..], raw[4].to_le_bytes()[..3]);
+
+        let mut words = GenRng::new(GenIter(#[coroutine] || {
+            yield 0x1122_3344_5566_7788u64;
+            yield 0x99;
+        }));
+        assert_eq!(words.next_u32(), 0x5566_7788);
+        assert_eq!(words.next_u64(), 0x99);
+    }
+
+    #[test]
+    fn exhausted() {
+        let mut rng = GenRng::new(xorshift(3, 1));
+        let mut bytes = [0; 8];
+        let err = rng.try_fill_bytes(&mut bytes).unwrap_err();
+        assert_eq!(err.code().map(|c| c.get()), Some(rand_core::Error::CUSTOM_START));
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| rng.next_u32())).is_err());
+
+        let mut rng = GenRng::zero_filled(xorshift(3, 1));
+        let first = rng.next_u32();
+        assert_eq!(first, xorshift(3, 1).next().unwrap());
+        assert_eq!(rng.next_u64(), 0);
+        rng.fill_bytes(&mut bytes);
+        assert_eq!(bytes, [0; 8]);
+    }
+}
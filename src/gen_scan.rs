@@ -0,0 +1,154 @@
+// owns the state, so that the generator captures it by value, even when `Copy`
+#[doc(hidden)]
+pub struct __ScanState<T>(pub T);
+
+impl<T> __ScanState<T> {
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// macro to make a [`GenIter`](crate::GenIter) over a generator carrying some state,
+/// like `Iterator::scan`.
+///
+/// `gen_scan!(state = init; |name| { ... })` evaluates `init` right away, and moves it
+/// into the generator, where the block gets it as the mutable variable `name`.
+/// The generator captures everything else like [`gen_iter!`](crate::gen_iter),
+/// or by value with `gen_scan!(move state = init; ...)`.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_scan;
+///
+/// let items = [3, 1, 4, 1, 5];
+/// let g = gen_scan!(state = (0, 0); |max| {
+///     for (i, &x) in items.iter().enumerate() {
+///         if x > max.1 {
+///             max = (i, x);
+///             yield max;
+///         }
+///     }
+/// });
+///
+/// assert_eq!(g.collect::<Vec<_>>(), [(0, 3), (2, 4), (4, 5)]);
+/// ```
+#[macro_export]
+macro_rules! gen_scan {
+    (state = $init: expr; |$name: ident| $block: block) => {{
+        let state = $crate::__ScanState($init);
+        $crate::GenIter(#[coroutine] || {
+            #[allow(unused_mut)]
+            let mut $name = state.into_inner();
+            $block
+        })
+    }};
+    (move state = $init: expr; |$name: ident| $block: block) => {{
+        let state = $crate::__ScanState($init);
+        $crate::GenIter(#[coroutine] move || {
+            #[allow(unused_mut)]
+            let mut $name = state.into_inner();
+            $block
+        })
+    }};
+}
+
+/// macro to make a [`GenIterReturn`](crate::GenIterReturn) over a generator carrying
+/// some state, like [`gen_scan!`](crate::gen_scan), with the value of the block as
+/// the return value, like the final state.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_scan_return;
+///
+/// let mut g = gen_scan_return!(state = 1u64; |fact| {
+///     for i in 1..=5 {
+///         fact *= i;
+///         yield fact;
+///     }
+///     fact
+/// });
+///
+/// assert_eq!((&mut g).last(), Some(120));
+/// assert_eq!(g.return_or_self().ok(), Some(120));
+/// ```
+#[macro_export]
+macro_rules! gen_scan_return {
+    (state = $init: expr; |$name: ident| $block: block) => {{
+        let state = $crate::__ScanState($init);
+        $crate::GenIterReturn::new(#[coroutine] || {
+            #[allow(unused_mut)]
+            let mut $name = state.into_inner();
+            $block
+        })
+    }};
+    (move state = $init: expr; |$name: ident| $block: block) => {{
+        let state = $crate::__ScanState($init);
+        $crate::GenIterReturn::new(#[coroutine] move || {
+            #[allow(unused_mut)]
+            let mut $name = state.into_inner();
+            $block
+        })
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIter;
+    use core::ops::Coroutine;
+    use std::vec::Vec;
+
+    fn running_total(items: Vec<i32>) -> GenIter<impl Coroutine<Yield = i32, Return = ()> + Unpin> {
+        gen_scan!(move state = 0; |total| {
+            for x in items {
+                total += x;
+                yield total;
+            }
+        })
+    }
+
+    #[test]
+    fn running_totals() {
+        assert_eq!(running_total(Vec::from([1, 2, -4, 10])).collect::<Vec<_>>(), [1, 3, -1, 9]);
+        assert_eq!(running_total(Vec::new()).count(), 0);
+    }
+
+    #[test]
+    fn window() {
+        let samples = [1, 5, 3, 8, 2, 9];
+        let mut g = gen_scan_return!(state = [0; 3]; |window| {
+            let mut seen = 0;
+            for &x in samples.iter() {
+                window.rotate_left(1);
+                window[2] = x;
+                seen += 1;
+                if seen >= window.len() {
+                    yield window.iter().sum::<i32>();
+                }
+            }
+            window
+        });
+        assert_eq!((&mut g).collect::<Vec<_>>(), [9, 16, 13, 19]);
+        assert_eq!(g.return_or_self().ok(), Some([8, 2, 9]));
+    }
+
+    #[test]
+    fn init_evaluated_once() {
+        let mut calls = 0;
+        let mut g = gen_scan!(state = {
+            calls += 1;
+            10
+        }; |n| {
+            while n > 7 {
+                n -= 1;
+                yield n;
+            }
+        });
+        // right away, not when the generator starts
+        assert_eq!(calls, 1);
+        assert_eq!(g.next(), Some(9));
+        assert_eq!(g.collect::<Vec<_>>(), [8, 7]);
+    }
+}
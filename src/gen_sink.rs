@@ -0,0 +1,261 @@
+use core::ops::Coroutine;
+use core::iter::{Extend, IntoIterator};
+use core::marker::Unpin;
+use core::fmt;
+#[cfg(feature = "futures")]
+use core::pin::Pin;
+#[cfg(feature = "futures")]
+use core::task::{Context, Poll};
+
+use crate::GenIterExchange;
+
+/// `GenSink<G, T>` turns a consuming generator into a push style sink.
+///
+/// The generator receives its items as resume arguments:
+/// 1. every [`push`](GenSink::push) resumes it with `Some(item)`,
+///    the first one is the argument of the generator closure
+/// 2. [`finish`](GenSink::finish) resumes it with `None` until it completes,
+///    and returns its return value
+///
+/// The generator yields `()` whenever it is ready for the next item,
+/// it may also complete early, in which case further items are refused.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::GenSink;
+///
+/// let mut sink = GenSink::new(#[coroutine] |mut item: Option<u32>| {
+///     let mut sum = 0;
+///     while let Some(n) = item {
+///         sum += n;
+///         item = yield;
+///     }
+///     sum
+/// });
+///
+/// sink.extend([1, 2, 3]);
+/// assert_eq!(sink.finish(), 6);
+/// ```
+pub struct GenSink<G, T>(GenIterExchange<G, Option<T>>)
+where
+    G: Coroutine<Option<T>, Yield = ()> + Unpin;
+
+impl<G, T> GenSink<G, T>
+where
+    G: Coroutine<Option<T>, Yield = ()> + Unpin,
+{
+    #[inline]
+    pub fn new(g: G) -> Self {
+        GenSink(GenIterExchange::new(g))
+    }
+
+    /// send `item` to the generator.
+    ///
+    /// if the generator is already complete, `item` is given back.
+    #[inline]
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        if self.0.is_complete() {
+            return Err(item);
+        }
+        self.0.next_with(Some(item));
+        Ok(())
+    }
+
+    /// whether the generator is complete and refuses further items
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.0.is_complete()
+    }
+
+    /// signal the end of the items, and get the return value of the generator.
+    ///
+    /// the generator is resumed with `None` until it completes.
+    #[inline]
+    pub fn finish(mut self) -> G::Return {
+        self.close();
+        match self.0.return_or_self() {
+            Ok(r) => r,
+            Err(_) => unreachable!("generator is done"),
+        }
+    }
+
+    /// signal the end of the items like [`finish`](GenSink::finish),
+    /// but keep the return value inside the sink
+    /// for [`return_or_self`](GenSink::return_or_self)
+    #[inline]
+    pub fn close(&mut self) {
+        while self.0.next_with(None).is_some() {}
+    }
+
+    /// get the return value if the generator is complete
+    #[inline]
+    pub fn return_or_self(self) -> Result<G::Return, Self> {
+        self.0.return_or_self().map_err(GenSink)
+    }
+}
+
+impl<G, T> Extend<T> for GenSink<G, T>
+where
+    G: Coroutine<Option<T>, Yield = ()> + Unpin,
+{
+    /// push every item, the items after the generator completed are dropped
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            if self.push(item).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+impl<G, T> fmt::Debug for GenSink<G, T>
+where
+    G: Coroutine<Option<T>, Yield = ()> + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("GenSink").field(&self.0).finish()
+    }
+}
+
+impl<G, T> From<G> for GenSink<G, T>
+where
+    G: Coroutine<Option<T>, Yield = ()> + Unpin,
+{
+    #[inline]
+    fn from(g: G) -> Self {
+        GenSink::new(g)
+    }
+}
+
+/// the error when an item is sent after the generator completed,
+/// by the `futures_sink::Sink` implementation of [`GenSink`],
+/// or the `embedded_io::Write` implementation of `GenWrite`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SinkComplete;
+
+impl fmt::Display for SinkComplete {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the generator of the sink is complete")
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::Error for SinkComplete {
+    #[inline]
+    fn kind(&self) -> embedded_io::ErrorKind {
+        embedded_io::ErrorKind::WriteZero
+    }
+}
+
+/// `GenSink` is always ready, `poll_close` runs [`close`](GenSink::close)
+/// so that the return value can be got afterwards
+#[cfg(feature = "futures")]
+impl<G, T> futures_sink::Sink<T> for GenSink<G, T>
+where
+    G: Coroutine<Option<T>, Yield = ()> + Unpin,
+{
+    type Error = SinkComplete;
+
+    #[inline]
+    fn poll_ready(self: Pin<&mut Self>, _: &mut Context) -> Poll<Result<(), SinkComplete>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), SinkComplete> {
+        self.get_mut().push(item).map_err(|_| SinkComplete)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<Result<(), SinkComplete>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context) -> Poll<Result<(), SinkComplete>> {
+        self.get_mut().close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GenSink;
+    use std::vec::Vec;
+
+    #[test]
+    fn extend_and_finish() {
+        let mut sink = GenSink::new(#[coroutine] |mut item: Option<&u8>| {
+            let mut seen = Vec::new();
+            while let Some(&b) = item {
+                seen.push(b);
+                item = yield;
+            }
+            seen
+        });
+
+        sink.extend(b"abc");
+        sink.push(&b'd').unwrap();
+        assert!(!sink.is_complete());
+
+        assert_eq!(sink.finish(), b"abcd");
+    }
+
+    #[test]
+    fn empty() {
+        let sink = GenSink::new(#[coroutine] |mut item: Option<u32>| {
+            let mut count = 0;
+            while item.is_some() {
+                count += 1;
+                item = yield;
+            }
+            count
+        });
+
+        assert_eq!(sink.finish(), 0);
+    }
+
+    /// takes at most two items, and completes on its own
+    #[test]
+    fn complete_early() {
+        let mut sink = GenSink::new(#[coroutine] |first: Option<u32>| {
+            let second = yield;
+            (first, second)
+        });
+
+        sink.extend(1..10);
+        assert!(sink.is_complete());
+        assert_eq!(sink.push(10), Err(10));
+
+        assert_eq!(sink.return_or_self().ok(), Some((Some(1), Some(2))));
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn futures_sink() {
+        use futures::executor::block_on;
+        use futures::sink::SinkExt;
+        use futures::stream::{self, StreamExt};
+        use super::SinkComplete;
+
+        let mut sink = GenSink::new(#[coroutine] |mut item: Option<u32>| {
+            let mut seen = Vec::new();
+            while let Some(n) = item {
+                seen.push(n);
+                item = yield;
+            }
+            seen
+        });
+
+        let mut items = stream::iter(0..10).map(Ok);
+        block_on(sink.send_all(&mut items)).unwrap();
+        block_on(SinkExt::close(&mut sink)).unwrap();
+
+        assert!(sink.is_complete());
+        assert_eq!(block_on(sink.send(10)), Err(SinkComplete));
+        assert_eq!(sink.return_or_self().ok(), Some((0..10).collect()));
+    }
+}
@@ -0,0 +1,134 @@
+use core::ops::Coroutine;
+use core::pin::Pin;
+use core::fmt;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::GenIterExchange;
+
+type Splitter<'a, S> = Pin<Box<dyn Coroutine<Option<S>, Yield = Vec<String>, Return = String> + 'a>>;
+
+/// `GenSplitter` splits text that arrives in chunks into records.
+///
+/// It is a small generator taking resume arguments, and a template for writing
+/// incremental parsers with them:
+/// 1. every [`feed`](GenSplitter::feed) resumes it with `Some(chunk)`,
+///    it yields the records completed by that chunk
+/// 2. [`finish`](GenSplitter::finish) resumes it with `None`,
+///    it returns the text after the last delimiter
+///
+/// The delimiters are not part of the records. Chunks can be any `S: AsRef<str>`,
+/// borrowed chunks have to outlive the splitter.
+///
+/// ```
+/// use gen_iter::GenSplitter;
+///
+/// let mut lines = GenSplitter::lines();
+///
+/// assert_eq!(lines.feed("hello wo"), Vec::<String>::new());
+/// assert_eq!(lines.feed("rld\nsecond\nthi"), ["hello world", "second"]);
+/// assert_eq!(lines.finish(), "thi");
+/// ```
+pub struct GenSplitter<'a, S>(GenIterExchange<Splitter<'a, S>, Option<S>>);
+
+impl<'a, S: AsRef<str> + 'a> GenSplitter<'a, S> {
+    /// split on `delim`
+    pub fn new(delim: char) -> Self {
+        let splitter = #[coroutine] move |mut chunk: Option<S>| {
+            let mut buf = String::new();
+            while let Some(text) = chunk {
+                // the buffer holds no delimiter, only search the new text
+                let mut search = buf.len();
+                buf.push_str(text.as_ref());
+
+                let mut records = Vec::new();
+                let mut start = 0;
+                while let Some(i) = buf[search..].find(delim) {
+                    records.push(String::from(&buf[start..search + i]));
+                    start = search + i + delim.len_utf8();
+                    search = start;
+                }
+                buf.drain(..start);
+
+                chunk = yield records;
+            }
+            buf
+        };
+        GenSplitter(GenIterExchange::new(Box::pin(splitter)))
+    }
+
+    /// split on `'\n'`, a `'\r'` before it is kept in the record
+    #[inline]
+    pub fn lines() -> Self {
+        GenSplitter::new('\n')
+    }
+
+    /// add `chunk` to the text, and get the records it completed
+    #[inline]
+    pub fn feed(&mut self, chunk: S) -> Vec<String> {
+        self.0.next_with(Some(chunk)).expect("splitter is running until finish")
+    }
+
+    /// end the text, and get the part after the last delimiter
+    #[inline]
+    pub fn finish(mut self) -> String {
+        self.0.next_with(None);
+        self.0.return_or_self().ok().expect("generator is done")
+    }
+}
+
+impl<'a, S> fmt::Debug for GenSplitter<'a, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GenSplitter").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GenSplitter;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+
+    #[test]
+    fn line_across_chunks() {
+        let mut lines = GenSplitter::lines();
+        assert!(lines.feed("a very ").is_empty());
+        assert!(lines.feed("long ").is_empty());
+        assert_eq!(lines.feed("line\n"), ["a very long line"]);
+        assert_eq!(lines.finish(), "");
+    }
+
+    #[test]
+    fn lines_per_chunk() {
+        let mut lines = GenSplitter::lines();
+        assert_eq!(lines.feed("one\ntwo\n\nthr"), ["one", "two", ""]);
+        assert_eq!(lines.feed("ee\nfour\n"), ["three", "four"]);
+        assert_eq!(lines.finish(), "");
+    }
+
+    #[test]
+    fn empty_chunks() {
+        let mut lines = GenSplitter::lines();
+        assert!(lines.feed("").is_empty());
+        assert!(lines.feed("x").is_empty());
+        assert!(lines.feed("").is_empty());
+        assert_eq!(lines.feed("\n"), ["x"]);
+        assert!(lines.feed("").is_empty());
+        assert_eq!(lines.finish(), "");
+
+        assert_eq!(GenSplitter::<&str>::lines().finish(), "");
+    }
+
+    #[test]
+    fn trailing_partial() {
+        let mut records = GenSplitter::new('→');
+        let mut seen = Vec::new();
+        for chunk in ["a→b", "c→", "d→e", "f"] {
+            seen.extend(records.feed(chunk.to_string()));
+        }
+        assert_eq!(seen, ["a", "bc", "d"]);
+        assert_eq!(records.finish(), String::from("ef"));
+    }
+}
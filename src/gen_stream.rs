@@ -0,0 +1,231 @@
+use core::ops::Coroutine;
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::fmt;
+
+#[cfg(feature = "async_iterator")]
+use core::async_iter::AsyncIterator;
+
+#[cfg(feature = "futures")]
+use futures_core::stream::{FusedStream, Stream};
+
+use crate::{GenIter, GenIterReturn};
+
+/// `GenStream<G>` is an asynchronous iterator over the values yielded by a generator.
+/// It implements `futures_core::Stream` with the `futures` feature,
+/// and `core::async_iter::AsyncIterator` with the `async_iterator` feature.
+///
+/// Every `poll_next` resumes the generator once, so it is never `Pending`.
+/// Like [`GenIterReturn`], the return value of the generator is kept,
+/// and can be got after the stream ended.
+///
+#[cfg_attr(feature = "futures", doc = "```")]
+#[cfg_attr(not(feature = "futures"), doc = "```ignore")]
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// extern crate futures;
+/// extern crate gen_iter;
+///
+/// use futures::executor::block_on;
+/// use futures::stream::StreamExt;
+/// use gen_iter::gen_iter_return;
+///
+/// let mut s = gen_iter_return!({
+///     yield 1;
+///     yield 2;
+///     "done"
+/// }).into_stream();
+///
+/// assert_eq!(block_on((&mut s).collect::<Vec<_>>()), [1, 2]);
+/// assert_eq!(s.return_or_self().ok(), Some("done"));
+/// ```
+pub struct GenStream<G: Coroutine + Unpin>(GenIterReturn<G>);
+
+// the generator is `Unpin`, the return value is never pinned
+impl<G: Coroutine + Unpin> Unpin for GenStream<G> {}
+
+impl<G> Clone for GenStream<G>
+where
+    G: Coroutine + Unpin + Clone,
+    G::Return: Clone,
+{
+    fn clone(&self) -> Self {
+        GenStream(self.0.clone())
+    }
+}
+
+impl<G> Copy for GenStream<G>
+where
+    G: Coroutine + Unpin + Copy,
+    G::Return: Copy,
+{}
+
+impl<G> fmt::Debug for GenStream<G>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("GenStream").field(&self.0).finish()
+    }
+}
+
+impl<G: Coroutine + Unpin> GenStream<G> {
+    #[inline]
+    pub fn new(g: G) -> Self {
+        GenStream(GenIterReturn::new(g))
+    }
+
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.0.is_done()
+    }
+
+    #[inline]
+    pub fn return_or_self(self) -> Result<G::Return, Self> {
+        self.0.return_or_self().map_err(GenStream)
+    }
+
+    /// get back the underlying [`GenIterReturn`]
+    #[inline]
+    pub fn into_inner(self) -> GenIterReturn<G> {
+        self.0
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<G: Coroutine + Unpin> Stream for GenStream<G> {
+    type Item = G::Yield;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, _: &mut Context) -> Poll<Option<G::Yield>> {
+        Poll::Ready((&mut self.get_mut().0).next())
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<G: Coroutine + Unpin> FusedStream for GenStream<G> {
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.is_done()
+    }
+}
+
+#[cfg(feature = "async_iterator")]
+impl<G: Coroutine + Unpin> AsyncIterator for GenStream<G> {
+    type Item = G::Yield;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, _: &mut Context) -> Poll<Option<G::Yield>> {
+        Poll::Ready((&mut self.get_mut().0).next())
+    }
+}
+
+impl<G: Coroutine + Unpin> From<G> for GenStream<G> {
+    #[inline]
+    fn from(g: G) -> Self {
+        GenStream::new(g)
+    }
+}
+
+impl<G: Coroutine<Return = ()> + Unpin> GenIter<G> {
+    /// turn this iterator into a [`GenStream`]
+    #[inline]
+    pub fn into_stream(self) -> GenStream<G> {
+        GenStream::new(self.0)
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// turn this into a [`GenStream`], the return value is kept in the stream
+    #[inline]
+    pub fn into_stream(self) -> GenStream<G> {
+        GenStream(self)
+    }
+}
+
+#[cfg(all(test, feature = "futures"))]
+mod tests {
+    use super::GenStream;
+    use crate::{GenIter, GenIterReturn};
+    use futures::executor::block_on;
+    use futures::stream::{FusedStream, StreamExt};
+    use std::vec::Vec;
+
+    #[test]
+    fn from_generator() {
+        let s = GenStream::from(#[coroutine] || {
+            yield "only";
+        });
+        assert_eq!(block_on(s.collect::<Vec<_>>()), ["only"]);
+    }
+
+    #[test]
+    fn collect_gen_iter() {
+        let s = GenIter(#[coroutine] || {
+            for i in 0..5 {
+                yield i * i;
+            }
+        }).into_stream();
+
+        assert_eq!(block_on(s.collect::<Vec<_>>()), [0, 1, 4, 9, 16]);
+    }
+
+    #[test]
+    fn next_after_end() {
+        let mut s = GenIterReturn::new(#[coroutine] || {
+            yield 'a';
+            3
+        }).into_stream();
+
+        assert_eq!(block_on(s.next()), Some('a'));
+        assert!(!s.is_terminated());
+        assert_eq!(block_on(s.next()), None);
+        assert!(s.is_terminated());
+        assert_eq!(block_on(s.next()), None);
+
+        assert_eq!(s.return_or_self().ok(), Some(3));
+    }
+
+    #[test]
+    fn from_partially_consumed() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            yield 2;
+            "done"
+        });
+        assert_eq!((&mut g).next(), Some(1));
+
+        let mut s = g.into_stream();
+        assert_eq!(block_on((&mut s).collect::<Vec<_>>()), [2]);
+        assert!(s.is_done());
+        assert_eq!(s.into_inner().return_or_self().ok(), Some("done"));
+    }
+}
+
+#[cfg(all(test, feature = "async_iterator"))]
+mod async_iterator_tests {
+    use crate::GenIterReturn;
+    use core::async_iter::AsyncIterator;
+    use core::pin::Pin;
+    use core::task::{Context, Poll, Waker};
+
+    /// behaves like `&mut GenIterReturn` as an iterator
+    #[test]
+    fn poll_next() {
+        let mut s = GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            yield 2;
+            "done"
+        }).into_stream();
+
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(Pin::new(&mut s).poll_next(&mut cx), Poll::Ready(Some(1)));
+        assert_eq!(Pin::new(&mut s).poll_next(&mut cx), Poll::Ready(Some(2)));
+        assert!(!s.is_done());
+        assert_eq!(Pin::new(&mut s).poll_next(&mut cx), Poll::Ready(None));
+        assert_eq!(Pin::new(&mut s).poll_next(&mut cx), Poll::Ready(None));
+        assert_eq!(s.return_or_self().ok(), Some("done"));
+    }
+}
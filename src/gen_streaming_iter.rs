@@ -0,0 +1,167 @@
+use core::ops::Coroutine;
+use core::iter::{Fuse, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use streaming_iterator::{StreamingIterator, StreamingIteratorMut};
+
+use crate::GenIter;
+
+/// `GenStreamingIter<G>` is a [`StreamingIterator`] over the yields of a generator.
+///
+/// This is the "slot" pattern, not true lending: the generator yields owned
+/// values, and the most recent one is stored inside the iterator, so that
+/// [`get`](StreamingIterator::get) can borrow it until the next
+/// [`advance`](StreamingIterator::advance). The generator itself can not
+/// yield references into its own state.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// extern crate gen_iter;
+/// extern crate streaming_iterator;
+///
+/// use gen_iter::{gen_iter, GenStreamingIter};
+/// use streaming_iterator::StreamingIterator;
+///
+/// let mut it = GenStreamingIter::new(gen_iter!({
+///     let mut line = String::new();
+///     for word in ["a", "b", "c"] {
+///         line.push_str(word);
+///         yield line.clone();
+///     }
+/// }));
+///
+/// assert_eq!(it.next().map(String::as_str), Some("a"));
+/// assert_eq!(it.next().map(String::as_str), Some("ab"));
+/// assert_eq!(it.get().map(String::as_str), Some("ab"));
+/// assert_eq!(it.next().map(String::as_str), Some("abc"));
+/// assert_eq!(it.next(), None);
+/// ```
+pub struct GenStreamingIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    gen: Fuse<GenIter<G>>,
+    item: Option<G::Yield>,
+}
+
+impl<G> GenStreamingIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    #[inline]
+    pub fn new(g: GenIter<G>) -> Self {
+        GenStreamingIter { gen: g.fuse(), item: None }
+    }
+}
+
+impl<G> StreamingIterator for GenStreamingIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    type Item = G::Yield;
+
+    #[inline]
+    fn advance(&mut self) {
+        self.item = self.gen.next();
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&G::Yield> {
+        self.item.as_ref()
+    }
+}
+
+impl<G> StreamingIteratorMut for GenStreamingIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    #[inline]
+    fn get_mut(&mut self) -> Option<&mut G::Yield> {
+        self.item.as_mut()
+    }
+}
+
+// the item is never pinned
+impl<G> Unpin for GenStreamingIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{}
+
+impl<G> From<GenIter<G>> for GenStreamingIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    #[inline]
+    fn from(g: GenIter<G>) -> Self {
+        GenStreamingIter::new(g)
+    }
+}
+
+impl<G> fmt::Debug for GenStreamingIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+    G::Yield: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GenStreamingIter")
+            .field("gen", &self.gen)
+            .field("item", &self.item)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GenStreamingIter;
+    use crate::gen_iter;
+    use std::vec::Vec;
+    use streaming_iterator::{StreamingIterator, StreamingIteratorMut};
+
+    #[test]
+    fn map_fold() {
+        let windows = || gen_iter!({
+            let mut buf = Vec::new();
+            for i in 1..=5u32 {
+                buf.push(i);
+                yield buf.clone();
+            }
+        });
+
+        let sum = GenStreamingIter::new(windows())
+            .map(|w: &Vec<u32>| w.iter().sum::<u32>())
+            .fold(0, |acc, s| acc + s);
+        let expected = windows().map(|w| w.iter().sum::<u32>()).sum::<u32>();
+        assert_eq!(sum, expected);
+        assert_eq!(sum, 1 + 3 + 6 + 10 + 15);
+
+        let lens = GenStreamingIter::new(windows())
+            .filter(|w| w.len() % 2 == 0)
+            .fold(Vec::new(), |mut v, w| {
+                v.push(w.len());
+                v
+            });
+        assert_eq!(lens, [2, 4]);
+    }
+
+    #[test]
+    fn slot() {
+        let mut it = GenStreamingIter::new(gen_iter!({
+            yield 1;
+            yield 2;
+        }));
+
+        assert_eq!(it.get(), None);
+        it.advance();
+        assert_eq!(it.get(), Some(&1));
+        *it.get_mut().unwrap() += 10;
+        assert_eq!(it.get(), Some(&11));
+
+        assert_eq!(it.next(), Some(&2));
+        assert_eq!(it.next(), None);
+        assert!(it.is_done());
+        // fused
+        assert_eq!(it.next(), None);
+    }
+}
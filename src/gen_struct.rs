@@ -0,0 +1,192 @@
+use core::ops::Coroutine;
+use core::pin::Pin;
+
+use alloc::boxed::Box;
+
+use crate::OwningGenIter;
+
+#[doc(hidden)]
+pub type __GenStruct<'a, Y> = OwningGenIter<dyn Coroutine<Yield = Y, Return = ()> + 'a>;
+
+#[doc(hidden)]
+#[inline]
+pub fn __gen_struct<'a, G>(gen: G) -> __GenStruct<'a, G::Yield>
+where
+    G: Coroutine<Return = ()> + 'a,
+{
+    let gen: Pin<Box<dyn Coroutine<Yield = G::Yield, Return = ()> + 'a>> = Box::pin(gen);
+    OwningGenIter::from(gen)
+}
+
+/// macro to declare an iterator struct over a generator, a type that can be named,
+/// unlike `impl Iterator`, like in a field or an associated type.
+///
+/// `gen_struct!(struct Name(fields) yields Y { ... })` declares the struct `Name`
+/// with a constructor `new(fields)` moving the fields into a `static` generator
+/// running the block, which can borrow them across its yields. The generator is
+/// pinned in a `Box`, behind a `dyn` type. The struct can have one lifetime
+/// parameter, for the fields to borrow data. It is neither `Send` nor `Sync`, and
+/// not fused, like [`OwningGenIter`].
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_struct;
+///
+/// gen_struct! {
+///     /// the words of a text and their lengths
+///     pub struct Words(text: String) yields (usize, String) {
+///         for word in text.split(' ') {
+///             yield (word.len(), word.to_string());
+///         }
+///     }
+/// }
+///
+/// struct Document {
+///     words: Words,
+/// }
+///
+/// let mut doc = Document { words: Words::new("a generator struct".into()) };
+/// assert_eq!(doc.words.next(), Some((1, "a".to_string())));
+/// assert_eq!(doc.words.map(|(n, _)| n).collect::<Vec<_>>(), [9, 6]);
+/// ```
+#[macro_export]
+macro_rules! gen_struct {
+    (
+        $(#[$attr: meta])*
+        $vis: vis struct $name: ident <$lt: lifetime> ($($field: ident: $ty: ty),* $(,)?) yields $y: ty $body: block
+    ) => {
+        $(#[$attr])*
+        $vis struct $name<$lt>($crate::__GenStruct<$lt, $y>);
+
+        impl<$lt> $name<$lt> {
+            #[allow(clippy::new_without_default)]
+            #[inline]
+            $vis fn new($($field: $ty),*) -> Self {
+                $name($crate::__gen_struct(#[coroutine] static move || {
+                    $(let $field = $field;)*
+                    $body
+                }))
+            }
+        }
+
+        impl<$lt> ::core::iter::Iterator for $name<$lt> {
+            type Item = $y;
+
+            #[inline]
+            fn next(&mut self) -> ::core::option::Option<$y> {
+                self.0.next()
+            }
+        }
+
+        impl<$lt> ::core::fmt::Debug for $name<$lt> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                f.debug_struct(::core::stringify!($name)).finish_non_exhaustive()
+            }
+        }
+    };
+    (
+        $(#[$attr: meta])*
+        $vis: vis struct $name: ident ($($field: ident: $ty: ty),* $(,)?) yields $y: ty $body: block
+    ) => {
+        $(#[$attr])*
+        $vis struct $name($crate::__GenStruct<'static, $y>);
+
+        impl $name {
+            #[allow(clippy::new_without_default)]
+            #[inline]
+            $vis fn new($($field: $ty),*) -> Self {
+                $name($crate::__gen_struct(#[coroutine] static move || {
+                    $(let $field = $field;)*
+                    $body
+                }))
+            }
+        }
+
+        impl ::core::iter::Iterator for $name {
+            type Item = $y;
+
+            #[inline]
+            fn next(&mut self) -> ::core::option::Option<$y> {
+                self.0.next()
+            }
+        }
+
+        impl ::core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                f.debug_struct(::core::stringify!($name)).finish_non_exhaustive()
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::format;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+
+    gen_struct! {
+        /// counts down from `from` to 1
+        struct Countdown(from: u32) yields u32 {
+            for i in (1..=from).rev() {
+                yield i;
+            }
+        }
+    }
+
+    gen_struct! {
+        struct Lines<'a>(text: &'a str, prefix: String) yields String {
+            for line in text.lines() {
+                // borrows `prefix` across the yields
+                let p = &prefix;
+                yield format!("{}{}", p, line);
+            }
+        }
+    }
+
+    trait Source {
+        type Iter: Iterator<Item = u32>;
+
+        fn items(&self) -> Self::Iter;
+    }
+
+    struct Ten;
+
+    impl Source for Ten {
+        type Iter = Countdown;
+
+        fn items(&self) -> Countdown {
+            Countdown::new(10)
+        }
+    }
+
+    struct Holder {
+        countdown: Countdown,
+        seen: Vec<u32>,
+    }
+
+    #[test]
+    fn associated_type() {
+        let it = Ten.items();
+        assert_eq!(it.sum::<u32>(), 55);
+    }
+
+    #[test]
+    fn struct_field() {
+        let mut h = Holder { countdown: Countdown::new(3), seen: Vec::new() };
+        if let Some(x) = h.countdown.next() {
+            h.seen.push(x);
+        }
+        h.seen.extend(&mut h.countdown);
+        assert_eq!(h.seen, [3, 2, 1]);
+        assert_eq!(format!("{:?}", h.countdown), "Countdown { .. }");
+    }
+
+    #[test]
+    fn with_lifetime() {
+        let text = String::from("a\nb");
+        let lines = Lines::new(&text, "> ".to_string());
+        assert_eq!(lines.collect::<Vec<_>>(), ["> a", "> b"]);
+    }
+}
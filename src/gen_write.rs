@@ -0,0 +1,241 @@
+use core::ops::Coroutine;
+use core::marker::Unpin;
+use core::fmt;
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::{GenSink, SinkComplete};
+
+/// `GenWrite<G>` feeds the written bytes to a consuming generator, through
+/// - `std::io::Write` with the `std` feature
+/// - `embedded_io::Write` with the `embedded-io` and `alloc` features, without `std`
+///
+/// Every non empty write copies the caller's bytes into a `Vec<u8>`,
+/// and resumes the generator once with `Some(bytes)`. Like [`GenSink`],
+/// the generator yields `()` when it is ready for the next bytes,
+/// and [`finish`](GenWrite::finish) resumes it with `None` until it completes.
+/// Flushing does nothing.
+///
+/// Writing after the generator completed fails, with `std::io::ErrorKind::WriteZero`
+/// or [`SinkComplete`].
+pub struct GenWrite<G>(GenSink<G, Vec<u8>>)
+where
+    G: Coroutine<Option<Vec<u8>>, Yield = ()> + Unpin;
+
+impl<G> GenWrite<G>
+where
+    G: Coroutine<Option<Vec<u8>>, Yield = ()> + Unpin,
+{
+    #[inline]
+    pub fn new(g: G) -> Self {
+        GenWrite(GenSink::new(g))
+    }
+
+    /// whether the generator is complete and refuses further bytes
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.0.is_complete()
+    }
+
+    /// signal the end of the bytes, and get the return value of the generator
+    #[inline]
+    pub fn finish(self) -> G::Return {
+        self.0.finish()
+    }
+
+    fn write_bytes(&mut self, buf: &[u8]) -> Result<usize, SinkComplete> {
+        if self.is_complete() {
+            return Err(SinkComplete);
+        }
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.0.push(buf.to_vec()).map_err(|_| SinkComplete)?;
+        Ok(buf.len())
+    }
+}
+
+/// writes after the generator completed fail with `WriteZero`
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::GenWrite;
+/// use std::io::Write;
+///
+/// let mut w = GenWrite::new(#[coroutine] |mut bytes: Option<Vec<u8>>| {
+///     let mut sum = 0u32;
+///     while let Some(b) = bytes {
+///         sum += b.iter().map(|&b| b as u32).sum::<u32>();
+///         bytes = yield;
+///     }
+///     sum
+/// });
+///
+/// w.write_all(&[1, 2, 3]).unwrap();
+/// write!(w, "{}", 0).unwrap();
+/// assert_eq!(w.finish(), 6 + b'0' as u32);
+/// ```
+#[cfg(feature = "std")]
+impl<G> io::Write for GenWrite<G>
+where
+    G: Coroutine<Option<Vec<u8>>, Yield = ()> + Unpin,
+{
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_bytes(buf)
+            .map_err(|_| io::Error::new(io::ErrorKind::WriteZero, "the generator of the writer is complete"))
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<G> embedded_io::ErrorType for GenWrite<G>
+where
+    G: Coroutine<Option<Vec<u8>>, Yield = ()> + Unpin,
+{
+    type Error = SinkComplete;
+}
+
+#[cfg(feature = "embedded-io")]
+impl<G> embedded_io::Write for GenWrite<G>
+where
+    G: Coroutine<Option<Vec<u8>>, Yield = ()> + Unpin,
+{
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, SinkComplete> {
+        self.write_bytes(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), SinkComplete> {
+        Ok(())
+    }
+}
+
+impl<G> From<G> for GenWrite<G>
+where
+    G: Coroutine<Option<Vec<u8>>, Yield = ()> + Unpin,
+{
+    #[inline]
+    fn from(g: G) -> Self {
+        GenWrite::new(g)
+    }
+}
+
+impl<G> fmt::Debug for GenWrite<G>
+where
+    G: Coroutine<Option<Vec<u8>>, Yield = ()> + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("GenWrite").field(&self.0).finish()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::GenWrite;
+    use std::io::{self, Write};
+    use std::vec::Vec;
+
+    /// a length prefixed framing of every write, and the total of bytes
+    fn framing() -> GenWrite<impl core::ops::Coroutine<Option<Vec<u8>>, Yield = (), Return = (Vec<u8>, usize)> + Unpin> {
+        GenWrite::new(#[coroutine] |mut bytes: Option<Vec<u8>>| {
+            let mut framed = Vec::new();
+            let mut total = 0;
+            while let Some(b) = bytes {
+                framed.push(b.len() as u8);
+                framed.extend_from_slice(&b);
+                total += b.len();
+                bytes = yield;
+            }
+            (framed, total)
+        })
+    }
+
+    #[test]
+    fn write_all() {
+        let mut w = framing();
+        w.write_all(b"ab").unwrap();
+        w.write_all(b"").unwrap();
+        w.write_all(b"cde").unwrap();
+        w.flush().unwrap();
+        assert!(!w.is_complete());
+
+        assert_eq!(w.finish(), (b"\x02ab\x03cde".to_vec(), 5));
+    }
+
+    #[test]
+    fn copy_into() {
+        let data = (0..=255u8).cycle().take(20_000).collect::<Vec<_>>();
+        let mut w = GenWrite::new(#[coroutine] |mut bytes: Option<Vec<u8>>| {
+            let mut all = Vec::new();
+            while let Some(b) = bytes {
+                all.extend(b);
+                bytes = yield;
+            }
+            all
+        });
+
+        assert_eq!(io::copy(&mut &data[..], &mut w).unwrap(), 20_000);
+        assert_eq!(w.finish(), data);
+    }
+
+    #[test]
+    fn write_after_complete() {
+        let mut w = GenWrite::new(#[coroutine] |first: Option<Vec<u8>>| {
+            let second = yield;
+            (first, second)
+        });
+
+        w.write_all(b"a").unwrap();
+        w.write_all(b"b").unwrap();
+        assert!(w.is_complete());
+        assert_eq!(w.write(b"c").unwrap_err().kind(), io::ErrorKind::WriteZero);
+        assert_eq!(write!(w, "{}", 1).unwrap_err().kind(), io::ErrorKind::WriteZero);
+        assert_eq!(w.finish(), (Some(b"a".to_vec()), Some(b"b".to_vec())));
+    }
+}
+
+#[cfg(all(test, feature = "embedded-io"))]
+mod embedded_io_tests {
+    use super::GenWrite;
+    use crate::SinkComplete;
+    use alloc::vec::Vec;
+    use embedded_io::Write;
+
+    #[test]
+    fn write_all_and_fmt() {
+        let mut w = GenWrite::new(#[coroutine] |mut bytes: Option<Vec<u8>>| {
+            let mut all = Vec::new();
+            while let Some(b) = bytes {
+                all.extend(b);
+                bytes = yield;
+            }
+            all
+        });
+
+        w.write_all(b"abc").unwrap();
+        write!(w, "{}-{}", 1, 2).unwrap();
+        w.flush().unwrap();
+        assert_eq!(w.finish(), b"abc1-2");
+    }
+
+    #[test]
+    fn write_after_complete() {
+        let mut w = GenWrite::new(#[coroutine] |first: Option<Vec<u8>>| first);
+
+        assert_eq!(w.write(b"a"), Ok(1));
+        assert_eq!(w.write(b"b"), Err(SinkComplete));
+        assert_eq!(w.write_all(b"c"), Err(SinkComplete));
+        assert_eq!(w.finish(), Some(b"a".to_vec()));
+    }
+}
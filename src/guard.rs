@@ -0,0 +1,152 @@
+//! cleanup guards for generator bodies.
+//!
+//! A generator dropped by its consumer at a yield never runs the code after it,
+//! only the destructors of its locals. These guards are such locals:
+//! [`defer`] runs a closure whenever the generator is dropped or completes, and
+//! [`defer_on_incomplete`] only if it is dropped before calling
+//! [`complete`](DeferOnIncomplete::complete).
+//!
+//! ```
+//! #![feature(coroutines, stmt_expr_attributes)]
+//!
+//! use gen_iter::gen_iter;
+//! use gen_iter::guard::{defer, defer_on_incomplete};
+//! use std::cell::RefCell;
+//!
+//! let log = RefCell::new(Vec::new());
+//! let mut it = gen_iter!({
+//!     let _close = defer(|| log.borrow_mut().push("closed"));
+//!     let rollback = defer_on_incomplete(|| log.borrow_mut().push("rolled back"));
+//!     yield 1;
+//!     yield 2;
+//!     rollback.complete();
+//! });
+//!
+//! assert_eq!(it.next(), Some(1));
+//! drop(it);
+//! assert_eq!(*log.borrow(), ["rolled back", "closed"]);
+//! ```
+
+use core::fmt;
+
+/// a guard running a closure when dropped, created by [`defer`]
+#[must_use = "the closure runs when the guard is dropped, right away if it is not bound"]
+pub struct Defer<F: FnOnce()> {
+    f: Option<F>,
+}
+
+impl<F: FnOnce()> Drop for Defer<F> {
+    fn drop(&mut self) {
+        if let Some(f) = self.f.take() {
+            f();
+        }
+    }
+}
+
+impl<F: FnOnce()> fmt::Debug for Defer<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Defer").finish_non_exhaustive()
+    }
+}
+
+/// run `f` when the returned guard is dropped, when the generator completes
+/// or is dropped at a yield
+#[inline]
+pub fn defer<F: FnOnce()>(f: F) -> Defer<F> {
+    Defer { f: Some(f) }
+}
+
+/// a guard running a closure when dropped, unless it was completed,
+/// created by [`defer_on_incomplete`]
+#[must_use = "the closure runs when the guard is dropped, right away if it is not bound"]
+pub struct DeferOnIncomplete<F: FnOnce()> {
+    f: Option<F>,
+}
+
+impl<F: FnOnce()> DeferOnIncomplete<F> {
+    /// disarm the guard, the closure is never called
+    #[inline]
+    pub fn complete(mut self) {
+        self.f = None;
+    }
+}
+
+impl<F: FnOnce()> Drop for DeferOnIncomplete<F> {
+    fn drop(&mut self) {
+        if let Some(f) = self.f.take() {
+            f();
+        }
+    }
+}
+
+impl<F: FnOnce()> fmt::Debug for DeferOnIncomplete<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DeferOnIncomplete")
+            .field("completed", &self.f.is_none())
+            .finish()
+    }
+}
+
+/// run `f` when the returned guard is dropped, unless
+/// [`complete`](DeferOnIncomplete::complete) was called before,
+/// like at the end of the generator
+#[inline]
+pub fn defer_on_incomplete<F: FnOnce()>(f: F) -> DeferOnIncomplete<F> {
+    DeferOnIncomplete { f: Some(f) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{defer, defer_on_incomplete};
+    use crate::GenIter;
+    use core::cell::Cell;
+
+    #[test]
+    fn dropped_mid_way() {
+        let closed = Cell::new(0);
+        let rolled_back = Cell::new(0);
+        let mut it = GenIter(#[coroutine] || {
+            let _close = defer(|| closed.set(closed.get() + 1));
+            let rollback = defer_on_incomplete(|| rolled_back.set(rolled_back.get() + 1));
+            for i in 0..3 {
+                yield i;
+            }
+            rollback.complete();
+        });
+        assert_eq!(it.next(), Some(0));
+        assert_eq!(it.next(), Some(1));
+        assert_eq!((closed.get(), rolled_back.get()), (0, 0));
+
+        drop(it);
+        assert_eq!((closed.get(), rolled_back.get()), (1, 1));
+    }
+
+    #[test]
+    fn fully_consumed() {
+        let closed = Cell::new(0);
+        let rolled_back = Cell::new(0);
+        let it = GenIter(#[coroutine] || {
+            let _close = defer(|| closed.set(closed.get() + 1));
+            let rollback = defer_on_incomplete(|| rolled_back.set(rolled_back.get() + 1));
+            for i in 0..3 {
+                yield i;
+            }
+            rollback.complete();
+        });
+        assert_eq!(it.sum::<u32>(), 3);
+        // `defer` runs on completion too
+        assert_eq!((closed.get(), rolled_back.get()), (1, 0));
+    }
+
+    #[test]
+    fn never_started() {
+        let closed = Cell::new(0);
+        let it = GenIter(#[coroutine] || {
+            let _close = defer(|| closed.set(closed.get() + 1));
+            yield 1;
+        });
+        // the guard does not exist before the first resume
+        drop(it);
+        assert_eq!(closed.get(), 0);
+    }
+}
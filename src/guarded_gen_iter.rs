@@ -0,0 +1,194 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::panic::Location;
+use core::fmt;
+
+use crate::GenIterReturn;
+
+/// `GuardedGenIter<G>` is a [`GenIterReturn`] that must be driven to completion,
+/// like a generator running a protocol that cannot be abandoned half way.
+///
+/// created by [`GenIterReturn::must_complete`].
+///
+/// Dropping it before the generator completed is a bug: it panics with
+/// debug assertions, and prints a warning to stderr without them if `std` is enabled.
+/// Both messages tell where the `GuardedGenIter` was created. There is no panic
+/// if the thread is already panicking, with `std`.
+///
+/// [`into_inner`](GuardedGenIter::into_inner) takes the generator back
+/// without the check, to abandon it on purpose.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter_return;
+///
+/// let mut tx = gen_iter_return!({
+///     yield "begin";
+///     yield "update";
+///     "commit"
+/// }).must_complete();
+///
+/// assert_eq!((&mut tx).count(), 2);
+/// assert_eq!(tx.return_or_self().ok(), Some("commit"));
+/// ```
+pub struct GuardedGenIter<G: Coroutine + Unpin> {
+    gen: Option<GenIterReturn<G>>,
+    created: &'static Location<'static>,
+}
+
+impl<G: Coroutine + Unpin> GuardedGenIter<G> {
+    #[inline]
+    fn gen(&self) -> &GenIterReturn<G> {
+        // only `None` while dropping or being taken apart
+        self.gen.as_ref().unwrap()
+    }
+
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.gen().is_done()
+    }
+
+    /// same as [`GenIterReturn::return_or_self`]
+    pub fn return_or_self(mut self) -> Result<G::Return, Self> {
+        match self.gen.take().unwrap().return_or_self() {
+            Ok(r) => Ok(r),
+            Err(g) => {
+                self.gen = Some(g);
+                Err(self)
+            },
+        }
+    }
+
+    /// where this `GuardedGenIter` was created
+    #[inline]
+    pub fn location(&self) -> &'static Location<'static> {
+        self.created
+    }
+
+    /// get back the generator, which can then be dropped before its completion
+    #[inline]
+    pub fn into_inner(mut self) -> GenIterReturn<G> {
+        self.gen.take().unwrap()
+    }
+}
+
+impl<G: Coroutine + Unpin> Iterator for &mut GuardedGenIter<G> {
+    type Item = G::Yield;
+
+    #[inline]
+    fn next(&mut self) -> Option<G::Yield> {
+        self.gen.as_mut().and_then(|mut g| g.next())
+    }
+}
+
+impl<G: Coroutine + Unpin> FusedIterator for &mut GuardedGenIter<G> {}
+
+impl<G: Coroutine + Unpin> Drop for GuardedGenIter<G> {
+    fn drop(&mut self) {
+        match self.gen {
+            Some(ref g) if !g.is_done() => abandoned(self.created),
+            _ => {},
+        }
+    }
+}
+
+/// report a `GuardedGenIter` dropped before its completion
+fn abandoned(created: &'static Location<'static>) {
+    #[cfg(any(test, feature = "std"))]
+    if std::thread::panicking() {
+        return;
+    }
+    #[cfg(debug_assertions)]
+    panic!("the generator of the GuardedGenIter created at {} was dropped before its completion", created);
+    #[cfg(all(not(debug_assertions), any(test, feature = "std")))]
+    std::eprintln!("warning: the generator of the GuardedGenIter created at {} was dropped before its completion", created);
+    // nowhere to report it
+    #[cfg(not(any(debug_assertions, test, feature = "std")))]
+    let _ = created;
+}
+
+impl<G> fmt::Debug for GuardedGenIter<G>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GuardedGenIter")
+            .field("gen", &self.gen)
+            .field("created", &self.created)
+            .finish()
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// check that this generator is driven to completion, see [`GuardedGenIter`]
+    #[inline]
+    #[track_caller]
+    pub fn must_complete(self) -> GuardedGenIter<G> {
+        GuardedGenIter { gen: Some(self), created: Location::caller() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIterReturn;
+    use std::panic::{self, AssertUnwindSafe};
+
+    fn transaction() -> GenIterReturn<impl core::ops::Coroutine<Yield = u32, Return = &'static str> + Unpin> {
+        GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            yield 2;
+            "commit"
+        })
+    }
+
+    #[test]
+    fn completed() {
+        let result = panic::catch_unwind(|| {
+            let mut g = transaction().must_complete();
+            assert_eq!((&mut g).sum::<u32>(), 3);
+            assert!(g.is_done());
+            g.return_or_self().ok()
+        });
+        assert_eq!(result.unwrap(), Some("commit"));
+
+        // dropping a completed generator is fine too
+        assert!(panic::catch_unwind(|| {
+            let mut g = transaction().must_complete();
+            (&mut g).for_each(drop);
+        }).is_ok());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn dropped_early() {
+        let line = line!() + 2;
+        let err = panic::catch_unwind(|| {
+            let mut g = transaction().must_complete();
+            assert_eq!((&mut g).next(), Some(1));
+            assert_eq!(g.location().line(), line);
+        }).unwrap_err();
+        let msg = err.downcast::<std::string::String>().unwrap();
+        assert!(msg.contains(&std::format!("{}:{}:", file!(), line)), "{}", msg);
+
+        // `return_or_self` does not complete it
+        let err = panic::catch_unwind(|| {
+            let g = transaction().must_complete();
+            assert!(g.return_or_self().is_err());
+        });
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn opt_out() {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut g = transaction().must_complete();
+            assert_eq!((&mut g).next(), Some(1));
+            let mut g = g.into_inner();
+            assert_eq!((&mut g).next(), Some(2));
+        }));
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,194 @@
+use core::ops::Coroutine;
+use core::iter::Iterator;
+use core::marker::Unpin;
+
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
+
+use crate::{GenIter, GenIterReturn};
+
+/// spawn a thread calling `next` on `state` and sending the items,
+/// until there are no more items or the receiver is dropped.
+/// the thread gives back `finish(state)`
+fn spawn_sending<S, Y, T, N, F>(mut state: S, bound: Option<usize>, mut next: N, finish: F) -> (Receiver<Y>, JoinHandle<T>)
+where
+    S: Send + 'static,
+    Y: Send + 'static,
+    T: Send + 'static,
+    N: FnMut(&mut S) -> Option<Y> + Send + 'static,
+    F: FnOnce(S) -> T + Send + 'static,
+{
+    match bound {
+        Some(bound) => {
+            let (tx, rx) = mpsc::sync_channel(bound);
+            (rx, thread::spawn(move || {
+                while let Some(y) = next(&mut state) {
+                    if tx.send(y).is_err() {
+                        break;
+                    }
+                }
+                finish(state)
+            }))
+        },
+        None => {
+            let (tx, rx) = mpsc::channel();
+            (rx, thread::spawn(move || {
+                while let Some(y) = next(&mut state) {
+                    if tx.send(y).is_err() {
+                        break;
+                    }
+                }
+                finish(state)
+            }))
+        },
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// move the generator to its own thread, sending its yields into a channel.
+    ///
+    /// with `bound = Some(n)` the channel holds at most `n` yields, the generator
+    /// is blocked until the receiver catches up. `Some(0)` means every yield waits
+    /// for its `recv`. with `None` the channel is unbounded and the generator
+    /// never blocks.
+    ///
+    /// the thread stops when the generator completes, or when the receiver
+    /// is dropped: the yield that could not be sent is lost.
+    /// a panic of the generator is returned by `join`.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_iter;
+    ///
+    /// let (rx, handle) = gen_iter!({
+    ///     yield 1;
+    ///     yield 2;
+    /// }).into_channel(Some(1));
+    ///
+    /// assert_eq!(rx.iter().collect::<Vec<_>>(), [1, 2]);
+    /// handle.join().unwrap();
+    /// ```
+    pub fn into_channel(self, bound: Option<usize>) -> (Receiver<G::Yield>, JoinHandle<()>)
+    where
+        G: Send + 'static,
+        G::Yield: Send + 'static,
+    {
+        spawn_sending(self, bound, Iterator::next, drop)
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// like [`GenIter::into_channel`], the thread gives back the return value
+    /// of the generator, or the generator itself if the receiver was dropped
+    /// before it completed.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_iter_return;
+    ///
+    /// let (rx, handle) = gen_iter_return!({
+    ///     yield 1;
+    ///     yield 2;
+    ///     return "done";
+    /// }).into_channel(None);
+    ///
+    /// assert_eq!(rx.iter().collect::<Vec<_>>(), [1, 2]);
+    /// assert_eq!(handle.join().unwrap().ok(), Some("done"));
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn into_channel(self, bound: Option<usize>) -> (Receiver<G::Yield>, JoinHandle<Result<G::Return, Self>>)
+    where
+        G: Send + 'static,
+        G::Yield: Send + 'static,
+        G::Return: Send + 'static,
+    {
+        spawn_sending(self, bound, |g| (&mut *g).next(), GenIterReturn::return_or_self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, GenIterReturn};
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::thread;
+    use std::time::Duration;
+    use std::vec::Vec;
+
+    #[test]
+    fn consume_on_other_thread() {
+        let (rx, handle) = GenIterReturn::new(#[coroutine] || {
+            let mut sum = 0;
+            for i in 0..100u64 {
+                sum += i;
+                yield i;
+            }
+            sum
+        }).into_channel(Some(4));
+
+        let consumer = thread::spawn(move || rx.iter().collect::<Vec<_>>());
+        assert_eq!(consumer.join().unwrap(), (0..100).collect::<Vec<_>>());
+        assert_eq!(handle.join().unwrap().ok(), Some(4950));
+    }
+
+    #[test]
+    fn unbounded_never_blocks() {
+        let (rx, handle) = GenIter(#[coroutine] || {
+            for i in 0..1000 {
+                yield i;
+            }
+        }).into_channel(None);
+
+        // everything was sent without anyone receiving
+        handle.join().unwrap();
+        assert_eq!(rx.iter().count(), 1000);
+    }
+
+    #[test]
+    fn bounded_blocks() {
+        let (rx, handle) = GenIter(#[coroutine] || {
+            for i in 0..10 {
+                yield i;
+            }
+        }).into_channel(Some(2));
+
+        thread::sleep(Duration::from_millis(20));
+        assert!(!handle.is_finished());
+        assert_eq!(rx.iter().count(), 10);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn receiver_dropped() {
+        let (rx, handle) = GenIterReturn::new(#[coroutine] || {
+            let mut i = 0;
+            loop {
+                i += 1;
+                yield i;
+            }
+        }).into_channel(Some(0));
+
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok(1));
+        drop(rx);
+        let mut g = handle.join().unwrap().unwrap_err();
+        assert!(!g.is_done());
+        // the generator is given back, the yield that could not be sent is lost
+        assert_eq!((&mut g).next(), Some(3));
+    }
+
+    #[test]
+    fn panic_in_join() {
+        let (rx, handle) = GenIter(#[coroutine] || {
+            yield 1;
+            panic!("generator failed");
+        }).into_channel(None);
+
+        assert_eq!(rx.recv(), Ok(1));
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Err(RecvTimeoutError::Disconnected));
+        assert!(handle.join().is_err());
+    }
+}
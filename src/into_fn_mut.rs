@@ -0,0 +1,99 @@
+use core::ops::Coroutine;
+use core::marker::Unpin;
+
+#[cfg(feature = "alloc")]
+use crate::ReturnSlot;
+use crate::GenIterExchange;
+
+impl<G: Coroutine<R> + Unpin, R> GenIterExchange<G, R> {
+    /// turn the generator into a closure, every call resumes it once.
+    ///
+    /// the closure returns `None` once the generator is complete, and keeps
+    /// returning `None` when called after that. the return value is dropped,
+    /// see `into_fn_mut_with_return` (with `alloc`) to keep it.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_exchange;
+    ///
+    /// let mut f = gen_exchange!(|mut x: u32| {
+    ///     while x != 0 {
+    ///         x = yield x * 2;
+    ///     }
+    /// }).into_fn_mut();
+    ///
+    /// assert_eq!(f(1), Some(2));
+    /// assert_eq!(f(0), None);
+    /// assert_eq!(f(1), None);
+    /// ```
+    #[inline]
+    pub fn into_fn_mut(mut self) -> impl FnMut(R) -> Option<G::Yield> {
+        move |arg| self.next_with(arg)
+    }
+
+    /// like [`into_fn_mut`](GenIterExchange::into_fn_mut),
+    /// the return value goes into the [`ReturnSlot`] once the generator completes.
+    #[cfg(feature = "alloc")]
+    pub fn into_fn_mut_with_return(self) -> (impl FnMut(R) -> Option<G::Yield>, ReturnSlot<G::Return>) {
+        let slot = ReturnSlot::new();
+        let ret = slot.clone();
+
+        let mut exchange = Some(self);
+        let f = move |arg| {
+            let y = exchange.as_mut()?.next_with(arg);
+            if y.is_none() {
+                if let Some(Ok(r)) = exchange.take().map(GenIterExchange::return_or_self) {
+                    slot.set(r);
+                }
+            }
+            y
+        };
+
+        (f, ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIterExchange;
+
+    /// a callback based api, calls `f` with `0..n` and counts the `Some`s
+    fn visit<F: FnMut(u32) -> Option<u32>>(n: u32, mut f: F) -> usize {
+        (0..n).filter_map(&mut f).count()
+    }
+
+    #[test]
+    fn into_fn_mut() {
+        let f = GenIterExchange::new(#[coroutine] |mut x: u32| {
+            for _ in 0..3 {
+                x = yield x + 1;
+            }
+        }).into_fn_mut();
+
+        assert_eq!(visit(10, f), 3);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn into_fn_mut_with_return() {
+        let (mut f, ret) = GenIterExchange::new(#[coroutine] |mut x: u32| {
+            let mut sum = 0;
+            for _ in 0..3 {
+                sum += x;
+                x = yield x + 1;
+            }
+            sum
+        }).into_fn_mut_with_return();
+
+        assert_eq!(f(1), Some(2));
+        assert!(!ret.is_set());
+
+        assert_eq!(visit(10, &mut f), 2);
+        assert!(ret.is_set());
+        assert_eq!(f(5), None);
+
+        assert_eq!(ret.take(), Some(2));
+        assert_eq!(ret.take(), None);
+    }
+}
@@ -0,0 +1,161 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::fmt;
+
+use js_sys::{Function, Object, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+
+/// `JsCoroutine` is a [`Coroutine`] over a JavaScript iterator, like a JavaScript
+/// generator object: every resume calls its `next()` method.
+///
+/// `{ value, done: false }` is a yield of `value`, `{ value, done: true }` completes
+/// with `value`, the return value of a JavaScript generator. Wrap it in a
+/// [`GenIterReturn`](crate::GenIterReturn) to iterate over the yields
+/// and then get the return value.
+///
+/// An exception thrown by `next()`, or a `next()` not returning an object, panics.
+/// [`catching`](JsCoroutine::catching) yields them as errors instead.
+///
+/// ```no_run
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// extern crate gen_iter;
+/// extern crate js_sys;
+/// extern crate wasm_bindgen;
+///
+/// use gen_iter::{GenIterReturn, JsCoroutine};
+/// use wasm_bindgen::prelude::*;
+///
+/// // sum(function* () { yield 1; yield 2; return "done" }())
+/// #[wasm_bindgen]
+/// pub fn sum(generator: js_sys::Object) -> JsValue {
+///     let mut g = GenIterReturn::new(JsCoroutine::new(generator));
+///     let sum: f64 = (&mut g).filter_map(|v| v.as_f64()).sum();
+///     let ret = g.return_or_self().ok().unwrap();
+///     JsValue::from(format!("{}: {:?}", sum, ret.as_string()))
+/// }
+/// ```
+pub struct JsCoroutine {
+    iter: Object,
+    next: Function,
+}
+
+impl JsCoroutine {
+    /// wrap a JavaScript iterator.
+    ///
+    /// panics if `iter` has no `next` method.
+    pub fn new(iter: Object) -> Self {
+        let next = Reflect::get(&iter, &JsValue::from_str("next"))
+            .ok()
+            .and_then(|f| f.dyn_into::<Function>().ok());
+        match next {
+            Some(next) => JsCoroutine { iter, next },
+            None => panic!("the JavaScript object has no next method"),
+        }
+    }
+
+    /// yield the exceptions of `next()` as errors, see [`TryJsCoroutine`]
+    #[inline]
+    pub fn catching(self) -> TryJsCoroutine {
+        TryJsCoroutine { js: Some(self) }
+    }
+
+    /// get back the JavaScript iterator
+    #[inline]
+    pub fn into_inner(self) -> Object {
+        self.iter
+    }
+
+    /// call `next()`, translating its `{ value, done }`
+    fn step(&self) -> Result<CoroutineState<JsValue, JsValue>, JsValue> {
+        let result = self.next.call0(&self.iter)?;
+        if !result.is_object() {
+            return Err(js_sys::TypeError::new("iterator.next() returned a non-object value").into());
+        }
+        let value = Reflect::get(&result, &JsValue::from_str("value"))?;
+        let done = Reflect::get(&result, &JsValue::from_str("done"))?;
+        if done.is_truthy() {
+            Ok(CoroutineState::Complete(value))
+        } else {
+            Ok(CoroutineState::Yielded(value))
+        }
+    }
+}
+
+// the JavaScript handles are never pinned
+impl Unpin for JsCoroutine {}
+
+impl Coroutine for JsCoroutine {
+    type Yield = JsValue;
+    type Return = JsValue;
+
+    fn resume(self: Pin<&mut Self>, _: ()) -> CoroutineState<JsValue, JsValue> {
+        match self.step() {
+            Ok(state) => state,
+            Err(e) => panic!("the JavaScript iterator threw {:?}", e),
+        }
+    }
+}
+
+impl From<Object> for JsCoroutine {
+    #[inline]
+    fn from(iter: Object) -> Self {
+        JsCoroutine::new(iter)
+    }
+}
+
+impl fmt::Debug for JsCoroutine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("JsCoroutine").field(&self.iter).finish()
+    }
+}
+
+/// `TryJsCoroutine` is a [`JsCoroutine`] yielding `Ok(value)`, and `Err(exception)`
+/// when `next()` throws.
+///
+/// created by [`JsCoroutine::catching`].
+///
+/// After an exception, `next()` is not called again and the coroutine
+/// completes with `undefined`.
+pub struct TryJsCoroutine {
+    js: Option<JsCoroutine>,
+}
+
+impl TryJsCoroutine {
+    /// whether `next()` threw, or the iterator completed
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.js.is_none()
+    }
+}
+
+impl Coroutine for TryJsCoroutine {
+    type Yield = Result<JsValue, JsValue>;
+    type Return = JsValue;
+
+    fn resume(self: Pin<&mut Self>, _: ()) -> CoroutineState<Result<JsValue, JsValue>, JsValue> {
+        let this = self.get_mut();
+        let js = match this.js.as_ref() {
+            Some(js) => js,
+            None => return CoroutineState::Complete(JsValue::UNDEFINED),
+        };
+        match js.step() {
+            Ok(CoroutineState::Yielded(v)) => CoroutineState::Yielded(Ok(v)),
+            Ok(CoroutineState::Complete(v)) => {
+                this.js = None;
+                CoroutineState::Complete(v)
+            },
+            Err(e) => {
+                this.js = None;
+                CoroutineState::Yielded(Err(e))
+            },
+        }
+    }
+}
+
+impl fmt::Debug for TryJsCoroutine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TryJsCoroutine").field("js", &self.js).finish()
+    }
+}
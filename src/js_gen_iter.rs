@@ -0,0 +1,112 @@
+use core::ops::Coroutine;
+use core::iter::{Fuse, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use alloc::boxed::Box;
+
+use js_sys::{Function, Object, Reflect, Symbol};
+use wasm_bindgen::prelude::*;
+
+use crate::GenIter;
+
+/// `JsGenIter` exports a generator to JavaScript, following the iterator protocol.
+///
+/// created by [`GenIter::into_js`], the yields are converted with `Into<JsValue>`.
+///
+/// From JavaScript, `next()` returns `{ value, done: false }` for every yield,
+/// then `{ value: undefined, done: true }` forever once the generator completed.
+/// `wasm_bindgen` cannot export a generic type, so the generator is boxed.
+///
+/// A `JsGenIter` is an iterator, but not an iterable: to use it with
+/// `for...of` or the spread syntax, pass [`into_iterable`](JsGenIter::into_iterable)
+/// to JavaScript instead.
+///
+/// ```no_run
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// extern crate gen_iter;
+/// extern crate wasm_bindgen;
+///
+/// use gen_iter::{gen_iter, JsGenIter};
+/// use wasm_bindgen::prelude::*;
+///
+/// // for (const n of countdown(3)) { console.log(n) }
+/// #[wasm_bindgen]
+/// pub fn countdown(from: u32) -> JsValue {
+///     gen_iter!(move {
+///         for i in (0..from).rev() {
+///             yield i;
+///         }
+///     }).into_js().into_iterable()
+/// }
+/// ```
+#[wasm_bindgen]
+pub struct JsGenIter(Fuse<Box<dyn Iterator<Item = JsValue>>>);
+
+#[wasm_bindgen]
+impl JsGenIter {
+    /// the next `{ value, done }` of the JavaScript iterator protocol
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Object {
+        let (value, done) = match self.0.next() {
+            Some(v) => (v, false),
+            None => (JsValue::UNDEFINED, true),
+        };
+        let result = Object::new();
+        // setting a property of a fresh plain object does not fail
+        let _ = Reflect::set(&result, &JsValue::from_str("value"), &value);
+        let _ = Reflect::set(&result, &JsValue::from_str("done"), &JsValue::from_bool(done));
+        result
+    }
+
+    /// a JavaScript iterable over the yields, usable with `for...of`:
+    /// this iterator with a `[Symbol.iterator]()` method returning itself
+    #[wasm_bindgen(js_name = intoIterable)]
+    pub fn into_iterable(self) -> JsValue {
+        let iterable = JsValue::from(self);
+        let this = Function::new_no_args("return this");
+        let _ = Reflect::set(&iterable, &Symbol::iterator(), &this);
+        iterable
+    }
+}
+
+impl JsGenIter {
+    #[inline]
+    pub fn new<G>(g: GenIter<G>) -> Self
+    where
+        G: Coroutine<Return = ()> + Unpin + 'static,
+        G::Yield: Into<JsValue>,
+    {
+        JsGenIter((Box::new(g.map(Into::into)) as Box<dyn Iterator<Item = JsValue>>).fuse())
+    }
+}
+
+impl<G> From<GenIter<G>> for JsGenIter
+where
+    G: Coroutine<Return = ()> + Unpin + 'static,
+    G::Yield: Into<JsValue>,
+{
+    #[inline]
+    fn from(g: GenIter<G>) -> Self {
+        JsGenIter::new(g)
+    }
+}
+
+impl fmt::Debug for JsGenIter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("JsGenIter").finish_non_exhaustive()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin + 'static,
+    G::Yield: Into<JsValue>,
+{
+    /// export this generator to JavaScript, see [`JsGenIter`]
+    #[inline]
+    pub fn into_js(self) -> JsGenIter {
+        JsGenIter::new(self)
+    }
+}
@@ -0,0 +1,237 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::{fmt, mem};
+
+use alloc::vec::Vec;
+
+use crate::GenIter;
+
+/// the next item of a source, in the heap of a [`KMergeBy`]
+struct Head<G: Coroutine<Return = ()> + Unpin> {
+    item: G::Yield,
+    index: usize,
+    gen: GenIter<G>,
+}
+
+/// `KMergeBy<G, F>` merges the items of sorted generators into one sorted iterator,
+/// like `itertools::kmerge_by`.
+///
+/// created by [`kmerge_by`], or [`kmerge`] as a [`KMerge`], with the `alloc` feature.
+///
+/// The next item of every generator is kept in a binary heap, ordered by `less`.
+/// A generator is only resumed when its item is given, the first time on the first
+/// `next`. Equal items are given in the order of their generators in the `Vec`.
+///
+/// ```
+/// #![feature(coroutines, coroutine_trait, stmt_expr_attributes)]
+///
+/// use gen_iter::{kmerge_by, GenIter};
+///
+/// let countdown = |from: u32, step: u32| GenIter(#[coroutine] move || {
+///     let mut i = from;
+///     while i > 0 {
+///         yield i;
+///         i = i.saturating_sub(step);
+///     }
+/// });
+///
+/// let merged = kmerge_by(vec![countdown(9, 3), countdown(5, 2)], |a, b| a > b);
+/// assert_eq!(merged.collect::<Vec<_>>(), [9, 6, 5, 3, 3, 1]);
+/// ```
+pub struct KMergeBy<G: Coroutine<Return = ()> + Unpin, F> {
+    // the generators not resumed yet, before the first `next`
+    sources: Vec<GenIter<G>>,
+    heap: Vec<Head<G>>,
+    less: F,
+}
+
+/// `KMerge<G>` merges the items of generators sorted in ascending order,
+/// created by [`kmerge`], see [`KMergeBy`]
+pub type KMerge<G> = KMergeBy<G, fn(&<G as Coroutine>::Yield, &<G as Coroutine>::Yield) -> bool>;
+
+impl<G, F> KMergeBy<G, F>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    F: FnMut(&G::Yield, &G::Yield) -> bool,
+{
+    /// whether `heap[a]` goes before `heap[b]`
+    fn before(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (&self.heap[a], &self.heap[b]);
+        (self.less)(&a.item, &b.item) || (!(self.less)(&b.item, &a.item) && a.index < b.index)
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if !self.before(i, parent) {
+                break;
+            }
+            self.heap.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let mut first = i;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < self.heap.len() && self.before(child, first) {
+                    first = child;
+                }
+            }
+            if first == i {
+                break;
+            }
+            self.heap.swap(i, first);
+            i = first;
+        }
+    }
+
+    fn start(&mut self) {
+        for (index, mut gen) in mem::take(&mut self.sources).into_iter().enumerate() {
+            if let Some(item) = gen.next() {
+                self.heap.push(Head { item, index, gen });
+                self.sift_up(self.heap.len() - 1);
+            }
+        }
+    }
+}
+
+impl<G, F> Iterator for KMergeBy<G, F>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    F: FnMut(&G::Yield, &G::Yield) -> bool,
+{
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        if !self.sources.is_empty() {
+            self.start();
+        }
+        let head = self.heap.first_mut()?;
+        let item = match head.gen.next() {
+            Some(next) => mem::replace(&mut head.item, next),
+            None => self.heap.swap_remove(0).item,
+        };
+        self.sift_down(0);
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), None)
+    }
+}
+
+impl<G, F> FusedIterator for KMergeBy<G, F>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    F: FnMut(&G::Yield, &G::Yield) -> bool,
+{}
+
+impl<G, F> fmt::Debug for KMergeBy<G, F>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+    G::Yield: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("KMergeBy")
+            .field("sources", &self.sources)
+            .field("heads", &self.heap.iter().map(|h| &h.item).collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+/// merge generators sorted by `less`, which tells whether an item goes before another,
+/// see [`KMergeBy`]
+#[inline]
+pub fn kmerge_by<G, F>(gens: Vec<GenIter<G>>, less: F) -> KMergeBy<G, F>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    F: FnMut(&G::Yield, &G::Yield) -> bool,
+{
+    KMergeBy { heap: Vec::with_capacity(gens.len()), sources: gens, less }
+}
+
+/// merge generators sorted in ascending order, see [`KMergeBy`]
+#[inline]
+pub fn kmerge<G>(gens: Vec<GenIter<G>>) -> KMerge<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: Ord,
+{
+    kmerge_by(gens, |a, b| a < b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{kmerge, kmerge_by};
+    use crate::{FnCoroutine, GenIter};
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    fn range(from: u32, to: u32, step: usize) -> GenIter<impl core::ops::Coroutine<Yield = u32, Return = ()> + Unpin> {
+        GenIter(#[coroutine] move || {
+            for i in (from..to).step_by(step) {
+                yield i;
+            }
+        })
+    }
+
+    #[test]
+    fn sorted() {
+        let ranges = [(0, 20, 3), (5, 10, 1), (0, 20, 3), (12, 13, 1), (30, 30, 1), (1, 40, 7)];
+        let merged = kmerge(ranges.iter().map(|&(a, b, s)| range(a, b, s)).collect()).collect::<Vec<_>>();
+        let mut all = ranges.iter().flat_map(|&(a, b, s)| (a..b).step_by(s)).collect::<Vec<_>>();
+        all.sort();
+        assert_eq!(merged, all);
+    }
+
+    #[test]
+    fn stable() {
+        // sorted on the tens, the units tell the source
+        let gens = (0..4).map(|src| GenIter(#[coroutine] move || {
+            for tens in [10, 20, 20, 30] {
+                yield tens + src;
+            }
+        })).collect();
+        let merged = kmerge_by(gens, |a, b| a / 10 < b / 10).collect::<Vec<_>>();
+        assert_eq!(merged, [
+            10, 11, 12, 13,
+            20, 20, 21, 21, 22, 22, 23, 23,
+            30, 31, 32, 33,
+        ]);
+    }
+
+    #[test]
+    fn lazy() {
+        let resumes = Cell::new(0);
+        let gens = (0..3).map(|src| {
+            let resumes = &resumes;
+            GenIter(#[coroutine] move || {
+                for i in 0..3 {
+                    resumes.set(resumes.get() + 1);
+                    yield i * 3 + src;
+                }
+            })
+        }).collect();
+        let mut merged = kmerge(gens);
+        assert_eq!(resumes.get(), 0);
+        assert_eq!(merged.next(), Some(0));
+        // the first item of every source, and the second one of the first
+        assert_eq!(resumes.get(), 4);
+        assert_eq!(merged.next(), Some(1));
+        assert_eq!(resumes.get(), 5);
+    }
+
+    #[test]
+    fn empty() {
+        let mut merged = kmerge(Vec::<GenIter<FnCoroutine<fn() -> Option<u32>>>>::new());
+        assert_eq!(merged.next(), None);
+
+        let mut merged = kmerge([range(0, 0, 1), range(3, 5, 1), range(0, 0, 1)].into());
+        assert_eq!(merged.by_ref().collect::<Vec<_>>(), [3, 4]);
+        assert_eq!(merged.next(), None);
+    }
+}
@@ -0,0 +1,203 @@
+use core::ops::Coroutine;
+use core::iter::{Fuse, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use crate::GenIter;
+
+/// an iterator whose items borrow from the iterator itself,
+/// so an item must be dropped before the next one is requested.
+///
+/// the std adapters don't apply, [`map_ref`](LendingIterator::map_ref)
+/// and [`for_each_ref`](LendingIterator::for_each_ref) cover the common uses.
+pub trait LendingIterator {
+    type Item<'a>
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>>;
+
+    /// turn the borrowed items into owned values, giving back a regular `Iterator`
+    #[inline]
+    fn map_ref<B, F>(self, f: F) -> MapRef<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item<'_>) -> B,
+    {
+        MapRef { iter: self, f }
+    }
+
+    /// call `f` on every item
+    #[inline]
+    fn for_each_ref<F>(mut self, mut f: F)
+    where
+        Self: Sized,
+        F: FnMut(Self::Item<'_>),
+    {
+        while let Some(item) = self.next() {
+            f(item);
+        }
+    }
+}
+
+/// `LendingGenIter<G>` lends the yields of a generator.
+///
+/// Each yielded value is stored in the iterator and `next` borrows it,
+/// the following `next` overwrites it. Nothing is cloned, which helps
+/// when the generator yields big buffers the consumer only looks at.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter, LendingGenIter, LendingIterator};
+///
+/// let mut it = LendingGenIter::new(gen_iter!({
+///     yield [0u8; 4096];
+///     yield [1u8; 4096];
+/// }));
+///
+/// assert_eq!(it.next().map(|b| b[0]), Some(0));
+/// assert_eq!(it.next().map(|b| b[0]), Some(1));
+/// assert_eq!(it.next(), None);
+/// ```
+///
+/// an item can't outlive the following `next`:
+///
+/// ```compile_fail
+/// # #![feature(coroutines, stmt_expr_attributes)]
+/// # use gen_iter::{gen_iter, LendingGenIter, LendingIterator};
+/// let mut it = LendingGenIter::new(gen_iter!({
+///     yield 1;
+///     yield 2;
+/// }));
+/// let a = it.next().unwrap();
+/// let b = it.next().unwrap(); // error: cannot borrow `it` as mutable more than once
+/// assert_eq!(*a + *b, 3);
+/// ```
+pub struct LendingGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    gen: Fuse<GenIter<G>>,
+    item: Option<G::Yield>,
+}
+
+impl<G> LendingGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    #[inline]
+    pub fn new(g: GenIter<G>) -> Self {
+        LendingGenIter { gen: g.fuse(), item: None }
+    }
+}
+
+impl<G> LendingIterator for LendingGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    type Item<'a> = &'a G::Yield where Self: 'a;
+
+    #[inline]
+    fn next(&mut self) -> Option<&G::Yield> {
+        self.item = self.gen.next();
+        self.item.as_ref()
+    }
+}
+
+// the item is never pinned
+impl<G> Unpin for LendingGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{}
+
+impl<G> From<GenIter<G>> for LendingGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    #[inline]
+    fn from(g: GenIter<G>) -> Self {
+        LendingGenIter::new(g)
+    }
+}
+
+impl<G> fmt::Debug for LendingGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+    G::Yield: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LendingGenIter")
+            .field("gen", &self.gen)
+            .field("item", &self.item)
+            .finish()
+    }
+}
+
+/// an iterator over the owned values mapped from a [`LendingIterator`]
+///
+/// created by [`LendingIterator::map_ref`].
+#[derive(Clone, Debug)]
+pub struct MapRef<I, F> {
+    iter: I,
+    f: F,
+}
+
+impl<I, F, B> Iterator for MapRef<I, F>
+where
+    I: LendingIterator,
+    F: FnMut(I::Item<'_>) -> B,
+{
+    type Item = B;
+
+    #[inline]
+    fn next(&mut self) -> Option<B> {
+        self.iter.next().map(&mut self.f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LendingGenIter, LendingIterator};
+    use crate::gen_iter;
+    use std::vec::Vec;
+
+    fn prefixes() -> LendingGenIter<impl core::ops::Coroutine<Yield = Vec<u32>, Return = ()> + Unpin> {
+        LendingGenIter::new(gen_iter!({
+            let mut buf = Vec::new();
+            for i in 0..4u32 {
+                buf.push(i);
+                yield buf.clone();
+            }
+        }))
+    }
+
+    #[test]
+    fn observe_items() {
+        let mut it = prefixes();
+        assert_eq!(it.next().map(Vec::as_slice), Some(&[0][..]));
+        assert_eq!(it.next().map(Vec::as_slice), Some(&[0, 1][..]));
+        assert_eq!(it.next().map(Vec::as_slice), Some(&[0, 1, 2][..]));
+        assert_eq!(it.next().map(Vec::as_slice), Some(&[0, 1, 2, 3][..]));
+        // fused
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn map_ref() {
+        let sums = prefixes().map_ref(|v| v.iter().sum::<u32>()).collect::<Vec<_>>();
+        assert_eq!(sums, [0, 1, 3, 6]);
+    }
+
+    #[test]
+    fn for_each_ref() {
+        let mut lens = Vec::new();
+        LendingGenIter::new(gen_iter!({
+            for len in [3, 0, 5] {
+                yield [7u8].repeat(len);
+            }
+        })).for_each_ref(|b| lens.push(b.len()));
+        assert_eq!(lens, [3, 0, 5]);
+    }
+}
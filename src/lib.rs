@@ -1,13 +1,13 @@
 //! # gen_iter - create generators to use as iterators
 //!
 //! ## [`GenIter`] and [`gen_iter!`]
-//! [`GenIter`] converts a [`Generator<(), Return=()>`](core::ops::Generator) into an iterator over the
+//! [`GenIter`] converts a [`Coroutine<(), Return=()>`](core::ops::Coroutine) into an iterator over the
 //! yielded type of the generator. The return type of the generator needs to be `()`.
 //! 
 //! [`gen_iter!`] helps to create a [`GenIter`]
 //!
 //! ```
-//! #![feature(generators)]
+//! #![feature(coroutines, stmt_expr_attributes)]
 //!
 //! use gen_iter::gen_iter;
 //!
@@ -32,14 +32,14 @@
 //! ```
 //! 
 //! ## [`GenIterReturn`] and [`gen_iter_return!`]
-//! [`GenIterReturn`] can be converted from a [`Generator<()>`](core::ops::Generator),
+//! [`GenIterReturn`] can be converted from a [`Coroutine<()>`](core::ops::Coroutine),
 //! `&mut GenIterReturn<G>` can be used as iterator.
 //! The return value of the generator can be got after the iterator is exhausted.
 //! 
 //! [`gen_iter_return!`] helps to create a [`GenIterReturn`].
 //! 
 //! ```
-//! #![feature(generators)]
+//! #![feature(coroutines, stmt_expr_attributes)]
 //!
 //! use gen_iter::gen_iter_return;
 //!
@@ -55,12 +55,541 @@
 //! println!("generator is_done={}", g.is_done()); // true
 //! println!("generator returns {}", g.return_or_self().ok().unwrap()); // "done"
 //! ```
+//!
+//! ## [`GenIterExchange`]
+//! [`GenIterExchange`] drives a [`Coroutine<R>`](core::ops::Coroutine) that takes a resume argument,
+//! each [`next_with`](GenIterExchange::next_with) call sends one argument in and gets one yield out.
+//! The return value of the generator can be got after it completes.
+//!
+//! ```
+//! #![feature(coroutines, stmt_expr_attributes)]
+//!
+//! use gen_iter::GenIterExchange;
+//!
+//! let mut g = GenIterExchange::new(#[coroutine] |mut x: u32| {
+//!     while x != 0 {
+//!         x = yield x + 1;
+//!     }
+//!     "done"
+//! });
+//!
+//! assert_eq!(g.next_with(1), Some(2));
+//! assert_eq!(g.next_with(41), Some(42));
+//! assert_eq!(g.next_with(0), None);
+//! assert_eq!(g.return_or_self().ok(), Some("done"));
+//! ```
+//!
+//! ## Cargo features
+//! - `alloc`: adds the helpers that need to allocate, like `GenIterExchange::send_all`
+//!   or `GenIter::pausable`, `Retry::final_attempt_only`, `GenIter::memoized`
+//!   replaying the items of a generator, `GenIterReturn::tee` splitting it in two,
+//!   `GenIterReturn::multipeek` looking any number of items ahead,
+//!   `GenIterReturn::chunk_by_yields` grouping the items with the same key,
+//!   `zip_all` and `zip_all_longest` resuming many generators in lock-step,
+//!   `kmerge` merging many sorted generators,
+//!   `CoroutineSet` running a changing set of generators in turn,
+//!   `flatten_gen` over the generators yielded by a generator,
+//!   `GenPool` reusing the memory of the generators it makes,
+//!   `GenIter::buffered` resuming a generator ahead of time,
+//!   `gen_struct!` declaring an iterator struct over a generator,
+//!   and `gen_iter_owned!` making an `OwningGenIter`
+//! - `std`: implies `alloc`, adds the helpers that need threads,
+//!   like `GenIterExchange::channel_pair`, `GenIter::par_for_each`,
+//!   `GenIter::background`, `GenIter::with_watch` and `SyncGenIter`,
+//!   `GenIter::timed` measuring every resume, `GenIter::watchdog` flagging the slow ones,
+//!   `GenIter::cancellable` stopped by a `CancelToken`,
+//!   `GenIter::throttle` spacing the items over time, `GenIterReturn::with_deadline`,
+//!   and the `std::io` adapters: `Read` for `GenReader`, `BufRead` for `GenChunkReader`,
+//!   `Write` for `GenWrite`, `GenIter::write_all_to`, and `gen_from_read`
+//!   and `gen_lines` the other way
+//! - `futures`: implements `futures_sink::Sink` for `GenSink`, adds `GenDuplex`,
+//!   adds `GenStream` and `AsyncGenIter`, `futures_core::Stream`s over generators,
+//!   with `alloc` `GenIterReturn::into_stream_with_return`,
+//!   with `std` `block_on_stream`, and `futures_io::AsyncRead` for `GenReader`
+//!   and `GenChunkReader`
+//! - `async_iterator`: implements the unstable `core::async_iter::AsyncIterator`
+//!   for `GenStream` and `AsyncGenIter`
+//! - `coroutine_clone`: adds `GenIter::cycle_gen`, repeating a generator which is `Clone`,
+//!   this needs the nightly `#![feature(coroutine_clone)]` in the crate defining it
+//! - `streaming-iterator`: adds `GenStreamingIter`, a
+//!   `streaming_iterator::StreamingIterator` over a generator
+//! - `genawaiter`: implies `std`, adds `FromGenawaiter`, a `Coroutine` over
+//!   a `genawaiter` generator, and `GenIterReturn::into_genawaiter` for the other way
+//! - `corosensei`: implies `std`, adds `FromCorosensei`, a `Coroutine` over
+//!   a stackful `corosensei` coroutine
+//! - `rayon`: implies `std`, adds `GenIter::par_bridge_checked` and
+//!   `GenIterReturn::par_yields`, bridges to rayon `ParallelIterator`s
+//! - `critical-section`: adds `CsGenIter`, a generator shared through
+//!   `critical_section`, without `std`
+//! - `embedded-io`: implements `embedded_io::Read` for `GenReader` and `GenChunkReader`
+//!   without `std`, with `alloc` `embedded_io::Write` for `GenWrite`
+//! - `nb`: adds `NbGenIter`, draining a generator yielding `nb::Result`s
+//! - `rand_core`: adds `GenRng`, a `rand_core::RngCore` over a generator yielding `u32`s or `u64`s
+//! - `either`: adds `FromEither`, a `Coroutine` over an `either::Either` of two generators,
+//!   `GenIter::left` and `GenIter::right`, and `race` between two generators
+//! - `tracing`: adds `GenIter::traced` and `GenIterReturn::traced`, a span per generator
+//!   and an event per yield
+//! - `wasm-bindgen`: implies `std`, adds `JsGenIter`, exporting a generator
+//!   to JavaScript as an iterator, and `JsCoroutine`, running a JavaScript
+//!   generator from Rust
+//! - `ffi`: implies `std`, adds `FfiGenIter` and `GenIter::into_ffi`, exporting
+//!   a generator to C as a `next` callback
+//! - `metrics`: implies `std`, adds `GenIter::metered` and `GenIterReturn::metered`,
+//!   counting the yields and measuring the resumes of a generator with `metrics`
+//! - `indicatif`: implies `std`, adds `GenIter::progress` and `GenIterReturn::progress`,
+//!   an `indicatif` progress bar ticking for every yield
+//! - `bumpalo`: adds `BumpGenIter`, a generator allocated in a `bumpalo::Bump` arena
+//! - `pyo3`: implies `std`, adds `PyGenIter` and `PyGenIterReturn`, exporting
+//!   a generator to Python as an iterator
+//! - `tokio`: implies `std`, adds `GenIter::spawn_blocking_stream`, the yields of a
+//!   generator driven on the tokio blocking pool as a `tokio_stream` stream
 
 #![no_std]
-#![feature(generators, generator_trait)]
+#![feature(coroutines, coroutine_trait)]
+#![cfg_attr(any(test, feature = "alloc"), feature(stmt_expr_attributes))]
+#![cfg_attr(feature = "async_iterator", feature(async_iterator))]
+#![cfg_attr(all(test, feature = "coroutine_clone"), feature(coroutine_clone))]
+
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "futures")]
+extern crate futures_sink;
+
+#[cfg(feature = "futures")]
+extern crate futures_core;
+
+#[cfg(all(feature = "futures", feature = "alloc"))]
+extern crate futures_channel;
+
+#[cfg(all(test, feature = "futures"))]
+extern crate futures;
+
+#[cfg(feature = "streaming-iterator")]
+extern crate streaming_iterator;
+
+#[cfg(feature = "genawaiter")]
+extern crate genawaiter;
+
+#[cfg(feature = "corosensei")]
+extern crate corosensei;
+
+#[cfg(feature = "rayon")]
+extern crate rayon;
+
+#[cfg(feature = "critical-section")]
+extern crate critical_section;
+
+#[cfg(feature = "tokio")]
+extern crate tokio;
+
+#[cfg(feature = "embedded-io")]
+extern crate embedded_io;
+
+#[cfg(feature = "nb")]
+extern crate nb;
+
+#[cfg(feature = "rand_core")]
+extern crate rand_core;
+
+#[cfg(feature = "either")]
+extern crate either;
+
+#[cfg(feature = "wasm-bindgen")]
+extern crate wasm_bindgen;
+
+#[cfg(feature = "wasm-bindgen")]
+extern crate js_sys;
+
+#[cfg(feature = "pyo3")]
+extern crate pyo3;
+
+#[cfg(feature = "tracing")]
+extern crate tracing;
+
+#[cfg(feature = "metrics")]
+extern crate metrics;
+
+#[cfg(feature = "indicatif")]
+extern crate indicatif;
+
+#[cfg(feature = "bumpalo")]
+extern crate bumpalo;
+
+#[cfg(all(test, feature = "metrics"))]
+extern crate metrics_util;
+
+#[cfg(all(test, feature = "rand_core"))]
+extern crate rand;
+
+#[cfg(feature = "tokio")]
+extern crate tokio_stream;
 
 mod gen_iter;
 pub use gen_iter::*;
 
 mod gen_iter_return;
 pub use gen_iter_return::*;
+
+mod gen_iter_exchange;
+pub use gen_iter_exchange::*;
+
+mod gen_iter_args;
+pub use gen_iter_args::*;
+
+mod zip_resume;
+pub use zip_resume::*;
+
+mod feedback;
+pub use feedback::*;
+
+mod map;
+pub use map::*;
+
+mod dialogue;
+pub use dialogue::*;
+
+mod gen_sink;
+pub use gen_sink::*;
+
+mod step_future;
+pub use step_future::*;
+
+mod gen_fmt_write;
+pub use gen_fmt_write::*;
+
+mod lending_gen_iter;
+pub use lending_gen_iter::*;
+
+mod display_join;
+pub use display_join::*;
+
+mod counted;
+pub use counted::*;
+
+mod progress_gen_iter;
+pub use progress_gen_iter::*;
+
+mod with_last;
+pub use with_last::*;
+
+mod guarded_gen_iter;
+pub use guarded_gen_iter::*;
+
+mod on_incomplete_drop;
+pub use on_incomplete_drop::*;
+
+mod abortable;
+pub use abortable::*;
+
+mod budgeted;
+pub use budgeted::*;
+
+mod take_resumes;
+pub use take_resumes::*;
+
+pub mod guard;
+
+mod unfold;
+pub use unfold::*;
+
+mod from_fn;
+pub use from_fn::*;
+
+mod repeat_gen;
+pub use repeat_gen::*;
+
+mod retry;
+pub use retry::*;
+
+mod or_else_gen;
+pub use or_else_gen::*;
+
+mod and_then_gen;
+pub use and_then_gen::*;
+
+mod chunk_by;
+pub use chunk_by::*;
+
+mod gen_fn;
+
+mod gen_scan;
+pub use gen_scan::*;
+
+mod yield_from;
+pub use yield_from::*;
+
+#[cfg(feature = "coroutine_clone")]
+mod gen_cycle;
+#[cfg(feature = "coroutine_clone")]
+pub use gen_cycle::*;
+
+#[cfg(any(feature = "futures", feature = "async_iterator"))]
+mod gen_stream;
+#[cfg(any(feature = "futures", feature = "async_iterator"))]
+pub use gen_stream::*;
+
+#[cfg(feature = "futures")]
+mod duplex;
+#[cfg(feature = "futures")]
+pub use duplex::*;
+
+#[cfg(feature = "critical-section")]
+mod cs_gen_iter;
+#[cfg(feature = "critical-section")]
+pub use cs_gen_iter::*;
+
+#[cfg(feature = "nb")]
+mod nb_gen_iter;
+#[cfg(feature = "nb")]
+pub use nb_gen_iter::*;
+
+#[cfg(feature = "either")]
+mod from_either;
+#[cfg(feature = "either")]
+pub use from_either::*;
+
+#[cfg(feature = "either")]
+mod race;
+#[cfg(feature = "either")]
+pub use race::*;
+
+#[cfg(feature = "tracing")]
+mod traced;
+#[cfg(feature = "tracing")]
+pub use traced::*;
+
+#[cfg(feature = "rand_core")]
+mod gen_rng;
+#[cfg(feature = "rand_core")]
+pub use gen_rng::*;
+
+#[cfg(feature = "streaming-iterator")]
+mod gen_streaming_iter;
+#[cfg(feature = "streaming-iterator")]
+pub use gen_streaming_iter::*;
+
+#[cfg(all(feature = "futures", feature = "alloc"))]
+mod return_future;
+#[cfg(all(feature = "futures", feature = "alloc"))]
+pub use return_future::*;
+
+#[cfg(any(feature = "futures", feature = "async_iterator"))]
+mod async_gen_iter;
+#[cfg(any(feature = "futures", feature = "async_iterator"))]
+pub use async_gen_iter::*;
+
+mod send_all;
+#[cfg(feature = "alloc")]
+pub use send_all::*;
+
+#[cfg(feature = "alloc")]
+mod gen_splitter;
+#[cfg(feature = "alloc")]
+pub use gen_splitter::*;
+
+#[cfg(feature = "alloc")]
+mod return_slot;
+#[cfg(feature = "alloc")]
+pub use return_slot::*;
+
+#[cfg(feature = "alloc")]
+mod owning_gen_iter;
+#[cfg(feature = "alloc")]
+pub use owning_gen_iter::*;
+
+#[cfg(feature = "alloc")]
+mod pausable;
+#[cfg(feature = "alloc")]
+pub use pausable::*;
+
+#[cfg(feature = "alloc")]
+mod memoized;
+#[cfg(feature = "alloc")]
+pub use memoized::*;
+
+#[cfg(feature = "alloc")]
+mod tee;
+#[cfg(feature = "alloc")]
+pub use tee::*;
+
+#[cfg(feature = "alloc")]
+mod multipeek;
+#[cfg(feature = "alloc")]
+pub use multipeek::*;
+
+#[cfg(feature = "alloc")]
+mod zip_all;
+#[cfg(feature = "alloc")]
+pub use zip_all::*;
+
+#[cfg(feature = "alloc")]
+mod kmerge;
+#[cfg(feature = "alloc")]
+pub use kmerge::*;
+
+#[cfg(feature = "alloc")]
+mod coroutine_set;
+#[cfg(feature = "alloc")]
+pub use coroutine_set::*;
+
+#[cfg(feature = "alloc")]
+mod flatten_gen;
+#[cfg(feature = "alloc")]
+pub use flatten_gen::*;
+
+#[cfg(feature = "alloc")]
+mod gen_pool;
+#[cfg(feature = "alloc")]
+pub use gen_pool::*;
+
+#[cfg(feature = "alloc")]
+mod buffered;
+#[cfg(feature = "alloc")]
+pub use buffered::*;
+
+#[cfg(feature = "alloc")]
+mod gen_struct;
+#[cfg(feature = "alloc")]
+pub use gen_struct::*;
+
+mod into_fn_mut;
+
+mod connect;
+pub use connect::*;
+
+mod pipe;
+pub use pipe::*;
+
+#[cfg(all(feature = "futures", feature = "std"))]
+extern crate futures_io;
+
+#[cfg(any(feature = "std", feature = "embedded-io"))]
+mod gen_reader;
+#[cfg(any(feature = "std", feature = "embedded-io"))]
+pub use gen_reader::*;
+
+#[cfg(all(feature = "futures", feature = "std"))]
+mod block_on_stream;
+#[cfg(all(feature = "futures", feature = "std"))]
+pub use block_on_stream::*;
+
+#[cfg(feature = "genawaiter")]
+mod from_genawaiter;
+#[cfg(feature = "genawaiter")]
+pub use from_genawaiter::*;
+
+#[cfg(feature = "corosensei")]
+mod from_corosensei;
+#[cfg(feature = "corosensei")]
+pub use from_corosensei::*;
+
+#[cfg(feature = "rayon")]
+mod par_bridge;
+#[cfg(feature = "rayon")]
+pub use par_bridge::*;
+
+#[cfg(feature = "std")]
+mod par_for_each;
+
+#[cfg(feature = "tokio")]
+mod spawn_blocking;
+
+#[cfg(feature = "wasm-bindgen")]
+mod js_gen_iter;
+#[cfg(feature = "wasm-bindgen")]
+pub use js_gen_iter::*;
+
+#[cfg(feature = "wasm-bindgen")]
+mod js_coroutine;
+#[cfg(feature = "wasm-bindgen")]
+pub use js_coroutine::*;
+
+#[cfg(feature = "ffi")]
+mod ffi_gen_iter;
+#[cfg(feature = "ffi")]
+pub use ffi_gen_iter::*;
+
+#[cfg(feature = "pyo3")]
+mod py_gen_iter;
+#[cfg(feature = "pyo3")]
+pub use py_gen_iter::*;
+
+#[cfg(feature = "std")]
+mod sync_gen_iter;
+#[cfg(feature = "std")]
+pub use sync_gen_iter::*;
+
+#[cfg(feature = "std")]
+mod into_channel;
+
+#[cfg(any(feature = "std", all(feature = "embedded-io", feature = "alloc")))]
+mod gen_write;
+#[cfg(any(feature = "std", all(feature = "embedded-io", feature = "alloc")))]
+pub use gen_write::*;
+
+#[cfg(feature = "std")]
+mod write_all_to;
+
+#[cfg(feature = "std")]
+mod gen_from_read;
+#[cfg(feature = "std")]
+pub use gen_from_read::*;
+
+#[cfg(feature = "std")]
+mod watch;
+#[cfg(feature = "std")]
+pub use watch::*;
+
+#[cfg(feature = "std")]
+mod timed;
+#[cfg(feature = "std")]
+pub use timed::*;
+
+#[cfg(feature = "std")]
+mod cancellable;
+#[cfg(feature = "std")]
+pub use cancellable::*;
+
+#[cfg(feature = "std")]
+mod throttle;
+#[cfg(feature = "std")]
+pub use throttle::*;
+
+#[cfg(feature = "std")]
+mod deadline;
+#[cfg(feature = "std")]
+pub use deadline::*;
+
+#[cfg(feature = "std")]
+mod watchdog;
+#[cfg(feature = "std")]
+pub use watchdog::*;
+
+#[cfg(feature = "metrics")]
+mod metered;
+#[cfg(feature = "metrics")]
+pub use metered::*;
+
+#[cfg(feature = "indicatif")]
+mod progress;
+#[cfg(feature = "indicatif")]
+pub use progress::*;
+
+#[cfg(feature = "bumpalo")]
+mod bump_gen_iter;
+#[cfg(feature = "bumpalo")]
+pub use bump_gen_iter::*;
+
+#[cfg(feature = "std")]
+mod background;
+#[cfg(feature = "std")]
+pub use background::*;
+
+#[cfg(feature = "std")]
+mod channel_pair;
+#[cfg(feature = "std")]
+pub use channel_pair::*;
@@ -0,0 +1,186 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::marker::Unpin;
+use core::pin::Pin;
+
+use crate::GenIterExchange;
+
+/// a generator that converts its resume arguments with `F`
+/// before passing them on to `G`.
+///
+/// created by [`GenIterExchange::map_args`].
+#[derive(Copy, Clone, Debug)]
+pub struct MapArgs<G, F> {
+    gen: G,
+    f: F,
+}
+
+// `f` is never pinned
+impl<G: Unpin, F> Unpin for MapArgs<G, F> {}
+
+impl<G, F, R, R2> Coroutine<R2> for MapArgs<G, F>
+where
+    G: Coroutine<R> + Unpin,
+    F: FnMut(R2) -> R,
+{
+    type Yield = G::Yield;
+    type Return = G::Return;
+
+    #[inline]
+    fn resume(self: Pin<&mut Self>, arg: R2) -> CoroutineState<Self::Yield, Self::Return> {
+        let this = self.get_mut();
+        Pin::new(&mut this.gen).resume((this.f)(arg))
+    }
+}
+
+/// a generator that converts the values yielded by `G` with `F`.
+///
+/// created by [`GenIterExchange::map_yield`].
+#[derive(Copy, Clone, Debug)]
+pub struct MapYield<G, F> {
+    gen: G,
+    f: F,
+}
+
+// `f` is never pinned
+impl<G: Unpin, F> Unpin for MapYield<G, F> {}
+
+impl<G, F, R, Y> Coroutine<R> for MapYield<G, F>
+where
+    G: Coroutine<R> + Unpin,
+    F: FnMut(G::Yield) -> Y,
+{
+    type Yield = Y;
+    type Return = G::Return;
+
+    #[inline]
+    fn resume(self: Pin<&mut Self>, arg: R) -> CoroutineState<Self::Yield, Self::Return> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.gen).resume(arg) {
+            CoroutineState::Yielded(y) => CoroutineState::Yielded((this.f)(y)),
+            CoroutineState::Complete(r) => CoroutineState::Complete(r),
+        }
+    }
+}
+
+impl<G: Coroutine<R> + Unpin, R> GenIterExchange<G, R> {
+    /// convert every resume argument with `f` before it reaches the generator.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::GenIterExchange;
+    ///
+    /// let mut g = GenIterExchange::new(#[coroutine] |mut n: usize| {
+    ///     loop {
+    ///         n = yield n * 2;
+    ///     }
+    /// }).map_args(|s: &str| s.len());
+    ///
+    /// assert_eq!(g.next_with("four"), Some(8));
+    /// ```
+    #[inline]
+    pub fn map_args<R2, F>(self, f: F) -> GenIterExchange<MapArgs<G, F>, R2>
+    where
+        F: FnMut(R2) -> R,
+    {
+        self.map_inner(|gen| MapArgs { gen, f })
+    }
+
+    /// convert every value yielded by the generator with `f`.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::GenIterExchange;
+    ///
+    /// let mut g = GenIterExchange::new(#[coroutine] |mut n: u32| {
+    ///     loop {
+    ///         n = yield n + 1;
+    ///     }
+    /// }).map_yield(|n| n % 2 == 0);
+    ///
+    /// assert_eq!(g.next_with(1), Some(true));
+    /// assert_eq!(g.next_with(2), Some(false));
+    /// ```
+    #[inline]
+    pub fn map_yield<Y, F>(self, f: F) -> GenIterExchange<MapYield<G, F>, R>
+    where
+        F: FnMut(G::Yield) -> Y,
+    {
+        self.map_inner(|gen| MapYield { gen, f })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIterExchange;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+
+    #[derive(Debug, PartialEq)]
+    enum Command {
+        Add(i32),
+        Sub(i32),
+        Quit,
+    }
+
+    fn parse(s: &str) -> Command {
+        let mut words = s.split(' ');
+        match (words.next(), words.next().and_then(|n| n.parse().ok())) {
+            (Some("add"), Some(n)) => Command::Add(n),
+            (Some("sub"), Some(n)) => Command::Sub(n),
+            _ => Command::Quit,
+        }
+    }
+
+    #[test]
+    fn map_args_and_yield() {
+        let mut g = GenIterExchange::new(#[coroutine] |mut cmd: Command| {
+            let mut total = 0;
+            loop {
+                match cmd {
+                    Command::Add(n) => total += n,
+                    Command::Sub(n) => total -= n,
+                    Command::Quit => return total,
+                }
+                cmd = yield total;
+            }
+        }).map_args(parse).map_yield(|total| total.to_string());
+
+        let replies: Vec<String> = ["add 5", "sub 2", "add 10"]
+            .iter()
+            .filter_map(|&line| g.next_with(line))
+            .collect();
+        assert_eq!(replies, ["5", "3", "13"]);
+
+        assert_eq!(g.next_with("quit"), None);
+        assert_eq!(g.return_or_self().ok(), Some(13));
+    }
+
+    #[test]
+    fn map_after_completion() {
+        let mut g = GenIterExchange::new(#[coroutine] |n: u32| {
+            yield n;
+            "done"
+        });
+
+        assert_eq!(g.next_with(1), Some(1));
+        assert_eq!(g.next_with(2), None);
+
+        let mut g = g.map_args(|s: &str| s.len() as u32).map_yield(|n| n * 2);
+        assert!(g.is_complete());
+        assert_eq!(g.next_with("ignored"), None);
+        assert_eq!(g.return_or_self().ok(), Some("done"));
+    }
+
+    #[test]
+    fn compose_with_zip_resume() {
+        let pairs = GenIterExchange::new(#[coroutine] |mut n: usize| {
+            loop {
+                n = yield n;
+            }
+        }).map_args(|s: &str| s.len()).zip_resume(["a", "bcd"].iter().copied());
+
+        assert_eq!(pairs.collect::<Vec<_>>(), [("a", 1), ("bcd", 3)]);
+    }
+}
@@ -0,0 +1,216 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::cell::RefCell;
+use core::pin::Pin;
+use core::fmt;
+
+use alloc::vec::Vec;
+
+use crate::GenIter;
+
+struct Memo<G: Coroutine<Return = ()>> {
+    // `None` once the generator completed
+    gen: Option<G>,
+    items: Vec<G::Yield>,
+}
+
+/// `Memoized<G>` records the items of a generator, so they can be iterated over
+/// more than once, without running the generator again.
+///
+/// created by [`GenIter::memoized`], with the `alloc` feature.
+///
+/// Every [`iter`](Memoized::iter) goes over the items from the start: the recorded
+/// ones first, then the generator is resumed when an iterator goes past them.
+/// Several iterators can be used at the same time, at different paces,
+/// they all see the same items.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter;
+///
+/// let memo = gen_iter!({
+///     yield 'a';
+///     yield 'b';
+/// }).memoized();
+///
+/// let mut first = memo.iter();
+/// assert_eq!(first.next(), Some('a'));
+/// assert_eq!(memo.iter().collect::<String>(), "ab");
+/// assert_eq!(first.next(), Some('b'));
+/// ```
+pub struct Memoized<G: Coroutine<Return = ()> + Unpin> {
+    memo: RefCell<Memo<G>>,
+}
+
+impl<G> Memoized<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: Clone,
+{
+    /// an iterator over the items from the start, see [`MemoizedIter`]
+    #[inline]
+    pub fn iter(&self) -> MemoizedIter<'_, G> {
+        MemoizedIter { memo: self, index: 0 }
+    }
+
+    /// the number of items recorded so far
+    #[inline]
+    pub fn recorded(&self) -> usize {
+        self.memo.borrow().items.len()
+    }
+
+    /// whether the generator completed, all of its items are recorded
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.memo.borrow().gen.is_none()
+    }
+
+    /// the item `index`, resuming the generator until it gets there
+    fn get(&self, index: usize) -> Option<G::Yield> {
+        let mut memo = self.memo.borrow_mut();
+        let Memo { ref mut gen, ref mut items } = *memo;
+        while items.len() <= index {
+            match Pin::new(gen.as_mut()?).resume(()) {
+                CoroutineState::Yielded(y) => items.push(y),
+                CoroutineState::Complete(()) => *gen = None,
+            }
+        }
+        Some(items[index].clone())
+    }
+}
+
+impl<G> fmt::Debug for Memoized<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+    G::Yield: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let memo = self.memo.borrow();
+        f.debug_struct("Memoized")
+            .field("gen", &memo.gen)
+            .field("items", &memo.items)
+            .finish()
+    }
+}
+
+/// an iterator over the items of a [`Memoized`], cloning the recorded ones,
+/// created by [`Memoized::iter`]
+pub struct MemoizedIter<'a, G: Coroutine<Return = ()> + Unpin> {
+    memo: &'a Memoized<G>,
+    index: usize,
+}
+
+impl<G> Iterator for MemoizedIter<'_, G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: Clone,
+{
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        let y = self.memo.get(self.index)?;
+        self.index += 1;
+        Some(y)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let memo = self.memo.memo.borrow();
+        let recorded = memo.items.len() - self.index;
+        if memo.gen.is_none() {
+            (recorded, Some(recorded))
+        } else {
+            (recorded, None)
+        }
+    }
+}
+
+impl<G> FusedIterator for MemoizedIter<'_, G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: Clone,
+{}
+
+impl<G> Clone for MemoizedIter<'_, G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        MemoizedIter { memo: self.memo, index: self.index }
+    }
+}
+
+impl<G> fmt::Debug for MemoizedIter<'_, G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MemoizedIter")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: Clone,
+{
+    /// record the items of this generator to iterate over them again, see [`Memoized`]
+    #[inline]
+    pub fn memoized(self) -> Memoized<G> {
+        Memoized { memo: RefCell::new(Memo { gen: Some(self.0), items: Vec::new() }) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIter;
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    #[test]
+    fn two_readers() {
+        let resumes = Cell::new(0);
+        let memo = GenIter(#[coroutine] || {
+            for i in 0..5 {
+                resumes.set(resumes.get() + 1);
+                yield i;
+            }
+        }).memoized();
+
+        let mut slow = memo.iter();
+        let mut fast = memo.iter();
+        assert_eq!(fast.by_ref().take(3).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(slow.next(), Some(0));
+        assert_eq!((resumes.get(), memo.recorded()), (3, 3));
+        assert_eq!(slow.size_hint(), (2, None));
+
+        assert_eq!(slow.by_ref().take(3).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(fast.collect::<Vec<_>>(), [3, 4]);
+        assert!(memo.is_done());
+        assert_eq!(slow.size_hint(), (1, Some(1)));
+        assert_eq!(slow.next(), Some(4));
+        assert_eq!(slow.next(), None);
+        assert_eq!(resumes.get(), 5);
+    }
+
+    #[test]
+    fn replay() {
+        let resumes = Cell::new(0);
+        let memo = GenIter(#[coroutine] || {
+            for i in 0..3 {
+                resumes.set(resumes.get() + 1);
+                yield i;
+            }
+        }).memoized();
+        assert_eq!(memo.iter().collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(memo.iter().collect::<Vec<_>>(), [0, 1, 2]);
+        // the generator ran once
+        assert_eq!(resumes.get(), 3);
+        assert_eq!(memo.iter().skip(1).clone().count(), 2);
+    }
+}
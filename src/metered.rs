@@ -0,0 +1,208 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::fmt;
+
+use std::time::Instant;
+
+use metrics::{counter, histogram, Counter, Histogram};
+
+use crate::{GenIter, GenIterReturn};
+
+/// a generator that records its activity with the `metrics` crate.
+///
+/// created by [`GenIter::metered`] and [`GenIterReturn::metered`].
+///
+/// Every metric has a `name` label, the name given to `metered`:
+/// - `gen_iter_yields_total`, a counter of the yields
+/// - `gen_iter_resume_seconds`, a histogram of the duration of every resume
+/// - `gen_iter_completed_total`, a counter incremented when the generator completes
+/// - `gen_iter_dropped_total`, a counter incremented when the generator is dropped
+///   before it completed
+///
+/// The metrics are registered with the recorder installed when `metered` is called.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter;
+///
+/// let total: u32 = gen_iter!({
+///     for i in 0..3 {
+///         yield i;
+///     }
+/// }).metered("numbers").sum();
+///
+/// assert_eq!(total, 3);
+/// ```
+pub struct Metered<G> {
+    gen: G,
+    name: &'static str,
+    yields: Counter,
+    resume_seconds: Histogram,
+    done: bool,
+}
+
+impl<G> Metered<G> {
+    fn new(gen: G, name: &'static str) -> Self {
+        Metered {
+            gen,
+            name,
+            yields: counter!("gen_iter_yields_total", "name" => name),
+            resume_seconds: histogram!("gen_iter_resume_seconds", "name" => name),
+            done: false,
+        }
+    }
+}
+
+// the metric handles are never pinned
+impl<G: Unpin> Unpin for Metered<G> {}
+
+impl<G, R> Coroutine<R> for Metered<G>
+where
+    G: Coroutine<R> + Unpin,
+{
+    type Yield = G::Yield;
+    type Return = G::Return;
+
+    fn resume(self: Pin<&mut Self>, arg: R) -> CoroutineState<Self::Yield, Self::Return> {
+        let this = self.get_mut();
+        let start = Instant::now();
+        let state = Pin::new(&mut this.gen).resume(arg);
+        this.resume_seconds.record(start.elapsed());
+        match state {
+            CoroutineState::Yielded(_) => this.yields.increment(1),
+            CoroutineState::Complete(_) => {
+                counter!("gen_iter_completed_total", "name" => this.name).increment(1);
+                this.done = true;
+            },
+        }
+        state
+    }
+}
+
+impl<G> Drop for Metered<G> {
+    fn drop(&mut self) {
+        if !self.done {
+            counter!("gen_iter_dropped_total", "name" => self.name).increment(1);
+        }
+    }
+}
+
+impl<G: fmt::Debug> fmt::Debug for Metered<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Metered")
+            .field("gen", &self.gen)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// record the activity of this generator with `metrics`, see [`Metered`]
+    #[inline]
+    pub fn metered(self, name: &'static str) -> GenIter<Metered<G>> {
+        GenIter(Metered::new(self.0, name))
+    }
+}
+
+impl<G> GenIterReturn<G>
+where
+    G: Coroutine + Unpin,
+{
+    /// record the activity of this generator with `metrics`, see [`Metered`].
+    ///
+    /// if it is already done, there is nothing left to record.
+    #[inline]
+    pub fn metered(self, name: &'static str) -> GenIterReturn<Metered<G>> {
+        self.map_inner(|g| Metered::new(g, name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, GenIterReturn};
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder, Snapshotter};
+    use metrics_util::MetricKind;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+
+    /// the metrics as `(name, label, value)`, histograms as their number of samples
+    fn snapshot(s: &Snapshotter) -> Vec<(String, String, u64)> {
+        let mut metrics = s.snapshot().into_vec().into_iter().map(|(key, _, _, value)| {
+            let (kind, key) = key.into_parts();
+            let label = key.labels().map(|l| std::format!("{}={}", l.key(), l.value())).collect::<Vec<_>>().join(",");
+            let value = match (kind, value) {
+                (MetricKind::Counter, DebugValue::Counter(n)) => n,
+                (MetricKind::Histogram, DebugValue::Histogram(samples)) => samples.len() as u64,
+                other => panic!("unexpected metric {:?}", other),
+            };
+            (key.name().to_string(), label, value)
+        }).collect::<Vec<_>>();
+        metrics.sort();
+        metrics
+    }
+
+    fn expected(metrics: &[(&str, &str, u64)]) -> Vec<(String, String, u64)> {
+        metrics.iter().map(|&(n, l, v)| (n.to_string(), l.to_string(), v)).collect()
+    }
+
+    #[test]
+    fn completed() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let g = GenIter(#[coroutine] || {
+                yield 1;
+                yield 2;
+                yield 3;
+            }).metered("three");
+            assert_eq!(g.sum::<u32>(), 6);
+
+            let mut g = GenIterReturn::new(#[coroutine] || {
+                yield 'a';
+                "done"
+            }).metered("ret");
+            assert_eq!((&mut g).count(), 1);
+            assert_eq!(g.return_or_self().ok(), Some("done"));
+        });
+        assert_eq!(snapshot(&snapshotter), expected(&[
+            ("gen_iter_completed_total", "name=ret", 1),
+            ("gen_iter_completed_total", "name=three", 1),
+            ("gen_iter_resume_seconds", "name=ret", 2),
+            ("gen_iter_resume_seconds", "name=three", 4),
+            ("gen_iter_yields_total", "name=ret", 1),
+            ("gen_iter_yields_total", "name=three", 3),
+        ]));
+    }
+
+    #[test]
+    fn dropped_incomplete() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let mut g = GenIter(#[coroutine] || {
+                yield 1;
+                yield 2;
+                yield 3;
+            }).metered("three");
+            assert_eq!(g.next(), Some(1));
+            drop(g);
+
+            let g = GenIter(#[coroutine] || {
+                yield 1;
+                yield 2;
+                yield 3;
+            }).metered("three");
+            assert_eq!(g.take(2).count(), 2);
+        });
+        assert_eq!(snapshot(&snapshotter), expected(&[
+            ("gen_iter_dropped_total", "name=three", 2),
+            ("gen_iter_resume_seconds", "name=three", 3),
+            ("gen_iter_yields_total", "name=three", 3),
+        ]));
+    }
+}
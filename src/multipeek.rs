@@ -0,0 +1,215 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use alloc::collections::VecDeque;
+
+use crate::GenIterReturn;
+
+/// `MultiPeek<G>` is a [`GenIterReturn`] that can look any number of items ahead,
+/// like `itertools::MultiPeek`.
+///
+/// created by [`GenIterReturn::multipeek`], with the `alloc` feature.
+/// Like `GenIterReturn`, `&mut MultiPeek<G>` is the iterator.
+///
+/// [`peek`](MultiPeek::peek) gives the next item not peeked at yet, moving a cursor
+/// forward, until [`reset_peek`](MultiPeek::reset_peek) or `next` moves it back to the
+/// front. [`peek_nth`](MultiPeek::peek_nth) looks at an item by its index instead, without
+/// moving the cursor. The items peeked at are kept until `next` gives them.
+///
+/// If the generator completes while peeking, its return value is kept
+/// as well, [`return_or_self`](MultiPeek::return_or_self) gives it once every item was
+/// given by `next`.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter_return;
+///
+/// let mut tokens = gen_iter_return!({
+///     yield "let";
+///     yield "x";
+///     yield "=";
+///     "eof"
+/// }).multipeek();
+///
+/// assert_eq!(tokens.peek(), Some(&"let"));
+/// assert_eq!(tokens.peek(), Some(&"x"));
+/// assert_eq!(tokens.peek_nth(2), Some(&"="));
+/// assert_eq!((&mut tokens).next(), Some("let"));
+/// assert_eq!(tokens.peek(), Some(&"x"));
+/// ```
+pub struct MultiPeek<G: Coroutine + Unpin> {
+    gen: GenIterReturn<G>,
+    buffer: VecDeque<G::Yield>,
+    cursor: usize,
+}
+
+impl<G: Coroutine + Unpin> MultiPeek<G> {
+    /// resume the generator until `n + 1` items are buffered, `false` if it completes first
+    fn fill(&mut self, n: usize) -> bool {
+        while self.buffer.len() <= n {
+            match (&mut self.gen).next() {
+                Some(y) => self.buffer.push_back(y),
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// the next item not peeked at yet, moving the cursor forward.
+    ///
+    /// the cursor does not move past the last item.
+    pub fn peek(&mut self) -> Option<&G::Yield> {
+        if !self.fill(self.cursor) {
+            return None;
+        }
+        self.cursor += 1;
+        self.buffer.get(self.cursor - 1)
+    }
+
+    /// the item `n` places ahead of the next one, without moving the cursor
+    pub fn peek_nth(&mut self, n: usize) -> Option<&G::Yield> {
+        if !self.fill(n) {
+            return None;
+        }
+        self.buffer.get(n)
+    }
+
+    /// move the cursor of [`peek`](MultiPeek::peek) back to the next item
+    #[inline]
+    pub fn reset_peek(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// whether the generator completed and every item was given by `next`
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.buffer.is_empty() && self.gen.is_done()
+    }
+
+    /// the return value once every item was given by `next`, else `self`
+    pub fn return_or_self(self) -> Result<G::Return, Self> {
+        if !self.buffer.is_empty() {
+            return Err(self);
+        }
+        let MultiPeek { gen, buffer, cursor } = self;
+        gen.return_or_self().map_err(|gen| MultiPeek { gen, buffer, cursor })
+    }
+}
+
+impl<G: Coroutine + Unpin> Iterator for &mut MultiPeek<G> {
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        self.cursor = 0;
+        match self.buffer.pop_front() {
+            Some(y) => Some(y),
+            None => (&mut self.gen).next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.buffer.len();
+        if self.gen.is_done() {
+            (buffered, Some(buffered))
+        } else {
+            (buffered, None)
+        }
+    }
+}
+
+impl<G: Coroutine + Unpin> FusedIterator for &mut MultiPeek<G> {}
+
+impl<G> fmt::Debug for MultiPeek<G>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Yield: fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MultiPeek")
+            .field("gen", &self.gen)
+            .field("buffer", &self.buffer)
+            .field("cursor", &self.cursor)
+            .finish()
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// look any number of items ahead, see [`MultiPeek`]
+    #[inline]
+    pub fn multipeek(self) -> MultiPeek<G> {
+        MultiPeek { gen: self, buffer: VecDeque::new(), cursor: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIterReturn;
+    use std::vec::Vec;
+
+    // the cases of `itertools::MultiPeek`
+    #[test]
+    fn peek_cursor() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            yield 2;
+            yield 3;
+            "done"
+        }).multipeek();
+        assert_eq!(g.peek(), Some(&1));
+        assert_eq!(g.peek(), Some(&2));
+        assert_eq!(g.peek(), Some(&3));
+        assert_eq!(g.peek(), None);
+        assert_eq!(g.peek(), None);
+
+        g.reset_peek();
+        assert_eq!(g.peek(), Some(&1));
+        // `next` resets the cursor
+        assert_eq!((&mut g).next(), Some(1));
+        assert_eq!(g.peek(), Some(&2));
+        assert_eq!(g.peek(), Some(&3));
+        assert_eq!((&mut g).next(), Some(2));
+        assert_eq!(g.peek(), Some(&3));
+        assert_eq!((&mut g).collect::<Vec<_>>(), [3]);
+        assert_eq!(g.peek(), None);
+    }
+
+    #[test]
+    fn peek_nth() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            yield 2;
+            yield 3;
+            "done"
+        }).multipeek();
+        assert_eq!(g.peek_nth(1), Some(&2));
+        assert_eq!(g.peek_nth(5), None);
+        assert_eq!(g.peek_nth(0), Some(&1));
+        // the cursor did not move
+        assert_eq!(g.peek(), Some(&1));
+        let it = &mut g;
+        assert_eq!(it.size_hint(), (3, Some(3)));
+        assert_eq!(it.collect::<Vec<_>>(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn completes_while_peeking() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            yield 2;
+            yield 3;
+            "done"
+        }).multipeek();
+        assert_eq!(g.peek_nth(3), None);
+        assert!(!g.is_done());
+        // items left, no return value yet
+        let mut g = g.return_or_self().err().unwrap();
+        assert_eq!((&mut g).count(), 3);
+        assert!(g.is_done());
+        assert_eq!(g.return_or_self().ok(), Some("done"));
+    }
+}
@@ -0,0 +1,201 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use crate::GenIter;
+
+/// `NbGenIter<G>` drains a generator yielding `nb::Result`s,
+/// like the values read from a peripheral.
+///
+/// `Err(nb::Error::WouldBlock)` means that no value is ready yet:
+/// - [`poll_next`](NbGenIter::poll_next) gives it back to the caller,
+///   for a cooperative loop or `nb::block!`
+/// - [`next_blocking`](NbGenIter::next_blocking), and the `Iterator`
+///   implementation, resume the generator again until it is something else
+///
+/// The first `Err(nb::Error::Other(e))` ends the iteration, so
+/// `collect::<Result<Vec<_>, _>>()` stops at the first real error.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// extern crate gen_iter;
+/// extern crate nb;
+///
+/// use gen_iter::{gen_iter, NbGenIter};
+///
+/// let mut adc = NbGenIter::new(gen_iter!({
+///     for sample in [12u16, 15] {
+///         yield Err(nb::Error::WouldBlock);
+///         yield Ok(sample);
+///     }
+///     yield Err(nb::Error::Other("overrun"));
+///     yield Ok(0);
+/// }));
+///
+/// assert_eq!(adc.poll_next(), Err(nb::Error::WouldBlock));
+/// assert_eq!(nb::block!(adc.poll_next()), Ok(Some(12)));
+/// assert_eq!(adc.collect::<Result<Vec<_>, _>>(), Err("overrun"));
+/// ```
+pub struct NbGenIter<G>(Option<GenIter<G>>)
+where
+    G: Coroutine<Return = ()> + Unpin;
+
+impl<G, T, E> NbGenIter<G>
+where
+    G: Coroutine<Yield = nb::Result<T, E>, Return = ()> + Unpin,
+{
+    #[inline]
+    pub fn new(g: GenIter<G>) -> Self {
+        NbGenIter(Some(g))
+    }
+
+    /// resume the generator once.
+    ///
+    /// `Ok(None)` once the generator completed or gave an error.
+    pub fn poll_next(&mut self) -> nb::Result<Option<T>, E> {
+        let g = match self.0.as_mut() {
+            Some(g) => g,
+            None => return Ok(None),
+        };
+        match g.next() {
+            Some(Ok(t)) => Ok(Some(t)),
+            Some(Err(nb::Error::WouldBlock)) => Err(nb::Error::WouldBlock),
+            Some(Err(nb::Error::Other(e))) => {
+                self.0 = None;
+                Err(nb::Error::Other(e))
+            },
+            None => {
+                self.0 = None;
+                Ok(None)
+            },
+        }
+    }
+
+    /// resume the generator until it yields a value or an error,
+    /// `None` once it completed or gave an error.
+    pub fn next_blocking(&mut self) -> Option<Result<T, E>> {
+        match nb::block!(self.poll_next()) {
+            Ok(t) => t.map(Ok),
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    /// whether the generator completed or gave an error
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+/// same as [`NbGenIter::next_blocking`]
+impl<G, T, E> Iterator for NbGenIter<G>
+where
+    G: Coroutine<Yield = nb::Result<T, E>, Return = ()> + Unpin,
+{
+    type Item = Result<T, E>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Result<T, E>> {
+        self.next_blocking()
+    }
+}
+
+impl<G, T, E> FusedIterator for NbGenIter<G>
+where
+    G: Coroutine<Yield = nb::Result<T, E>, Return = ()> + Unpin,
+{}
+
+impl<G, T, E> From<GenIter<G>> for NbGenIter<G>
+where
+    G: Coroutine<Yield = nb::Result<T, E>, Return = ()> + Unpin,
+{
+    #[inline]
+    fn from(g: GenIter<G>) -> Self {
+        NbGenIter::new(g)
+    }
+}
+
+impl<G> fmt::Debug for NbGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("NbGenIter").field(&self.0).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NbGenIter;
+    use crate::GenIter;
+    use std::vec::Vec;
+
+    #[derive(Debug, PartialEq)]
+    struct Overrun;
+
+    /// a peripheral busy 3 times before each of its values, then failing
+    fn peripheral(values: u32) -> NbGenIter<impl core::ops::Coroutine<Yield = nb::Result<u32, Overrun>, Return = ()> + Unpin> {
+        NbGenIter::new(GenIter(#[coroutine] move || {
+            for v in 0..values {
+                for _ in 0..3 {
+                    yield Err(nb::Error::WouldBlock);
+                }
+                yield Ok(v);
+            }
+            yield Err(nb::Error::Other(Overrun));
+            yield Ok(100);
+        }))
+    }
+
+    #[test]
+    fn cooperative() {
+        let mut p = peripheral(2);
+        let mut polls = 0;
+        let mut values = Vec::new();
+        loop {
+            polls += 1;
+            match p.poll_next() {
+                Ok(Some(v)) => values.push(v),
+                Ok(None) => unreachable!("ends with an error"),
+                Err(nb::Error::WouldBlock) => continue,
+                Err(nb::Error::Other(e)) => {
+                    assert_eq!(e, Overrun);
+                    break;
+                },
+            }
+        }
+        assert_eq!(values, [0, 1]);
+        assert_eq!(polls, 9);
+
+        // nothing after the error
+        assert!(p.is_done());
+        assert_eq!(p.poll_next(), Ok(None));
+    }
+
+    #[test]
+    fn blocking() {
+        let mut p = peripheral(3);
+        assert_eq!(p.next_blocking(), Some(Ok(0)));
+        assert_eq!((&mut p).take(2).collect::<Vec<_>>(), [Ok(1), Ok(2)]);
+        assert_eq!(p.next_blocking(), Some(Err(Overrun)));
+        assert_eq!(p.next_blocking(), None);
+
+        assert_eq!(peripheral(4).collect::<Result<Vec<_>, _>>(), Err(Overrun));
+    }
+
+    #[test]
+    fn completes() {
+        let mut p = NbGenIter::new(GenIter(#[coroutine] || {
+            yield Err(nb::Error::WouldBlock);
+            yield Ok::<_, nb::Error<()>>('a');
+        }));
+        assert_eq!(p.poll_next(), Err(nb::Error::WouldBlock));
+        assert_eq!(p.poll_next(), Ok(Some('a')));
+        assert!(!p.is_done());
+        assert_eq!(p.poll_next(), Ok(None));
+        assert!(p.is_done());
+        assert_eq!(p.next(), None);
+    }
+}
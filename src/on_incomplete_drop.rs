@@ -0,0 +1,222 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::fmt;
+
+use crate::{GenIter, GenIterReturn};
+
+/// a generator that calls a closure if it is dropped before it completed,
+/// like to release a resource or count the abandoned generators.
+///
+/// created by [`GenIter::on_incomplete_drop`] and [`GenIterReturn::on_incomplete_drop`],
+/// whose closure is given the number of yields so far.
+///
+/// The closure runs at most once: not once the generator completed, nor after
+/// [`into_inner`](OnIncompleteDrop::into_inner). It runs if the generator
+/// panics, as the panic drops it before it completed.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter_return;
+/// use std::cell::Cell;
+///
+/// let abandoned = Cell::new(None);
+/// let mut g = gen_iter_return!({
+///     for i in 0..10 {
+///         yield i;
+///     }
+///     "done"
+/// }).on_incomplete_drop(|yields| abandoned.set(Some(yields)));
+///
+/// assert_eq!((&mut g).take(3).count(), 3);
+/// drop(g);
+/// assert_eq!(abandoned.get(), Some(3));
+/// ```
+pub struct OnIncompleteDrop<G, F: FnOnce(usize)> {
+    gen: G,
+    guard: Guard<F>,
+}
+
+/// calls the closure when dropped, unless it was taken
+struct Guard<F: FnOnce(usize)> {
+    f: Option<F>,
+    yields: usize,
+}
+
+impl<F: FnOnce(usize)> Drop for Guard<F> {
+    fn drop(&mut self) {
+        if let Some(f) = self.f.take() {
+            f(self.yields);
+        }
+    }
+}
+
+impl<G, F: FnOnce(usize)> OnIncompleteDrop<G, F> {
+    fn new(gen: G, f: F) -> Self {
+        OnIncompleteDrop { gen, guard: Guard { f: Some(f), yields: 0 } }
+    }
+
+    /// the number of yields so far
+    #[inline]
+    pub fn yields(&self) -> usize {
+        self.guard.yields
+    }
+
+    /// get back the generator, without calling the closure
+    pub fn into_inner(self) -> G {
+        let OnIncompleteDrop { gen, mut guard } = self;
+        guard.f = None;
+        gen
+    }
+}
+
+// the closure is never pinned
+impl<G: Unpin, F: FnOnce(usize)> Unpin for OnIncompleteDrop<G, F> {}
+
+impl<G, F, R> Coroutine<R> for OnIncompleteDrop<G, F>
+where
+    G: Coroutine<R> + Unpin,
+    F: FnOnce(usize),
+{
+    type Yield = G::Yield;
+    type Return = G::Return;
+
+    fn resume(self: Pin<&mut Self>, arg: R) -> CoroutineState<Self::Yield, Self::Return> {
+        let this = self.get_mut();
+        let state = Pin::new(&mut this.gen).resume(arg);
+        match state {
+            CoroutineState::Yielded(_) => this.guard.yields += 1,
+            CoroutineState::Complete(_) => this.guard.f = None,
+        }
+        state
+    }
+}
+
+impl<G: fmt::Debug, F: FnOnce(usize)> fmt::Debug for OnIncompleteDrop<G, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OnIncompleteDrop")
+            .field("gen", &self.gen)
+            .field("yields", &self.guard.yields)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// call `f` if this generator is dropped before it completed, see [`OnIncompleteDrop`]
+    #[inline]
+    pub fn on_incomplete_drop<F: FnOnce()>(self, f: F) -> GenIter<OnIncompleteDrop<G, impl FnOnce(usize)>> {
+        GenIter(OnIncompleteDrop::new(self.0, move |_| f()))
+    }
+}
+
+impl<G> GenIterReturn<G>
+where
+    G: Coroutine + Unpin,
+{
+    /// call `f` with the number of yields if this generator is dropped
+    /// before it completed, see [`OnIncompleteDrop`].
+    ///
+    /// if it is already done, `f` is never called.
+    #[inline]
+    pub fn on_incomplete_drop<F: FnOnce(usize)>(self, f: F) -> GenIterReturn<OnIncompleteDrop<G, F>> {
+        self.map_inner(|g| OnIncompleteDrop::new(g, f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, GenIterReturn};
+    use core::cell::Cell;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::vec::Vec;
+
+    #[test]
+    fn early_drop() {
+        let calls = Cell::new(0);
+        let mut it = GenIter(#[coroutine] || {
+            for i in 0..3 {
+                yield i;
+            }
+        }).on_incomplete_drop(|| calls.set(calls.get() + 1));
+        assert_eq!(it.next(), Some(0));
+        assert_eq!(calls.get(), 0);
+        drop(it);
+        assert_eq!(calls.get(), 1);
+
+        // never resumed
+        drop(GenIter(#[coroutine] || {
+            for i in 0..3 {
+                yield i;
+            }
+        }).on_incomplete_drop(|| calls.set(calls.get() + 1)));
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn completed() {
+        let calls = Cell::new(0);
+        let it = GenIter(#[coroutine] || {
+            for i in 0..3 {
+                yield i;
+            }
+        }).on_incomplete_drop(|| calls.set(calls.get() + 1));
+        assert_eq!(it.collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(calls.get(), 0);
+
+        let yields = Cell::new(None);
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield 'a';
+            "done"
+        }).on_incomplete_drop(|n| yields.set(Some(n)));
+        assert_eq!((&mut g).count(), 1);
+        assert_eq!(g.return_or_self().ok(), Some("done"));
+        assert_eq!(yields.get(), None);
+    }
+
+    #[test]
+    fn yields_so_far() {
+        let yields = Cell::new(None);
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            for i in 0..5 {
+                yield i;
+            }
+        }).on_incomplete_drop(|n| yields.set(Some(n)));
+        assert_eq!((&mut g).take(2).count(), 2);
+        drop(g);
+        assert_eq!(yields.get(), Some(2));
+    }
+
+    #[test]
+    fn into_inner() {
+        let calls = Cell::new(0);
+        let mut it = GenIter(#[coroutine] || {
+            for i in 0..3 {
+                yield i;
+            }
+        }).on_incomplete_drop(|| calls.set(calls.get() + 1));
+        assert_eq!(it.next(), Some(0));
+        assert_eq!(it.0.yields(), 1);
+        let mut it = GenIter(it.0.into_inner());
+        assert_eq!(it.next(), Some(1));
+        drop(it);
+        assert_eq!(calls.get(), 0);
+    }
+
+    #[test]
+    fn panic_during_iteration() {
+        let calls = Cell::new(0);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let it = GenIter(#[coroutine] || {
+                yield 1;
+                panic!("boom");
+            }).on_incomplete_drop(|| calls.set(calls.get() + 1));
+            it.count()
+        }));
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+}
@@ -0,0 +1,185 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::iter::Iterator;
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::{fmt, mem};
+
+use crate::GenIterReturn;
+
+enum State<G: Coroutine + Unpin, F, H> {
+    First(GenIterReturn<G>, F),
+    Fallback(H),
+    Done,
+}
+
+/// a generator running `G`, then if it returns an `Err`, the fallback generator
+/// made by `F` from the error.
+///
+/// created by [`GenIterReturn::or_else_gen`].
+pub struct OrElseGen<G: Coroutine + Unpin, F, H> {
+    state: State<G, F, H>,
+}
+
+// `f` is never pinned
+impl<G: Coroutine + Unpin, F, H: Unpin> Unpin for OrElseGen<G, F, H> {}
+
+impl<G, F, H, R, E, E2> Coroutine for OrElseGen<G, F, H>
+where
+    G: Coroutine<Return = Result<R, E>> + Unpin,
+    F: FnOnce(E) -> GenIterReturn<H>,
+    H: Coroutine<Yield = G::Yield, Return = Result<R, E2>> + Unpin,
+{
+    type Yield = G::Yield;
+    type Return = Result<R, E2>;
+
+    fn resume(self: Pin<&mut Self>, _: ()) -> CoroutineState<G::Yield, Result<R, E2>> {
+        let this = self.get_mut();
+        loop {
+            match this.state {
+                State::First(ref mut g, _) => {
+                    if let Some(y) = (&mut *g).next() {
+                        return CoroutineState::Yielded(y);
+                    }
+                    let (g, f) = match mem::replace(&mut this.state, State::Done) {
+                        State::First(g, f) => (g, f),
+                        _ => unreachable!(),
+                    };
+                    match g.into_result() {
+                        Ok(Ok(r)) => return CoroutineState::Complete(Ok(r)),
+                        Ok(Err(e)) => match f(e).into_result() {
+                            Ok(r) => return CoroutineState::Complete(r),
+                            Err(h) => this.state = State::Fallback(h),
+                        },
+                        Err(_) => unreachable!(),
+                    }
+                },
+                State::Fallback(ref mut h) => {
+                    let state = Pin::new(h).resume(());
+                    if let CoroutineState::Complete(_) = state {
+                        this.state = State::Done;
+                    }
+                    return state;
+                },
+                State::Done => panic!("`OrElseGen` resumed after completion"),
+            }
+        }
+    }
+}
+
+impl<G, F, H> fmt::Debug for OrElseGen<G, F, H>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+    H: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.state {
+            State::First(ref g, _) => f.debug_tuple("First").field(g).finish(),
+            State::Fallback(ref h) => f.debug_tuple("Fallback").field(h).finish(),
+            State::Done => f.write_str("Done"),
+        }
+    }
+}
+
+impl<G, R, E> GenIterReturn<G>
+where
+    G: Coroutine<Return = Result<R, E>> + Unpin,
+{
+    /// if this generator returns an `Err`, go on with the generator `f` makes from the error,
+    /// yielding its items and returning its return value.
+    ///
+    /// `f` is only called if the error happens.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_iter_return;
+    ///
+    /// let mut g = gen_iter_return!({
+    ///     yield "network";
+    ///     Err("timed out")
+    /// }).or_else_gen(|e| gen_iter_return!(move {
+    ///     yield e;
+    ///     yield "cache";
+    ///     Ok::<_, ()>(7)
+    /// }));
+    ///
+    /// assert_eq!((&mut g).collect::<Vec<_>>(), ["network", "timed out", "cache"]);
+    /// assert_eq!(g.return_or_self().ok(), Some(Ok(7)));
+    /// ```
+    #[inline]
+    pub fn or_else_gen<F, H, E2>(self, f: F) -> GenIterReturn<OrElseGen<G, F, H>>
+    where
+        F: FnOnce(E) -> GenIterReturn<H>,
+        H: Coroutine<Yield = G::Yield, Return = Result<R, E2>> + Unpin,
+    {
+        GenIterReturn::new(OrElseGen { state: State::First(self, f) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIterReturn;
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    fn network(fail: bool) -> GenIterReturn<impl core::ops::Coroutine<Yield = u32, Return = Result<&'static str, u32>> + Unpin> {
+        GenIterReturn::new(#[coroutine] move || {
+            yield 1;
+            yield 2;
+            if fail {
+                return Err(3);
+            }
+            Ok("network")
+        })
+    }
+
+    #[test]
+    fn success() {
+        let called = Cell::new(false);
+        let mut g = network(false).or_else_gen(|_| {
+            called.set(true);
+            GenIterReturn::new(#[coroutine] || {
+                yield 0;
+                Ok::<_, ()>("cache")
+            })
+        });
+        assert_eq!((&mut g).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(g.return_or_self().ok(), Some(Ok("network")));
+        assert!(!called.get());
+    }
+
+    #[test]
+    fn failure_then_success() {
+        let mut g = network(true).or_else_gen(|e| GenIterReturn::new(#[coroutine] move || {
+            yield e * 10;
+            Ok::<_, ()>("cache")
+        }));
+        assert_eq!((&mut g).collect::<Vec<_>>(), [1, 2, 30]);
+        assert_eq!(g.return_or_self().ok(), Some(Ok("cache")));
+    }
+
+    #[test]
+    fn failure_then_failure() {
+        let mut g = network(true).or_else_gen(|e| GenIterReturn::new(#[coroutine] move || {
+            if false {
+                yield 0;
+            }
+            Err::<&str, _>([e, 4])
+        }));
+        assert_eq!((&mut g).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(g.return_or_self().ok(), Some(Err([3, 4])));
+    }
+
+    #[test]
+    fn already_done() {
+        let mut first = network(true);
+        assert_eq!((&mut first).count(), 2);
+        let mut g = first.or_else_gen(|e| GenIterReturn::new(#[coroutine] move || {
+            yield e;
+            Ok::<_, ()>("cache")
+        }));
+        assert_eq!((&mut g).collect::<Vec<_>>(), [3]);
+        assert_eq!(g.return_or_self().ok(), Some(Ok("cache")));
+    }
+}
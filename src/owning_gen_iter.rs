@@ -0,0 +1,159 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::iter::Iterator;
+use core::pin::Pin;
+use core::fmt;
+
+use alloc::boxed::Box;
+
+/// `OwningGenIter<G>` iterates over a generator which does not need to be `Unpin`,
+/// like a `static` generator keeping borrows of its own data across its yields.
+///
+/// The generator is pinned in a `Box`, so it can be returned from a function
+/// with the data it owns. [`gen_iter_owned!`](crate::gen_iter_owned) makes
+/// such a generator, owning a value the body can borrow.
+///
+/// It is not fused, like [`GenIter`](crate::GenIter).
+pub struct OwningGenIter<G>(Pin<Box<G>>)
+where
+    G: Coroutine<Return = ()> + ?Sized;
+
+impl<G> OwningGenIter<G>
+where
+    G: Coroutine<Return = ()>,
+{
+    #[inline]
+    pub fn new(gen: G) -> Self {
+        OwningGenIter(Box::pin(gen))
+    }
+}
+
+impl<G> Iterator for OwningGenIter<G>
+where
+    G: Coroutine<Return = ()> + ?Sized,
+{
+    type Item = G::Yield;
+
+    #[inline]
+    fn next(&mut self) -> Option<G::Yield> {
+        match self.0.as_mut().resume(()) {
+            CoroutineState::Yielded(y) => Some(y),
+            CoroutineState::Complete(()) => None,
+        }
+    }
+}
+
+impl<G> From<Pin<Box<G>>> for OwningGenIter<G>
+where
+    G: Coroutine<Return = ()> + ?Sized,
+{
+    #[inline]
+    fn from(gen: Pin<Box<G>>) -> Self {
+        OwningGenIter(gen)
+    }
+}
+
+impl<G> fmt::Debug for OwningGenIter<G>
+where
+    G: Coroutine<Return = ()> + ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OwningGenIter").finish_non_exhaustive()
+    }
+}
+
+/// macro to make an iterator owning `data`, over a generator borrowing it.
+///
+/// `data` is evaluated right away and moved into a `static` generator, whose body
+/// gets it as the closure parameter and can borrow it across its yields.
+/// The generator is pinned in a `Box` by [`OwningGenIter`], so the iterator
+/// does not borrow anything from the function creating it.
+///
+/// ```
+/// #![feature(coroutines, coroutine_trait, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter_owned, OwningGenIter};
+///
+/// fn words(line: &str) -> OwningGenIter<impl std::ops::Coroutine<Yield = usize, Return = ()>> {
+///     let upper = line.to_uppercase();
+///     gen_iter_owned!(upper, |upper| {
+///         for word in upper.split(' ') {
+///             yield word.len();
+///         }
+///     })
+/// }
+///
+/// assert_eq!(words("hello generator world").collect::<Vec<_>>(), [5, 9, 5]);
+/// ```
+#[macro_export]
+macro_rules! gen_iter_owned {
+    ($data: expr, |$name: pat_param| $block: block) => {{
+        let data = $data;
+        $crate::OwningGenIter::new(#[coroutine] static move || {
+            let $name = data;
+            $block
+        })
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::OwningGenIter;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::vec::Vec;
+    use std::string::String;
+
+    fn lengths(words: &[&str]) -> OwningGenIter<impl core::ops::Coroutine<Yield = usize, Return = ()>> {
+        let owned = words.iter().map(|w| String::from(*w)).collect::<Vec<_>>();
+        gen_iter_owned!(owned, |owned| {
+            // borrows `owned` across the yields
+            for word in owned.iter() {
+                yield word.len();
+            }
+        })
+    }
+
+    #[test]
+    fn returned_from_function() {
+        let it = lengths(&["a", "bcd", "ef"]);
+        assert_eq!(it.collect::<Vec<_>>(), [1, 3, 2]);
+        assert_eq!(lengths(&[]).count(), 0);
+    }
+
+    struct Logged(&'static str, Rc<RefCell<Vec<&'static str>>>);
+
+    impl Drop for Logged {
+        fn drop(&mut self) {
+            self.1.borrow_mut().push(self.0);
+        }
+    }
+
+    #[test]
+    fn drop_order() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let data = Logged("data", log.clone());
+        let local = log.clone();
+        let mut it = gen_iter_owned!(data, |data| {
+            let _guard = Logged("guard", local);
+            let borrowed = &data;
+            yield borrowed.0;
+            yield borrowed.0;
+        });
+        assert_eq!(it.next(), Some("data"));
+        assert!(log.borrow().is_empty());
+
+        // the body's locals first, then the data they could borrow
+        drop(it);
+        assert_eq!(*log.borrow(), ["guard", "data"]);
+    }
+
+    #[test]
+    fn dropped_before_start() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let it = gen_iter_owned!(Logged("data", log.clone()), |data| {
+            yield data.0;
+        });
+        drop(it);
+        assert_eq!(*log.borrow(), ["data"]);
+    }
+}
@@ -0,0 +1,193 @@
+use core::ops::Coroutine;
+use core::iter::Iterator;
+use core::marker::Unpin;
+use core::fmt;
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use rayon::iter::{IterBridge, ParallelBridge, ParallelIterator};
+
+use crate::{GenIter, GenIterReturn};
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// bridge the yields of the generator into a rayon `ParallelIterator`.
+    ///
+    /// the generator still runs on one thread at a time,
+    /// the work done on its yields is spread over the rayon thread pool.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// extern crate gen_iter;
+    /// extern crate rayon;
+    ///
+    /// use gen_iter::gen_iter;
+    /// use rayon::iter::ParallelIterator;
+    ///
+    /// let sum: u64 = gen_iter!({
+    ///     for i in 0..1000u64 {
+    ///         yield i;
+    ///     }
+    /// }).par_bridge_checked().map(|x| x * 2).sum();
+    /// assert_eq!(sum, 999_000);
+    /// ```
+    ///
+    /// a generator that is not `Send` is refused right here, the error
+    /// points at the captured value:
+    ///
+    /// ```compile_fail
+    /// # #![feature(coroutines, stmt_expr_attributes)]
+    /// # use gen_iter::gen_iter;
+    /// let rc = std::rc::Rc::new(1);
+    /// let bridge = gen_iter!(move {
+    ///     yield *rc;
+    /// }).par_bridge_checked(); // error: coroutine cannot be sent between threads safely
+    /// ```
+    #[inline]
+    pub fn par_bridge_checked(self) -> IterBridge<GenIter<G>>
+    where
+        G: Send,
+        G::Yield: Send,
+    {
+        self.par_bridge()
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// bridge the yields of the generator into a rayon `ParallelIterator`,
+    /// the return value goes into the [`ReturnHandle`] once the bridge
+    /// drained the generator.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// extern crate gen_iter;
+    /// extern crate rayon;
+    ///
+    /// use gen_iter::gen_iter_return;
+    /// use rayon::iter::ParallelIterator;
+    ///
+    /// let (yields, ret) = gen_iter_return!({
+    ///     for i in 0..100u32 {
+    ///         yield i;
+    ///     }
+    ///     return "done";
+    /// }).par_yields();
+    ///
+    /// assert_eq!(yields.count(), 100);
+    /// assert_eq!(ret.take(), Some("done"));
+    /// ```
+    pub fn par_yields(self) -> (impl ParallelIterator<Item = G::Yield>, ReturnHandle<G::Return>)
+    where
+        G: Send,
+        G::Yield: Send,
+        G::Return: Send,
+    {
+        let handle = ReturnHandle(Arc::new(Mutex::new(None)));
+        let yields = YieldsWithReturn { gen: Some(self), handle: handle.clone() };
+        (yields.par_bridge(), handle)
+    }
+}
+
+/// a handle that receives the return value of a generator once its yields
+/// were all consumed by a parallel iterator.
+///
+/// created by [`GenIterReturn::par_yields`]. the handle can be cloned and sent
+/// to other threads, all clones refer to the same value.
+pub struct ReturnHandle<R>(Arc<Mutex<Option<R>>>);
+
+impl<R> ReturnHandle<R> {
+    /// take the return value out of the handle,
+    /// `None` if the generator is not done yet or it was already taken
+    #[inline]
+    pub fn take(&self) -> Option<R> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).take()
+    }
+
+    /// whether the handle holds a return value
+    #[inline]
+    pub fn is_set(&self) -> bool {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).is_some()
+    }
+}
+
+impl<R> Clone for ReturnHandle<R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        ReturnHandle(self.0.clone())
+    }
+}
+
+impl<R> fmt::Debug for ReturnHandle<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReturnHandle").field("is_set", &self.is_set()).finish()
+    }
+}
+
+/// the yields of a generator, the return value goes into `handle`
+struct YieldsWithReturn<G: Coroutine + Unpin> {
+    gen: Option<GenIterReturn<G>>,
+    handle: ReturnHandle<G::Return>,
+}
+
+impl<G: Coroutine + Unpin> Iterator for YieldsWithReturn<G> {
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        let y = self.gen.as_mut()?.next();
+        if y.is_none() {
+            if let Some(Ok(r)) = self.gen.take().map(GenIterReturn::return_or_self) {
+                *self.handle.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(r);
+            }
+        }
+        y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, GenIterReturn};
+    use rayon::iter::ParallelIterator;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    #[test]
+    fn parallel_sum() {
+        let numbers = || GenIter(#[coroutine] || {
+            for i in 0..100_000u64 {
+                yield i;
+            }
+        });
+
+        let sum: u64 = numbers().par_bridge_checked().map(|x| x * 3).sum();
+        assert_eq!(sum, numbers().map(|x| x * 3).sum());
+    }
+
+    #[test]
+    fn yields_with_return() {
+        let (yields, ret) = GenIterReturn::new(#[coroutine] || {
+            let mut n = 0;
+            for i in 0..100_000u64 {
+                n += 1;
+                yield i;
+            }
+            n
+        }).par_yields();
+
+        let handle = ret.clone();
+        assert!(!handle.is_set());
+
+        let seen = AtomicUsize::new(0);
+        let sum: u64 = yields.inspect(|_| { seen.fetch_add(1, Ordering::Relaxed); }).sum();
+        assert_eq!(sum, (0..100_000).sum());
+        assert_eq!(seen.into_inner(), 100_000);
+
+        // the handle can be read from another thread
+        let r = thread::spawn(move || handle.take()).join().unwrap();
+        assert_eq!(r, Some(100_000));
+        assert_eq!(ret.take(), None);
+    }
+}
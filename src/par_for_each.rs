@@ -0,0 +1,222 @@
+use core::ops::Coroutine;
+use core::iter::Iterator;
+use core::marker::Unpin;
+
+use std::panic;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::vec::Vec;
+
+use crate::{GenIter, GenIterReturn};
+
+/// drive `iter` on the calling thread, and call `f` on its items
+/// from `workers` scoped threads. the first panic of a worker is resumed.
+fn fan_out<I, F>(iter: I, workers: usize, f: &F)
+where
+    I: Iterator,
+    I::Item: Send,
+    F: Fn(I::Item) + Sync,
+{
+    assert!(workers > 0, "par_for_each needs at least one worker");
+
+    thread::scope(|s| {
+        let (tx, rx) = mpsc::sync_channel(workers);
+        // the receiver is dropped with the last worker,
+        // so the loop below stops if every worker panicked
+        let rx = Arc::new(Mutex::new(rx));
+
+        let handles = (0..workers).map(|_| {
+            let rx = rx.clone();
+            s.spawn(move || loop {
+                let item = rx.lock().unwrap_or_else(PoisonError::into_inner).recv();
+                match item {
+                    Ok(item) => f(item),
+                    Err(_) => break,
+                }
+            })
+        }).collect::<Vec<_>>();
+        drop(rx);
+
+        for item in iter {
+            if tx.send(item).is_err() {
+                break;
+            }
+        }
+        drop(tx);
+
+        for h in handles {
+            if let Err(e) = h.join() {
+                panic::resume_unwind(e);
+            }
+        }
+    });
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// call `f` on every yield, from `workers` threads.
+    ///
+    /// the generator runs on the calling thread, its yields go through a
+    /// bounded channel to the workers, so it doesn't need to be `Send`.
+    /// returns once every yield was processed.
+    ///
+    /// the order in which `f` sees the yields is not specified.
+    ///
+    /// # Panics
+    /// if `workers` is `0`. a panic in `f` is resumed on the calling thread
+    /// once the other workers stopped.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_iter;
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    ///
+    /// let sum = AtomicU64::new(0);
+    /// gen_iter!({
+    ///     for i in 1..=100u64 {
+    ///         yield i;
+    ///     }
+    /// }).par_for_each(4, |x| {
+    ///     sum.fetch_add(x, Ordering::Relaxed);
+    /// });
+    /// assert_eq!(sum.into_inner(), 5050);
+    /// ```
+    pub fn par_for_each<F>(self, workers: usize, f: F)
+    where
+        G::Yield: Send,
+        F: Fn(G::Yield) + Sync,
+    {
+        fan_out(self, workers, &f);
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// like [`GenIter::par_for_each`], and get the return value
+    /// of the generator afterwards.
+    ///
+    /// if the generator is already done, `f` is not called.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_iter_return;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// let count = AtomicUsize::new(0);
+    /// let ret = gen_iter_return!({
+    ///     for i in 0..10 {
+    ///         yield i;
+    ///     }
+    ///     return "done";
+    /// }).par_for_each_with_return(3, |_| {
+    ///     count.fetch_add(1, Ordering::Relaxed);
+    /// });
+    /// assert_eq!(ret, "done");
+    /// assert_eq!(count.into_inner(), 10);
+    /// ```
+    pub fn par_for_each_with_return<F>(mut self, workers: usize, f: F) -> G::Return
+    where
+        G::Yield: Send,
+        F: Fn(G::Yield) + Sync,
+    {
+        fan_out(&mut self, workers, &f);
+        match self.return_or_self() {
+            Ok(r) => r,
+            Err(_) => unreachable!("generator is done"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, GenIterReturn};
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::thread;
+    use std::time::Duration;
+    use std::vec::Vec;
+
+    #[test]
+    fn process_all() {
+        // not `Send`, it stays on this thread
+        let rc = Rc::new(3);
+        let count = AtomicUsize::new(0);
+        let sum = AtomicUsize::new(0);
+        let threads = Mutex::new(Vec::new());
+
+        GenIter(#[coroutine] move || {
+            for i in 0..500 {
+                yield i * *rc;
+            }
+        }).par_for_each(4, |x| {
+            count.fetch_add(1, Ordering::Relaxed);
+            sum.fetch_add(x, Ordering::Relaxed);
+            let id = thread::current().id();
+            let mut threads = threads.lock().unwrap();
+            if !threads.contains(&id) {
+                threads.push(id);
+            }
+            drop(threads);
+            thread::sleep(Duration::from_micros(50));
+        });
+
+        assert_eq!(count.into_inner(), 500);
+        assert_eq!(sum.into_inner(), 3 * (0..500).sum::<usize>());
+        let threads = threads.into_inner().unwrap();
+        assert!(!threads.contains(&thread::current().id()));
+        assert!(threads.len() <= 4);
+    }
+
+    #[test]
+    fn with_return() {
+        let count = AtomicUsize::new(0);
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            for i in 0..300 {
+                yield i;
+            }
+            "done"
+        });
+        assert_eq!((&mut g).next(), Some(0));
+
+        let ret = g.par_for_each_with_return(3, |_| {
+            count.fetch_add(1, Ordering::Relaxed);
+        });
+        assert_eq!(ret, "done");
+        assert_eq!(count.into_inner(), 299);
+    }
+
+    #[test]
+    fn worker_panic_propagates() {
+        let err = panic::catch_unwind(AssertUnwindSafe(|| {
+            GenIter(#[coroutine] || {
+                for i in 0..300 {
+                    yield i;
+                }
+            }).par_for_each(2, |x| {
+                if x == 100 {
+                    panic!("bad item");
+                }
+            });
+        })).unwrap_err();
+        assert_eq!(err.downcast_ref::<&str>(), Some(&"bad item"));
+    }
+
+    #[test]
+    fn all_workers_panic() {
+        // the generator is endless, it must stop once no worker is left
+        let err = panic::catch_unwind(AssertUnwindSafe(|| {
+            GenIter(#[coroutine] || {
+                loop {
+                    yield ();
+                }
+            }).par_for_each(2, |()| panic!("no more workers"));
+        })).unwrap_err();
+        assert_eq!(err.downcast_ref::<&str>(), Some(&"no more workers"));
+    }
+}
@@ -0,0 +1,280 @@
+use core::ops::Coroutine;
+use core::iter::Iterator;
+use core::marker::Unpin;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::fmt;
+
+use alloc::sync::Arc;
+
+use crate::GenIter;
+
+/// the result of [`Pausable::poll_next`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PausePoll<Y> {
+    /// the generator is paused, it was not resumed
+    Paused,
+    /// the next item of the generator
+    Item(Y),
+    /// the generator completed
+    Done,
+}
+
+/// `PauseHandle` pauses and resumes a [`Pausable`], from any thread.
+///
+/// created by [`GenIter::pausable`] and [`Pausable::handle`], cloning it is cheap.
+#[derive(Clone, Default)]
+pub struct PauseHandle(Arc<AtomicBool>);
+
+impl PauseHandle {
+    #[inline]
+    pub fn pause(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    #[inline]
+    pub fn resume(&self) {
+        self.0.store(false, Ordering::Release);
+    }
+
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+impl fmt::Debug for PauseHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PauseHandle")
+            .field("paused", &self.is_paused())
+            .finish()
+    }
+}
+
+/// `Pausable<G>` polls a generator that can be paused with a [`PauseHandle`],
+/// like an animation in a game loop.
+///
+/// created by [`GenIter::pausable`].
+///
+/// It is not an `Iterator`, as an iterator returning `None` is over:
+/// [`poll_next`](Pausable::poll_next) returns [`PausePoll::Paused`] while
+/// paused, without resuming the generator, so no item is skipped or repeated
+/// when it goes on.
+///
+/// [`poll_next_or_last`](Pausable::poll_next_or_last) holds the last item instead,
+/// while paused.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter, PausePoll};
+///
+/// let (mut frames, handle) = gen_iter!({
+///     yield 'a';
+///     yield 'b';
+/// }).pausable();
+///
+/// assert_eq!(frames.poll_next(), PausePoll::Item('a'));
+/// handle.pause();
+/// assert_eq!(frames.poll_next(), PausePoll::Paused);
+/// handle.resume();
+/// assert_eq!(frames.poll_next(), PausePoll::Item('b'));
+/// assert_eq!(frames.poll_next(), PausePoll::Done);
+/// ```
+pub struct Pausable<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    gen: GenIter<G>,
+    handle: PauseHandle,
+    // only kept by `poll_next_or_last`
+    last: Option<G::Yield>,
+    done: bool,
+}
+
+impl<G> Pausable<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// resume the generator, unless it is paused or it completed
+    pub fn poll_next(&mut self) -> PausePoll<G::Yield> {
+        self.last = None;
+        self.poll()
+    }
+
+    fn poll(&mut self) -> PausePoll<G::Yield> {
+        if self.done {
+            return PausePoll::Done;
+        }
+        if self.handle.is_paused() {
+            return PausePoll::Paused;
+        }
+        match self.gen.next() {
+            Some(y) => PausePoll::Item(y),
+            None => {
+                self.done = true;
+                PausePoll::Done
+            },
+        }
+    }
+
+    /// same as [`poll_next`](Pausable::poll_next), but a clone of the last
+    /// item it returned instead of `Paused`, if there is one
+    pub fn poll_next_or_last(&mut self) -> PausePoll<G::Yield>
+    where
+        G::Yield: Clone,
+    {
+        match self.poll() {
+            PausePoll::Item(y) => {
+                self.last = Some(y.clone());
+                PausePoll::Item(y)
+            },
+            PausePoll::Paused => match self.last {
+                Some(ref y) => PausePoll::Item(y.clone()),
+                None => PausePoll::Paused,
+            },
+            PausePoll::Done => PausePoll::Done,
+        }
+    }
+
+    /// another handle on the pause
+    #[inline]
+    pub fn handle(&self) -> PauseHandle {
+        self.handle.clone()
+    }
+
+    /// whether the generator completed
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// get back the generator
+    #[inline]
+    pub fn into_inner(self) -> GenIter<G> {
+        self.gen
+    }
+}
+
+impl<G> fmt::Debug for Pausable<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+    G::Yield: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Pausable")
+            .field("gen", &self.gen)
+            .field("handle", &self.handle)
+            .field("last", &self.last)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// poll this generator, unless the returned handle paused it, see [`Pausable`]
+    pub fn pausable(self) -> (Pausable<G>, PauseHandle) {
+        let handle = PauseHandle::default();
+        (Pausable { gen: self, handle: handle.clone(), last: None, done: false }, handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, PausePoll};
+    use std::vec::Vec;
+
+    #[test]
+    fn toggled_between_polls() {
+        let (mut p, handle) = GenIter(#[coroutine] || {
+            for i in 0..5 {
+                yield i;
+            }
+        }).pausable();
+        let mut items = Vec::new();
+        let mut paused = 0;
+        for poll in 0.. {
+            // paused for every third poll
+            if poll % 3 == 2 {
+                handle.pause();
+            } else {
+                handle.resume();
+            }
+            match p.poll_next() {
+                PausePoll::Item(i) => items.push(i),
+                PausePoll::Paused => paused += 1,
+                PausePoll::Done => break,
+            }
+        }
+        assert_eq!(items, [0, 1, 2, 3, 4]);
+        assert_eq!(paused, 2);
+        assert!(p.is_done());
+
+        // still done when paused
+        handle.pause();
+        assert_eq!(p.poll_next(), PausePoll::Done);
+    }
+
+    #[test]
+    fn repeat_last() {
+        let (mut p, handle) = GenIter(#[coroutine] || {
+            for i in 0..3 {
+                yield i;
+            }
+        }).pausable();
+        handle.pause();
+        // nothing to repeat yet
+        assert_eq!(p.poll_next_or_last(), PausePoll::Paused);
+
+        handle.resume();
+        assert_eq!(p.poll_next_or_last(), PausePoll::Item(0));
+        p.handle().pause();
+        assert!(handle.is_paused());
+        assert_eq!(p.poll_next_or_last(), PausePoll::Item(0));
+        assert_eq!(p.poll_next_or_last(), PausePoll::Item(0));
+
+        handle.resume();
+        assert_eq!(p.poll_next_or_last(), PausePoll::Item(1));
+        handle.pause();
+        // `poll_next` forgets the last item
+        assert_eq!(p.poll_next(), PausePoll::Paused);
+        assert_eq!(p.poll_next_or_last(), PausePoll::Paused);
+
+        handle.resume();
+        assert_eq!(p.poll_next_or_last(), PausePoll::Item(2));
+        assert_eq!(p.poll_next_or_last(), PausePoll::Done);
+        handle.pause();
+        assert_eq!(p.poll_next_or_last(), PausePoll::Done);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod std_tests {
+    use crate::{GenIter, PausePoll};
+    use std::sync::mpsc;
+    use std::thread;
+
+    #[test]
+    fn paused_from_another_thread() {
+        let (mut p, handle) = GenIter(#[coroutine] || {
+            yield 1;
+            yield 2;
+        }).pausable();
+        assert_eq!(p.poll_next(), PausePoll::Item(1));
+
+        let (tx, rx) = mpsc::channel();
+        let other = thread::spawn(move || {
+            handle.pause();
+            tx.send(()).unwrap();
+            handle
+        });
+        rx.recv().unwrap();
+        assert_eq!(p.poll_next(), PausePoll::Paused);
+
+        other.join().unwrap().resume();
+        assert_eq!(p.poll_next(), PausePoll::Item(2));
+        assert_eq!(p.poll_next(), PausePoll::Done);
+    }
+}
@@ -0,0 +1,160 @@
+use core::ops::Coroutine;
+use core::marker::Unpin;
+use core::fmt;
+
+use crate::{GenIterReturn, GenSink};
+
+/// the error of [`try_pipe`], when the consumer completed before the producer
+pub struct PipeInterrupted<P: Coroutine + Unpin, R> {
+    /// the producer, nothing it yielded was lost
+    pub remaining: GenIterReturn<P>,
+    /// the return value of the consumer
+    pub ret: R,
+}
+
+impl<P, R> fmt::Debug for PipeInterrupted<P, R>
+where
+    P: Coroutine + Unpin + fmt::Debug,
+    P::Return: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PipeInterrupted")
+            .field("remaining", &self.remaining)
+            .field("ret", &self.ret)
+            .finish()
+    }
+}
+
+/// forward everything `src` yields into `dst`, then [`finish`](GenSink::finish) `dst`.
+///
+/// returns the return values of both generators.
+/// if `dst` completes early, `src` is still run to completion and the rest
+/// of its yields are dropped, see [`try_pipe`] to stop instead.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter_return, pipe, GenSink};
+///
+/// let src = gen_iter_return!({
+///     for i in 1..=4 {
+///         yield i;
+///     }
+///     "produced"
+/// });
+/// let dst = GenSink::new(#[coroutine] |mut item: Option<u32>| {
+///     let mut sum = 0;
+///     while let Some(n) = item {
+///         sum += n;
+///         item = yield;
+///     }
+///     sum
+/// });
+///
+/// assert_eq!(pipe(src, dst), ("produced", 10));
+/// ```
+pub fn pipe<P, C>(mut src: GenIterReturn<P>, mut dst: GenSink<C, P::Yield>) -> (P::Return, C::Return)
+where
+    P: Coroutine + Unpin,
+    C: Coroutine<Option<P::Yield>, Yield = ()> + Unpin,
+{
+    dst.extend(&mut src);
+    // drain what `dst` refused
+    (&mut src).for_each(drop);
+
+    let ret = src.return_or_self().ok().expect("generator is done");
+    (ret, dst.finish())
+}
+
+/// like [`pipe`], but stops pulling from `src` as soon as `dst` completes.
+///
+/// in that case `src` is given back with the return value of `dst`
+/// in a [`PipeInterrupted`].
+#[allow(clippy::type_complexity)]
+pub fn try_pipe<P, C>(
+    mut src: GenIterReturn<P>,
+    mut dst: GenSink<C, P::Yield>,
+) -> Result<(P::Return, C::Return), PipeInterrupted<P, C::Return>>
+where
+    P: Coroutine + Unpin,
+    C: Coroutine<Option<P::Yield>, Yield = ()> + Unpin,
+{
+    while !dst.is_complete() {
+        match (&mut src).next() {
+            Some(item) => {
+                let _ = dst.push(item);
+            },
+            None => {
+                let ret = src.return_or_self().ok().expect("generator is done");
+                return Ok((ret, dst.finish()));
+            },
+        }
+    }
+
+    let ret = dst.return_or_self().ok().expect("generator is done");
+    Err(PipeInterrupted { remaining: src, ret })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pipe, try_pipe, PipeInterrupted};
+    use crate::{GenIterReturn, GenSink};
+    use core::ops::Coroutine;
+    use std::vec::Vec;
+
+    fn numbers(n: u32) -> GenIterReturn<impl Coroutine<Yield = u32, Return = u32> + Unpin> {
+        GenIterReturn::new(#[coroutine] move || {
+            for i in 0..n {
+                yield i;
+            }
+            n
+        })
+    }
+
+    fn summing() -> GenSink<impl Coroutine<Option<u32>, Yield = (), Return = u32> + Unpin, u32> {
+        GenSink::new(#[coroutine] |mut item: Option<u32>| {
+            let mut sum = 0;
+            while let Some(n) = item {
+                sum += n;
+                item = yield;
+            }
+            sum
+        })
+    }
+
+    /// takes the first `n` items
+    fn first(n: usize) -> GenSink<impl Coroutine<Option<u32>, Yield = (), Return = Vec<u32>> + Unpin, u32> {
+        GenSink::new(#[coroutine] move |mut item: Option<u32>| {
+            let mut taken = Vec::new();
+            while let Some(i) = item {
+                taken.push(i);
+                if taken.len() == n {
+                    break;
+                }
+                item = yield;
+            }
+            taken
+        })
+    }
+
+    #[test]
+    fn pipe_sum() {
+        assert_eq!(pipe(numbers(5), summing()), (5, 10));
+        assert_eq!(pipe(numbers(0), summing()), (0, 0));
+        assert_eq!(try_pipe(numbers(5), summing()).ok(), Some((5, 10)));
+    }
+
+    #[test]
+    fn consumer_finishes_first() {
+        assert_eq!(pipe(numbers(5), first(2)), (5, [0, 1].to_vec()));
+
+        let PipeInterrupted { mut remaining, ret } = match try_pipe(numbers(5), first(2)) {
+            Ok(_) => panic!("consumer should finish first"),
+            Err(e) => e,
+        };
+        assert_eq!(ret, [0, 1]);
+        assert_eq!((&mut remaining).collect::<Vec<_>>(), [2, 3, 4]);
+        assert_eq!(remaining.return_or_self().ok(), Some(5));
+    }
+}
@@ -0,0 +1,231 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::fmt;
+
+use std::borrow::Cow;
+
+use indicatif::ProgressBar;
+
+use crate::{GenIter, GenIterReturn};
+
+/// a generator that ticks an `indicatif::ProgressBar` for every yield.
+///
+/// created by [`GenIter::progress`] and [`GenIterReturn::progress`],
+/// or their `progress_with` variants taking a bar with its own style or draw target.
+///
+/// The bar is finished when the generator completes, with a message made
+/// from the return value for a `GenIterReturn`, and finished and cleared
+/// when the generator is dropped before.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter_return;
+///
+/// let mut g = gen_iter_return!({
+///     for i in 0..100 {
+///         yield i;
+///     }
+///     "sent"
+/// }).progress(Some(100), |r| format!("{} 100 items", r));
+///
+/// for _item in &mut g {
+///     // ...
+/// }
+/// assert_eq!(g.return_or_self().ok(), Some("sent"));
+/// ```
+pub struct Progress<G, F> {
+    gen: G,
+    bar: ProgressBar,
+    message: Option<F>,
+    done: bool,
+}
+
+impl<G, F> Progress<G, F> {
+    fn new(gen: G, bar: ProgressBar, message: Option<F>) -> Self {
+        Progress { gen, bar, message, done: false }
+    }
+
+    /// the progress bar
+    #[inline]
+    pub fn bar(&self) -> &ProgressBar {
+        &self.bar
+    }
+}
+
+/// the type of the missing message closure of a [`Progress`] over a [`GenIter`]
+pub type NoMessage = fn(&()) -> &'static str;
+
+/// a bar of `len` items, or a spinner
+fn new_bar(len: Option<u64>) -> ProgressBar {
+    match len {
+        Some(len) => ProgressBar::new(len),
+        None => ProgressBar::new_spinner(),
+    }
+}
+
+// the bar and the message are never pinned
+impl<G: Unpin, F> Unpin for Progress<G, F> {}
+
+impl<G, F, R, M> Coroutine<R> for Progress<G, F>
+where
+    G: Coroutine<R> + Unpin,
+    F: FnOnce(&G::Return) -> M,
+    M: Into<Cow<'static, str>>,
+{
+    type Yield = G::Yield;
+    type Return = G::Return;
+
+    fn resume(self: Pin<&mut Self>, arg: R) -> CoroutineState<Self::Yield, Self::Return> {
+        let this = self.get_mut();
+        let state = Pin::new(&mut this.gen).resume(arg);
+        match state {
+            CoroutineState::Yielded(_) => this.bar.inc(1),
+            CoroutineState::Complete(ref r) => {
+                match this.message.take() {
+                    Some(f) => this.bar.finish_with_message(f(r)),
+                    None => this.bar.finish(),
+                }
+                this.done = true;
+            },
+        }
+        state
+    }
+}
+
+impl<G, F> Drop for Progress<G, F> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.bar.finish_and_clear();
+        }
+    }
+}
+
+impl<G: fmt::Debug, F> fmt::Debug for Progress<G, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Progress")
+            .field("gen", &self.gen)
+            .field("bar", &self.bar)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// show the progress of this generator, on a bar of `len` items or a spinner,
+    /// see [`Progress`]
+    #[inline]
+    pub fn progress(self, len: Option<u64>) -> GenIter<Progress<G, NoMessage>> {
+        self.progress_with(new_bar(len))
+    }
+
+    /// show the progress of this generator on `bar`, see [`Progress`]
+    #[inline]
+    pub fn progress_with(self, bar: ProgressBar) -> GenIter<Progress<G, NoMessage>> {
+        GenIter(Progress::new(self.0, bar, None))
+    }
+}
+
+impl<G> GenIterReturn<G>
+where
+    G: Coroutine + Unpin,
+{
+    /// show the progress of this generator, on a bar of `len` items or a spinner,
+    /// and finish it with the message `f(&return_value)`, see [`Progress`].
+    ///
+    /// if it is already done, there is no bar.
+    #[inline]
+    pub fn progress<F, M>(self, len: Option<u64>, f: F) -> GenIterReturn<Progress<G, F>>
+    where
+        F: FnOnce(&G::Return) -> M,
+        M: Into<Cow<'static, str>>,
+    {
+        self.progress_with(new_bar(len), f)
+    }
+
+    /// show the progress of this generator on `bar`,
+    /// and finish it with the message `f(&return_value)`, see [`Progress`]
+    #[inline]
+    pub fn progress_with<F, M>(self, bar: ProgressBar, f: F) -> GenIterReturn<Progress<G, F>>
+    where
+        F: FnOnce(&G::Return) -> M,
+        M: Into<Cow<'static, str>>,
+    {
+        self.map_inner(|g| Progress::new(g, bar, Some(f)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, GenIterReturn};
+    use indicatif::ProgressBar;
+    use std::format;
+
+    #[test]
+    fn ticks_and_finishes() {
+        let bar = ProgressBar::hidden();
+        bar.set_length(5);
+        let mut it = GenIter(#[coroutine] || {
+            for i in 0..5 {
+                yield i;
+            }
+        }).progress_with(bar.clone());
+
+        assert_eq!(it.by_ref().take(3).count(), 3);
+        assert_eq!(bar.position(), 3);
+        assert!(!bar.is_finished());
+
+        assert_eq!(it.count(), 2);
+        assert_eq!(bar.position(), 5);
+        assert!(bar.is_finished());
+    }
+
+    #[test]
+    fn spinner_and_len() {
+        let it = GenIter(#[coroutine] || {
+            for i in 0..2 {
+                yield i;
+            }
+        }).progress(None);
+        assert_eq!(it.0.bar().length(), None);
+        let it = GenIter(#[coroutine] || {
+            for i in 0..2 {
+                yield i;
+            }
+        }).progress(Some(2));
+        assert_eq!(it.0.bar().length(), Some(2));
+    }
+
+    #[test]
+    fn cleared_on_drop() {
+        let bar = ProgressBar::hidden();
+        let mut it = GenIter(#[coroutine] || {
+            for i in 0..5 {
+                yield i;
+            }
+        }).progress_with(bar.clone());
+        assert_eq!(it.next(), Some(0));
+        drop(it);
+        assert_eq!(bar.position(), 1);
+        assert!(bar.is_finished());
+    }
+
+    #[test]
+    fn return_message() {
+        let bar = ProgressBar::hidden();
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield 'a';
+            yield 'b';
+            42
+        }).progress_with(bar.clone(), |n| format!("done: {}", n));
+
+        assert_eq!((&mut g).count(), 2);
+        assert_eq!(g.return_or_self().ok(), Some(42));
+        assert_eq!(bar.position(), 2);
+        assert!(bar.is_finished());
+        assert_eq!(bar.message(), "done: 42");
+    }
+}
@@ -0,0 +1,338 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+use crate::GenIter;
+#[cfg(feature = "std")]
+use crate::watch::Watch;
+#[cfg(feature = "std")]
+use crate::WatchHandle;
+
+/// the consumed items of a [`ProgressGenIter`], the expected total,
+/// and whether the generator completed
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ProgressCounts {
+    done: u64,
+    total: Option<u64>,
+    finished: bool,
+}
+
+impl ProgressCounts {
+    /// same as [`ProgressGenIter::progress`]
+    pub fn progress(&self) -> (u64, Option<u64>) {
+        if self.finished {
+            (self.done, Some(self.done))
+        } else {
+            (self.done, self.total)
+        }
+    }
+
+    /// same as [`ProgressGenIter::fraction`]
+    pub fn fraction(&self) -> Option<f32> {
+        if self.finished {
+            return Some(1.0);
+        }
+        match self.total {
+            Some(0) => Some(1.0),
+            Some(total) => Some((self.done as f64 / total as f64).min(1.0) as f32),
+            None => None,
+        }
+    }
+
+    /// same as [`ProgressGenIter::is_finished`]
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// `ProgressGenIter<G>` iterates over a generator like [`GenIter`],
+/// and tells how far along it is, without any UI.
+///
+/// created by [`GenIter::track_progress`], with the expected number of items if known.
+///
+/// - [`progress`](ProgressGenIter::progress) is the number of items consumed,
+///   and the expected total. Once the generator completed, the total is
+///   the number of items consumed, even if the expected total was off
+/// - [`fraction`](ProgressGenIter::fraction) is between `0.0` and `1.0`,
+///   `None` without a total. Once the generator completed, it is `1.0`
+///
+/// Both can be read between two `next()`, or from another thread with
+/// the `std` feature and a `WatchHandle`, the handle of
+/// `GenIter::with_watch`, reading the latest [`ProgressCounts`].
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter;
+///
+/// let mut it = gen_iter!({
+///     for i in 0..4 {
+///         yield i;
+///     }
+/// }).track_progress(Some(4));
+///
+/// assert_eq!(it.next(), Some(0));
+/// assert_eq!(it.progress(), (1, Some(4)));
+/// assert_eq!(it.fraction(), Some(0.25));
+/// ```
+pub struct ProgressGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    gen: GenIter<G>,
+    counts: ProgressCounts,
+    #[cfg(feature = "std")]
+    shared: Option<Arc<Watch<ProgressCounts>>>,
+}
+
+impl<G> ProgressGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// the number of items consumed, and the expected total
+    #[inline]
+    pub fn progress(&self) -> (u64, Option<u64>) {
+        self.counts.progress()
+    }
+
+    /// the fraction of the expected total consumed, `None` without a total
+    #[inline]
+    pub fn fraction(&self) -> Option<f32> {
+        self.counts.fraction()
+    }
+
+    /// whether the generator completed
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.counts.finished
+    }
+
+    /// the counts, as read by the handles
+    #[inline]
+    pub fn counts(&self) -> ProgressCounts {
+        self.counts
+    }
+
+    /// replace the expected total, like with a better estimate
+    pub fn set_total(&mut self, total: Option<u64>) {
+        self.counts.total = total;
+        self.publish();
+    }
+
+    /// share the counts with the handles, if any
+    #[inline]
+    fn publish(&self) {
+        #[cfg(feature = "std")]
+        if let Some(shared) = &self.shared {
+            shared.store(self.counts);
+            if self.counts.finished {
+                shared.finish();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<G> ProgressGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// a handle reading the progress from another thread, its
+    /// [`latest`](WatchHandle::latest) counts are never `None`.
+    ///
+    /// the counts are only shared once there is a handle.
+    pub fn handle(&mut self) -> WatchHandle<ProgressCounts> {
+        let counts = self.counts;
+        let shared = self.shared.get_or_insert_with(|| Watch::new(Some(counts)));
+        WatchHandle(shared.clone())
+    }
+}
+
+impl<G> Iterator for ProgressGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        if self.counts.finished {
+            return None;
+        }
+        let next = self.gen.next();
+        match next {
+            Some(_) => self.counts.done += 1,
+            None => self.counts.finished = true,
+        }
+        self.publish();
+        next
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.counts.finished {
+            (0, Some(0))
+        } else {
+            (0, None)
+        }
+    }
+}
+
+impl<G> FusedIterator for ProgressGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{}
+
+impl<G> fmt::Debug for ProgressGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ProgressGenIter")
+            .field("gen", &self.gen)
+            .field("done", &self.counts.done)
+            .field("total", &self.counts.total)
+            .field("finished", &self.counts.finished)
+            .finish()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// count the items consumed, out of `total` if known, see [`ProgressGenIter`]
+    #[inline]
+    pub fn track_progress(self, total: Option<u64>) -> ProgressGenIter<G> {
+        ProgressGenIter {
+            gen: self,
+            counts: ProgressCounts { done: 0, total, finished: false },
+            #[cfg(feature = "std")]
+            shared: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIter;
+
+    #[test]
+    fn exact_total() {
+        let mut it = GenIter(#[coroutine] || {
+            for i in 0..4 {
+                yield i;
+            }
+        }).track_progress(Some(4));
+        assert_eq!((it.progress(), it.fraction()), ((0, Some(4)), Some(0.0)));
+        assert_eq!(it.by_ref().take(2).count(), 2);
+        assert_eq!((it.progress(), it.fraction()), ((2, Some(4)), Some(0.5)));
+        assert_eq!(it.by_ref().count(), 2);
+        assert_eq!((it.progress(), it.fraction()), ((4, Some(4)), Some(1.0)));
+        assert!(it.is_finished());
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn missing_total() {
+        let mut it = GenIter(#[coroutine] || {
+            for i in 0..3 {
+                yield i;
+            }
+        }).track_progress(None);
+        assert_eq!(it.next(), Some(0));
+        assert_eq!((it.progress(), it.fraction()), ((1, None), None));
+
+        it.set_total(Some(4));
+        assert_eq!(it.fraction(), Some(0.25));
+
+        assert_eq!(it.by_ref().count(), 2);
+        // the total is known once the generator completed
+        assert_eq!((it.progress(), it.fraction()), ((3, Some(3)), Some(1.0)));
+    }
+
+    #[test]
+    fn wrong_estimates() {
+        // fewer items than expected
+        let mut it = GenIter(#[coroutine] || {
+            for i in 0..2 {
+                yield i;
+            }
+        }).track_progress(Some(10));
+        assert_eq!(it.by_ref().count(), 2);
+        assert_eq!((it.progress(), it.fraction()), ((2, Some(2)), Some(1.0)));
+
+        // more items than expected
+        let mut it = GenIter(#[coroutine] || {
+            for i in 0..5 {
+                yield i;
+            }
+        }).track_progress(Some(2));
+        assert_eq!(it.by_ref().take(4).count(), 4);
+        assert_eq!((it.progress(), it.fraction()), ((4, Some(2)), Some(1.0)));
+        assert!(!it.is_finished());
+        assert_eq!(it.by_ref().count(), 1);
+        assert_eq!(it.progress(), (5, Some(5)));
+
+        let mut it = GenIter(#[coroutine] || {
+            if false {
+                yield 0;
+            }
+        }).track_progress(Some(0));
+        assert_eq!(it.fraction(), Some(1.0));
+        assert_eq!(it.next(), None);
+        assert_eq!(it.progress(), (0, Some(0)));
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod std_tests {
+    use crate::GenIter;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::vec::Vec;
+
+    #[test]
+    fn handle_from_another_thread() {
+        let (step, steps) = mpsc::channel::<()>();
+        let (stepped, stepped_rx) = mpsc::channel();
+        let mut it = GenIter(#[coroutine] move || {
+            for i in 0..4u8 {
+                yield i;
+            }
+        }).track_progress(Some(4));
+        assert_eq!(it.next(), Some(0));
+
+        let handle = it.handle();
+        let progress = || handle.latest().unwrap().progress();
+        assert_eq!(progress(), (1, Some(4)));
+
+        // one item for every step
+        let worker = thread::spawn(move || {
+            for () in steps {
+                stepped.send(it.next()).unwrap();
+            }
+        });
+        for i in 1..3 {
+            step.send(()).unwrap();
+            assert_eq!(stepped_rx.recv().unwrap(), Some(i));
+        }
+        assert_eq!(progress(), (3, Some(4)));
+        assert_eq!(handle.latest().unwrap().fraction(), Some(0.75));
+        assert!(!handle.is_finished());
+
+        step.send(()).unwrap();
+        step.send(()).unwrap();
+        assert_eq!(stepped_rx.iter().take(2).collect::<Vec<_>>(), [Some(3), None]);
+        assert_eq!(progress(), (4, Some(4)));
+        assert!(handle.is_finished());
+        assert!(handle.latest().unwrap().is_finished());
+
+        drop(step);
+        worker.join().unwrap();
+    }
+}
@@ -0,0 +1,284 @@
+use core::ops::Coroutine;
+use core::iter::{Fuse, Iterator};
+use core::marker::{Send, Unpin};
+use core::fmt;
+
+use alloc::boxed::Box;
+
+use pyo3::exceptions::PyStopIteration;
+use pyo3::prelude::*;
+
+use crate::{GenIter, GenIterReturn};
+
+/// the boxed generator of a [`PyGenIter`]
+trait PyNext: Send {
+    fn py_next(&mut self, py: Python<'_>) -> Option<PyObject>;
+}
+
+impl<G> PyNext for Fuse<GenIter<G>>
+where
+    G: Coroutine<Return = ()> + Unpin + Send,
+    G::Yield: IntoPy<PyObject>,
+{
+    #[inline]
+    fn py_next(&mut self, py: Python<'_>) -> Option<PyObject> {
+        self.next().map(|y| y.into_py(py))
+    }
+}
+
+/// the boxed generator of a [`PyGenIterReturn`], `None` once its return value was taken
+trait PyResume: Send {
+    fn py_resume(&mut self, py: Python<'_>) -> PyResult<PyObject>;
+}
+
+impl<G> PyResume for Option<GenIterReturn<G>>
+where
+    G: Coroutine + Unpin + Send,
+    G::Yield: IntoPy<PyObject>,
+    G::Return: IntoPy<PyObject> + Send,
+{
+    fn py_resume(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        if let Some(y) = self.as_mut().and_then(|g| (&mut *g).next()) {
+            return Ok(y.into_py(py));
+        }
+        match self.take().map(GenIterReturn::return_or_self) {
+            // a tuple would be taken as the arguments of the exception
+            Some(Ok(r)) => Err(PyStopIteration::new_err((r.into_py(py),))),
+            _ => Err(PyStopIteration::new_err(())),
+        }
+    }
+}
+
+/// `PyGenIter` exports a generator to Python as an iterator.
+///
+/// created by [`GenIter::into_py_iter`], the yields are converted with `IntoPy`.
+///
+/// `__next__` raises `StopIteration` once the generator completed, and on
+/// every call after that. `pyo3` classes cannot be generic, so the generator is
+/// boxed, and it must be `Send` as Python can hand the iterator to another thread.
+///
+/// ```no_run
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// extern crate gen_iter;
+/// extern crate pyo3;
+///
+/// use gen_iter::{gen_iter, PyGenIter};
+/// use pyo3::prelude::*;
+///
+/// // for n in countdown(3): print(n)
+/// #[pyfunction]
+/// fn countdown(from: u32) -> PyGenIter {
+///     gen_iter!(move {
+///         for i in (0..from).rev() {
+///             yield i;
+///         }
+///     }).into_py_iter()
+/// }
+/// ```
+#[pyclass]
+pub struct PyGenIter(Box<dyn PyNext>);
+
+#[pymethods]
+impl PyGenIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> Option<PyObject> {
+        self.0.py_next(py)
+    }
+}
+
+impl PyGenIter {
+    #[inline]
+    pub fn new<G>(g: GenIter<G>) -> Self
+    where
+        G: Coroutine<Return = ()> + Unpin + Send + 'static,
+        G::Yield: IntoPy<PyObject>,
+    {
+        PyGenIter(Box::new(g.fuse()))
+    }
+}
+
+impl<G> From<GenIter<G>> for PyGenIter
+where
+    G: Coroutine<Return = ()> + Unpin + Send + 'static,
+    G::Yield: IntoPy<PyObject>,
+{
+    #[inline]
+    fn from(g: GenIter<G>) -> Self {
+        PyGenIter::new(g)
+    }
+}
+
+impl fmt::Debug for PyGenIter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PyGenIter").finish_non_exhaustive()
+    }
+}
+
+/// `PyGenIterReturn` exports a generator to Python as an iterator
+/// returning a value, like a Python generator.
+///
+/// created by [`GenIterReturn::into_py_iter`].
+///
+/// Once the generator completes, `__next__` raises a `StopIteration` whose `value`
+/// is the return value, converted with `IntoPy`, then a `StopIteration` without
+/// one on every call after that. `yield from` in a Python generator evaluates
+/// to that value.
+#[pyclass]
+pub struct PyGenIterReturn(Box<dyn PyResume>);
+
+#[pymethods]
+impl PyGenIterReturn {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        self.0.py_resume(py)
+    }
+}
+
+impl PyGenIterReturn {
+    #[inline]
+    pub fn new<G>(g: GenIterReturn<G>) -> Self
+    where
+        G: Coroutine + Unpin + Send + 'static,
+        G::Yield: IntoPy<PyObject>,
+        G::Return: IntoPy<PyObject> + Send,
+    {
+        PyGenIterReturn(Box::new(Some(g)))
+    }
+}
+
+impl<G> From<GenIterReturn<G>> for PyGenIterReturn
+where
+    G: Coroutine + Unpin + Send + 'static,
+    G::Yield: IntoPy<PyObject>,
+    G::Return: IntoPy<PyObject> + Send,
+{
+    #[inline]
+    fn from(g: GenIterReturn<G>) -> Self {
+        PyGenIterReturn::new(g)
+    }
+}
+
+impl fmt::Debug for PyGenIterReturn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PyGenIterReturn").finish_non_exhaustive()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin + Send + 'static,
+    G::Yield: IntoPy<PyObject>,
+{
+    /// export this generator to Python, see [`PyGenIter`]
+    #[inline]
+    pub fn into_py_iter(self) -> PyGenIter {
+        PyGenIter::new(self)
+    }
+}
+
+impl<G> GenIterReturn<G>
+where
+    G: Coroutine + Unpin + Send + 'static,
+    G::Yield: IntoPy<PyObject>,
+    G::Return: IntoPy<PyObject> + Send,
+{
+    /// export this generator to Python, see [`PyGenIterReturn`]
+    #[inline]
+    pub fn into_py_iter(self) -> PyGenIterReturn {
+        PyGenIterReturn::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, GenIterReturn};
+    use pyo3::prelude::*;
+    use pyo3::types::IntoPyDict;
+
+    fn run(name: &str, obj: impl IntoPy<PyObject>, code: &str) {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let globals = [(name, obj.into_py(py))].into_py_dict_bound(py);
+            if let Err(e) = py.run_bound(code, Some(&globals), None) {
+                e.print(py);
+                panic!("python error");
+            }
+        });
+    }
+
+    #[test]
+    fn iterate() {
+        let g = GenIter(#[coroutine] || {
+            for i in 0..3u32 {
+                yield i * 10;
+            }
+        }).into_py_iter();
+        run("it", g, "
+assert iter(it) is it
+assert [n for n in it] == [0, 10, 20]
+assert list(it) == []
+");
+    }
+
+    #[test]
+    fn stop_iteration_value() {
+        let g = GenIterReturn::new(#[coroutine] || {
+            yield "a";
+            yield "b";
+            (1, "done")
+        }).into_py_iter();
+        run("it", g, "
+values = []
+try:
+    while True:
+        values.append(next(it))
+except StopIteration as e:
+    ret = e.value
+assert values == ['a', 'b']
+assert ret == (1, 'done')
+
+try:
+    next(it)
+    assert False
+except StopIteration as e:
+    assert e.value is None
+");
+    }
+
+    #[test]
+    fn yield_from() {
+        let g = GenIterReturn::new(#[coroutine] || {
+            yield 1.5;
+            42
+        }).into_py_iter();
+        run("it", g, "
+def outer():
+    ret = yield from it
+    yield ret
+assert list(outer()) == [1.5, 42]
+");
+    }
+
+    #[test]
+    fn already_done() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield ();
+            "early"
+        });
+        for _ in &mut g {}
+        run("it", g.into_py_iter(), "
+try:
+    next(it)
+    assert False
+except StopIteration as e:
+    assert e.value == 'early'
+");
+    }
+}
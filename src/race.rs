@@ -0,0 +1,202 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use either::Either;
+
+use crate::GenIterReturn;
+
+/// how a [`Race`] ended, from [`Race::return_or_self`]
+#[derive(Debug)]
+pub struct RaceOutcome<A: Coroutine + Unpin, B: Coroutine + Unpin> {
+    /// the return value of the generator which completed first
+    pub winner: Either<A::Return, B::Return>,
+    /// the other generator, on the other side
+    pub loser: Either<GenIterReturn<A>, GenIterReturn<B>>,
+}
+
+/// `Race<A, B>` resumes two generators in turn, until either of them completes,
+/// like to keep the first of two ways of computing a result.
+///
+/// created by [`race`]. Like [`GenIterReturn`], `&mut Race<A, B>` is the iterator,
+/// over the items of both generators as they come.
+///
+/// The turns strictly alternate, starting with `a`: `a`, `b`, `a` and so on,
+/// whatever the generators yield. The iteration ends as soon as one of them completes,
+/// the other one is not resumed anymore and is given back in the [`RaceOutcome`].
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter_return, race};
+///
+/// let slow = gen_iter_return!({
+///     for i in 0..10 {
+///         yield i;
+///     }
+///     "slow"
+/// });
+/// let fast = gen_iter_return!({
+///     yield 100;
+///     "fast"
+/// });
+///
+/// let mut r = race(slow, fast);
+/// assert_eq!((&mut r).collect::<Vec<_>>(), [0, 100, 1]);
+///
+/// let outcome = r.return_or_self().ok().unwrap();
+/// assert_eq!(outcome.winner.right(), Some("fast"));
+/// assert_eq!(outcome.loser.left().unwrap().count(), 8);
+/// ```
+pub struct Race<A: Coroutine + Unpin, B: Coroutine + Unpin> {
+    a: GenIterReturn<A>,
+    b: GenIterReturn<B>,
+    a_next: bool,
+}
+
+impl<A, B> Race<A, B>
+where
+    A: Coroutine + Unpin,
+    B: Coroutine<Yield = A::Yield> + Unpin,
+{
+    /// whether one of the generators completed
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.a.is_done() || self.b.is_done()
+    }
+
+    /// the winner and the loser once one of the generators completed, else `self`.
+    ///
+    /// if both were already done when the race started, `a` wins.
+    pub fn return_or_self(self) -> Result<RaceOutcome<A, B>, Self> {
+        let Race { a, b, a_next } = self;
+        if a.is_done() {
+            match a.into_result() {
+                Ok(r) => Ok(RaceOutcome { winner: Either::Left(r), loser: Either::Right(b) }),
+                Err(_) => unreachable!(),
+            }
+        } else if b.is_done() {
+            match b.into_result() {
+                Ok(r) => Ok(RaceOutcome { winner: Either::Right(r), loser: Either::Left(a) }),
+                Err(_) => unreachable!(),
+            }
+        } else {
+            Err(Race { a, b, a_next })
+        }
+    }
+}
+
+impl<A, B> Iterator for &mut Race<A, B>
+where
+    A: Coroutine + Unpin,
+    B: Coroutine<Yield = A::Yield> + Unpin,
+{
+    type Item = A::Yield;
+
+    fn next(&mut self) -> Option<A::Yield> {
+        if self.is_done() {
+            return None;
+        }
+        let a_turn = self.a_next;
+        self.a_next = !a_turn;
+        if a_turn {
+            (&mut self.a).next()
+        } else {
+            (&mut self.b).next()
+        }
+    }
+}
+
+impl<A, B> FusedIterator for &mut Race<A, B>
+where
+    A: Coroutine + Unpin,
+    B: Coroutine<Yield = A::Yield> + Unpin,
+{}
+
+impl<A, B> fmt::Debug for Race<A, B>
+where
+    A: Coroutine + Unpin + fmt::Debug,
+    A::Return: fmt::Debug,
+    B: Coroutine + Unpin + fmt::Debug,
+    B::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Race")
+            .field("a", &self.a)
+            .field("b", &self.b)
+            .field("a_next", &self.a_next)
+            .finish()
+    }
+}
+
+/// resume `a` and `b` in turn until either completes, see [`Race`]
+#[inline]
+pub fn race<A, B>(a: GenIterReturn<A>, b: GenIterReturn<B>) -> Race<A, B>
+where
+    A: Coroutine + Unpin,
+    B: Coroutine<Yield = A::Yield> + Unpin,
+{
+    Race { a, b, a_next: true }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::race;
+    use crate::GenIterReturn;
+    use std::vec::Vec;
+
+    fn count(from: u32, n: u32) -> GenIterReturn<impl core::ops::Coroutine<Yield = u32, Return = u32> + Unpin> {
+        GenIterReturn::new(#[coroutine] move || {
+            for i in from..from + n {
+                yield i;
+            }
+            n
+        })
+    }
+
+    #[test]
+    fn left_first() {
+        let mut r = race(count(0, 2), count(10, 5));
+        assert_eq!((&mut r).collect::<Vec<_>>(), [0, 10, 1, 11]);
+        let outcome = r.return_or_self().ok().unwrap();
+        assert_eq!(outcome.winner.left(), Some(2));
+        // the loser goes on from where it was
+        let mut loser = outcome.loser.right().unwrap();
+        assert_eq!((&mut loser).collect::<Vec<_>>(), [12, 13, 14]);
+        assert_eq!(loser.return_or_self().ok(), Some(5));
+    }
+
+    #[test]
+    fn right_first() {
+        let mut r = race(count(0, 5), count(10, 2));
+        assert_eq!((&mut r).collect::<Vec<_>>(), [0, 10, 1, 11, 2]);
+        let outcome = r.return_or_self().ok().unwrap();
+        assert_eq!(outcome.winner.right(), Some(2));
+        assert_eq!(outcome.loser.left().unwrap().count(), 2);
+    }
+
+    #[test]
+    fn no_yields() {
+        let mut r = race(count(0, 3), count(10, 0));
+        assert_eq!((&mut r).collect::<Vec<_>>(), [0]);
+        assert_eq!(r.return_or_self().ok().unwrap().winner.right(), Some(0));
+
+        let mut r = race(count(0, 0), count(10, 0));
+        assert_eq!((&mut r).count(), 0);
+        // `b` is never resumed
+        let outcome = r.return_or_self().ok().unwrap();
+        assert_eq!(outcome.winner.left(), Some(0));
+        assert!(!outcome.loser.right().unwrap().is_done());
+    }
+
+    #[test]
+    fn not_done() {
+        let mut r = race(count(0, 3), count(10, 3));
+        assert_eq!((&mut r).take(3).collect::<Vec<_>>(), [0, 10, 1]);
+        assert!(!r.is_done());
+        let mut r = r.return_or_self().err().unwrap();
+        // the turns go on
+        assert_eq!((&mut r).next(), Some(11));
+    }
+}
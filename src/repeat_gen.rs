@@ -0,0 +1,176 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::fmt;
+
+/// `RepeatGen<F, G>` repeats the yields of the generators made by a closure,
+/// making a new one whenever the previous one completes.
+///
+/// created by [`repeat_with_gen`]. Unlike `GenIter::cycle_gen` with the `coroutine_clone`
+/// feature, the generators do not need to be `Clone`.
+///
+/// A factory making a generator which completes without yielding anything would
+/// loop forever, so the iteration ends then, and the factory is not called anymore.
+///
+/// ```
+/// #![feature(coroutines, coroutine_trait, stmt_expr_attributes)]
+///
+/// use gen_iter::repeat_with_gen;
+///
+/// let it = repeat_with_gen(|| #[coroutine] || {
+///     yield 'a';
+///     yield 'b';
+/// });
+///
+/// assert_eq!(it.take(5).collect::<String>(), "ababa");
+/// ```
+pub struct RepeatGen<F, G> {
+    f: F,
+    gen: Option<G>,
+    // whether the running generator yielded, `None` once the iteration ended
+    yielded: Option<bool>,
+}
+
+impl<F, G> Iterator for RepeatGen<F, G>
+where
+    F: FnMut() -> G,
+    G: Coroutine<Return = ()> + Unpin,
+{
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        loop {
+            let yielded = self.yielded?;
+            let gen = match self.gen {
+                Some(ref mut gen) => gen,
+                None => self.gen.insert((self.f)()),
+            };
+            match Pin::new(gen).resume(()) {
+                CoroutineState::Yielded(y) => {
+                    self.yielded = Some(true);
+                    return Some(y);
+                },
+                CoroutineState::Complete(()) if yielded => {
+                    self.gen = None;
+                    self.yielded = Some(false);
+                },
+                CoroutineState::Complete(()) => {
+                    self.gen = None;
+                    self.yielded = None;
+                    return None;
+                },
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.yielded {
+            Some(_) => (0, None),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<F, G> FusedIterator for RepeatGen<F, G>
+where
+    F: FnMut() -> G,
+    G: Coroutine<Return = ()> + Unpin,
+{}
+
+/// the running generator is not cloned, the clone starts with a new one
+impl<F: Clone, G> Clone for RepeatGen<F, G> {
+    fn clone(&self) -> Self {
+        RepeatGen {
+            f: self.f.clone(),
+            gen: None,
+            yielded: self.yielded.map(|_| false),
+        }
+    }
+}
+
+impl<F, G: fmt::Debug> fmt::Debug for RepeatGen<F, G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RepeatGen")
+            .field("gen", &self.gen)
+            .field("ended", &self.yielded.is_none())
+            .finish_non_exhaustive()
+    }
+}
+
+/// repeat the yields of the generators made by `f`, see [`RepeatGen`]
+#[inline]
+pub fn repeat_with_gen<F, G>(f: F) -> RepeatGen<F, G>
+where
+    F: FnMut() -> G,
+    G: Coroutine<Return = ()> + Unpin,
+{
+    RepeatGen { f, gen: None, yielded: Some(false) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::repeat_with_gen;
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    #[test]
+    fn three_items() {
+        let made = Cell::new(0);
+        let mut it = repeat_with_gen(|| {
+            made.set(made.get() + 1);
+            #[coroutine] || {
+                for i in 0..3 {
+                    yield i;
+                }
+            }
+        });
+        // nothing made before the first item
+        assert_eq!(made.get(), 0);
+        assert_eq!(it.by_ref().take(8).collect::<Vec<_>>(), [0, 1, 2, 0, 1, 2, 0, 1]);
+        assert_eq!(made.get(), 3);
+    }
+
+    #[test]
+    fn clone_starts_over() {
+        let mut it = repeat_with_gen(|| #[coroutine] || {
+            yield 1;
+            yield 2;
+        });
+        assert_eq!(it.next(), Some(1));
+        let copy = it.clone();
+        assert_eq!(it.take(3).collect::<Vec<_>>(), [2, 1, 2]);
+        assert_eq!(copy.take(3).collect::<Vec<_>>(), [1, 2, 1]);
+    }
+
+    #[test]
+    fn empty() {
+        let made = Cell::new(0);
+        let mut it = repeat_with_gen(|| {
+            made.set(made.get() + 1);
+            #[coroutine] || {
+                if false {
+                    yield 0;
+                }
+            }
+        });
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next(), None);
+        assert_eq!(made.get(), 1);
+
+        // a later generator yielding nothing ends it too
+        let made = Cell::new(0);
+        let it = repeat_with_gen(|| {
+            let n = made.get();
+            made.set(n + 1);
+            #[coroutine] move || {
+                for i in 0..2 - n.min(2) {
+                    yield i;
+                }
+            }
+        });
+        assert_eq!(it.collect::<Vec<_>>(), [0, 1, 0]);
+        assert_eq!(made.get(), 3);
+    }
+}
@@ -0,0 +1,283 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::collections::VecDeque;
+
+/// `Retry<F, G, B>` runs a fallible generator again, made by a factory closure,
+/// while it returns an `Err`, like a download yielding its progress.
+///
+/// created by [`retry`]. Like [`GenIterReturn`](crate::GenIterReturn),
+/// `&mut Retry<F, G, B>` is the iterator, over the yields of every attempt one after
+/// the other. With `alloc`, `final_attempt_only` only
+/// yields those of the attempt giving the return value instead.
+///
+/// The attempts are numbered from 1. The return value, from
+/// [`return_or_self`](Retry::return_or_self), is the one of the first attempt
+/// returning `Ok`, or of the last one, with the number of attempts made.
+///
+/// ```
+/// #![feature(coroutines, coroutine_trait, stmt_expr_attributes)]
+///
+/// use gen_iter::retry;
+///
+/// let mut download = retry(|attempt| #[coroutine] move || {
+///     yield "connecting";
+///     if attempt < 3 {
+///         return Err("timed out");
+///     }
+///     yield "downloading";
+///     Ok(42)
+/// }, 5);
+///
+/// assert_eq!((&mut download).filter(|s| *s == "connecting").count(), 3);
+/// assert_eq!(download.return_or_self().ok(), Some((Ok(42), 3)));
+/// ```
+pub struct Retry<F, G: Coroutine, B = fn(u32)> {
+    factory: F,
+    backoff: B,
+    // `None` between two attempts
+    gen: Option<G>,
+    attempts: u32,
+    max_attempts: u32,
+    ret: Option<G::Return>,
+    // `Some` with `final_attempt_only`, the yields of the running attempt
+    #[cfg(feature = "alloc")]
+    buffer: Option<VecDeque<G::Yield>>,
+}
+
+impl<F, G, B, T, E> Retry<F, G, B>
+where
+    F: FnMut(u32) -> G,
+    G: Coroutine<Return = Result<T, E>> + Unpin,
+    B: FnMut(u32),
+{
+    /// call `backoff` with the number of the failed attempt before the next one,
+    /// like to sleep a bit
+    #[inline]
+    pub fn with_backoff<B2: FnMut(u32)>(self, backoff: B2) -> Retry<F, G, B2> {
+        Retry {
+            factory: self.factory,
+            backoff,
+            gen: self.gen,
+            attempts: self.attempts,
+            max_attempts: self.max_attempts,
+            ret: self.ret,
+            #[cfg(feature = "alloc")]
+            buffer: self.buffer,
+        }
+    }
+
+    /// only yield the items of the attempt giving the return value.
+    ///
+    /// they are kept until the attempt completes, the items of the failed
+    /// attempts are dropped. this should be called before iterating.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn final_attempt_only(mut self) -> Self {
+        self.buffer = Some(VecDeque::new());
+        self
+    }
+
+    /// the number of attempts started so far
+    #[inline]
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// whether the last attempt completed
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.ret.is_some()
+    }
+
+    /// the return value of the last attempt and the number of attempts,
+    /// or `self` if it did not complete
+    pub fn return_or_self(self) -> Result<(G::Return, u32), Self> {
+        match self.ret {
+            Some(r) => Ok((r, self.attempts)),
+            None => Err(self),
+        }
+    }
+}
+
+impl<F, G, B, T, E> Iterator for &mut Retry<F, G, B>
+where
+    F: FnMut(u32) -> G,
+    G: Coroutine<Return = Result<T, E>> + Unpin,
+    B: FnMut(u32),
+{
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        loop {
+            if self.ret.is_some() {
+                #[cfg(feature = "alloc")]
+                return self.buffer.as_mut().and_then(VecDeque::pop_front);
+                #[cfg(not(feature = "alloc"))]
+                return None;
+            }
+            let gen = match self.gen {
+                Some(ref mut gen) => gen,
+                None => {
+                    self.attempts += 1;
+                    self.gen.insert((self.factory)(self.attempts))
+                },
+            };
+            match Pin::new(gen).resume(()) {
+                CoroutineState::Yielded(y) => {
+                    #[cfg(feature = "alloc")]
+                    if let Some(ref mut buffer) = self.buffer {
+                        buffer.push_back(y);
+                        continue;
+                    }
+                    return Some(y);
+                },
+                CoroutineState::Complete(Err(_)) if self.attempts < self.max_attempts => {
+                    self.gen = None;
+                    #[cfg(feature = "alloc")]
+                    if let Some(ref mut buffer) = self.buffer {
+                        buffer.clear();
+                    }
+                    (self.backoff)(self.attempts);
+                },
+                CoroutineState::Complete(r) => {
+                    self.gen = None;
+                    self.ret = Some(r);
+                },
+            }
+        }
+    }
+}
+
+impl<F, G, B, T, E> FusedIterator for &mut Retry<F, G, B>
+where
+    F: FnMut(u32) -> G,
+    G: Coroutine<Return = Result<T, E>> + Unpin,
+    B: FnMut(u32),
+{}
+
+impl<F, G, B> fmt::Debug for Retry<F, G, B>
+where
+    G: Coroutine + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Retry")
+            .field("gen", &self.gen)
+            .field("attempts", &self.attempts)
+            .field("max_attempts", &self.max_attempts)
+            .field("ret", &self.ret)
+            .finish_non_exhaustive()
+    }
+}
+
+/// run the generators made by `factory`, given the number of the attempt,
+/// until one returns `Ok` or `max_attempts` were made, see [`Retry`]
+///
+/// # Panics
+/// if `max_attempts` is 0
+#[inline]
+pub fn retry<F, G, T, E>(factory: F, max_attempts: u32) -> Retry<F, G>
+where
+    F: FnMut(u32) -> G,
+    G: Coroutine<Return = Result<T, E>> + Unpin,
+{
+    assert!(max_attempts > 0, "`retry` needs at least one attempt");
+    Retry {
+        factory,
+        backoff: |_| {},
+        gen: None,
+        attempts: 0,
+        max_attempts,
+        ret: None,
+        #[cfg(feature = "alloc")]
+        buffer: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::retry;
+    use core::cell::RefCell;
+    use core::ops::Coroutine;
+    use core::pin::Pin;
+    use std::boxed::Box;
+    use std::panic;
+    use std::vec::Vec;
+
+    type Attempt = Pin<Box<dyn Coroutine<Yield = (u32, u32), Return = Result<&'static str, u32>>>>;
+
+    /// fails `failures` times, yielding the attempt and 0, 1, 2 while it succeeds
+    fn flaky(failures: u32) -> impl FnMut(u32) -> Attempt {
+        move |attempt| Box::pin(#[coroutine] move || {
+            yield (attempt, 0);
+            if attempt <= failures {
+                return Err(attempt);
+            }
+            yield (attempt, 1);
+            yield (attempt, 2);
+            Ok("done")
+        })
+    }
+
+    #[test]
+    fn fails_twice() {
+        let backoffs = RefCell::new(Vec::new());
+        let mut g = retry(flaky(2), 5).with_backoff(|n| backoffs.borrow_mut().push(n));
+        assert_eq!(
+            (&mut g).collect::<Vec<_>>(),
+            [(1, 0), (2, 0), (3, 0), (3, 1), (3, 2)],
+        );
+        assert_eq!(*backoffs.borrow(), [1, 2]);
+        assert_eq!(g.attempts(), 3);
+        assert!(g.is_done());
+        assert_eq!(g.return_or_self().ok(), Some((Ok("done"), 3)));
+    }
+
+    #[test]
+    fn always_fails() {
+        let backoffs = RefCell::new(Vec::new());
+        let mut g = retry(flaky(u32::MAX), 4).with_backoff(|n| backoffs.borrow_mut().push(n));
+        assert_eq!((&mut g).count(), 4);
+        // no backoff after the last attempt
+        assert_eq!(*backoffs.borrow(), [1, 2, 3]);
+        assert_eq!(g.return_or_self().ok(), Some((Err(4), 4)));
+    }
+
+    #[test]
+    fn not_done() {
+        let mut g = retry(flaky(1), 3);
+        assert_eq!((&mut g).take(2).collect::<Vec<_>>(), [(1, 0), (2, 0)]);
+        assert_eq!(g.attempts(), 2);
+        let mut g = g.return_or_self().err().unwrap();
+        assert_eq!((&mut g).count(), 2);
+        assert_eq!(g.return_or_self().ok(), Some((Ok("done"), 2)));
+    }
+
+    #[test]
+    fn no_attempt() {
+        assert!(panic::catch_unwind(|| retry(flaky(0), 0)).is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    mod alloc_tests {
+        use super::flaky;
+        use crate::retry;
+        use std::vec::Vec;
+
+        #[test]
+        fn final_attempt_only() {
+            let mut g = retry(flaky(2), 5).final_attempt_only();
+            assert_eq!((&mut g).collect::<Vec<_>>(), [(3, 0), (3, 1), (3, 2)]);
+            assert_eq!(g.return_or_self().ok(), Some((Ok("done"), 3)));
+
+            let mut g = retry(flaky(u32::MAX), 3).final_attempt_only();
+            assert_eq!((&mut g).collect::<Vec<_>>(), [(3, 0)]);
+            assert_eq!(g.return_or_self().ok(), Some((Err(3), 3)));
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::future::Future;
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::fmt;
+
+use futures_channel::oneshot;
+use futures_core::stream::{FusedStream, Stream};
+
+use crate::GenIterReturn;
+
+/// the stream of [`GenIterReturn::into_stream_with_return`]
+pub struct ReturnStream<G: Coroutine + Unpin> {
+    gen: Option<G>,
+    ret: Option<oneshot::Sender<G::Return>>,
+}
+
+impl<G: Coroutine + Unpin> Stream for ReturnStream<G> {
+    type Item = G::Yield;
+
+    fn poll_next(self: Pin<&mut Self>, _: &mut Context) -> Poll<Option<G::Yield>> {
+        let this = self.get_mut();
+        let g = match this.gen {
+            Some(ref mut g) => g,
+            None => return Poll::Ready(None),
+        };
+
+        match Pin::new(g).resume(()) {
+            CoroutineState::Yielded(y) => Poll::Ready(Some(y)),
+            CoroutineState::Complete(r) => {
+                this.gen = None;
+                if let Some(tx) = this.ret.take() {
+                    // nobody is waiting if the future was dropped
+                    let _ = tx.send(r);
+                }
+                Poll::Ready(None)
+            },
+        }
+    }
+}
+
+impl<G: Coroutine + Unpin> FusedStream for ReturnStream<G> {
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.gen.is_none()
+    }
+}
+
+impl<G: Coroutine + Unpin + fmt::Debug> fmt::Debug for ReturnStream<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReturnStream").field("gen", &self.gen).finish_non_exhaustive()
+    }
+}
+
+/// the error of a [`ReturnFuture`], when its stream was dropped
+/// before the generator completed
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Incomplete;
+
+impl fmt::Display for Incomplete {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the stream was dropped before the generator completed")
+    }
+}
+
+/// a future of the return value of the generator behind a [`ReturnStream`],
+/// it resolves once the stream ended
+#[derive(Debug)]
+pub struct ReturnFuture<R>(oneshot::Receiver<R>);
+
+impl<R> Future for ReturnFuture<R> {
+    type Output = Result<R, Incomplete>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<R, Incomplete>> {
+        Pin::new(&mut self.get_mut().0).poll(cx).map(|r| r.map_err(|_| Incomplete))
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// split into a stream of the yields and a future of the return value,
+    /// that can be moved to different tasks.
+    ///
+    /// the future resolves when the stream ended, or with [`Incomplete`]
+    /// if the stream is dropped before that.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// extern crate futures;
+    /// extern crate gen_iter;
+    ///
+    /// use futures::executor::block_on;
+    /// use futures::stream::StreamExt;
+    /// use gen_iter::gen_iter_return;
+    ///
+    /// let (items, ret) = gen_iter_return!({
+    ///     yield 1;
+    ///     yield 2;
+    ///     "done"
+    /// }).into_stream_with_return();
+    ///
+    /// assert_eq!(block_on(items.collect::<Vec<_>>()), [1, 2]);
+    /// assert_eq!(block_on(ret), Ok("done"));
+    /// ```
+    pub fn into_stream_with_return(self) -> (ReturnStream<G>, ReturnFuture<G::Return>) {
+        let (tx, rx) = oneshot::channel();
+        let gen = match self.into_result() {
+            Ok(r) => {
+                let _ = tx.send(r);
+                return (ReturnStream { gen: None, ret: None }, ReturnFuture(rx));
+            },
+            Err(g) => g,
+        };
+
+        (ReturnStream { gen: Some(gen), ret: Some(tx) }, ReturnFuture(rx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Incomplete;
+    use crate::GenIterReturn;
+    use core::ops::Coroutine;
+    use futures::executor::block_on;
+    use futures::future::join;
+    use futures::stream::StreamExt;
+    use std::thread;
+    use std::vec::Vec;
+
+    fn letters() -> GenIterReturn<impl Coroutine<Yield = char, Return = usize> + Unpin> {
+        GenIterReturn::new(#[coroutine] || {
+            let mut count = 0;
+            for c in "abc".chars() {
+                count += 1;
+                yield c;
+            }
+            count
+        })
+    }
+
+    #[test]
+    fn stream_then_return() {
+        let (items, ret) = letters().into_stream_with_return();
+
+        let (items, ret) = block_on(join(items.collect::<Vec<_>>(), ret));
+        assert_eq!(items, ['a', 'b', 'c']);
+        assert_eq!(ret, Ok(3));
+    }
+
+    #[test]
+    fn across_threads() {
+        let (items, ret) = GenIterReturn::new(#[coroutine] || {
+            yield 1u8;
+            "sent"
+        }).into_stream_with_return();
+
+        let consumer = thread::spawn(move || block_on(items.collect::<Vec<_>>()));
+        assert_eq!(block_on(ret), Ok("sent"));
+        assert_eq!(consumer.join().unwrap(), [1]);
+    }
+
+    #[test]
+    fn dropped_early() {
+        let (mut items, ret) = letters().into_stream_with_return();
+        assert_eq!(block_on(items.next()), Some('a'));
+        drop(items);
+
+        assert_eq!(block_on(ret), Err(Incomplete));
+    }
+
+    #[test]
+    fn already_done() {
+        let mut g = letters();
+        assert_eq!((&mut g).count(), 3);
+
+        let (mut items, ret) = g.into_stream_with_return();
+        assert_eq!(block_on(items.next()), None);
+        assert_eq!(block_on(ret), Ok(3));
+    }
+}
@@ -0,0 +1,51 @@
+use core::cell::Cell;
+use core::fmt;
+
+use alloc::rc::Rc;
+
+/// a shared slot that receives the return value of a generator
+/// once the adapter driving it observes completion.
+///
+/// the slot can be cloned, all clones refer to the same value.
+pub struct ReturnSlot<R>(Rc<Cell<Option<R>>>);
+
+impl<R> ReturnSlot<R> {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        ReturnSlot(Rc::new(Cell::new(None)))
+    }
+
+    #[inline]
+    pub(crate) fn set(&self, r: R) {
+        self.0.set(Some(r));
+    }
+
+    /// take the return value out of the slot,
+    /// `None` if the generator is not done yet or it was already taken
+    #[inline]
+    pub fn take(&self) -> Option<R> {
+        self.0.take()
+    }
+
+    /// whether the slot holds a return value
+    #[inline]
+    pub fn is_set(&self) -> bool {
+        let r = self.0.take();
+        let set = r.is_some();
+        self.0.set(r);
+        set
+    }
+}
+
+impl<R> Clone for ReturnSlot<R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        ReturnSlot(self.0.clone())
+    }
+}
+
+impl<R> fmt::Debug for ReturnSlot<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReturnSlot").field("is_set", &self.is_set()).finish()
+    }
+}
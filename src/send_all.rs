@@ -0,0 +1,166 @@
+use core::ops::Coroutine;
+use core::iter::IntoIterator;
+use core::marker::Unpin;
+#[cfg(feature = "alloc")]
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::GenIterExchange;
+
+/// the result of [`GenIterExchange::send_all`]
+#[cfg(feature = "alloc")]
+pub enum SendAllOutcome<G: Coroutine<R> + Unpin, R> {
+    /// the generator completed, the rest of the inputs was dropped
+    Completed {
+        yields: Vec<G::Yield>,
+        ret: G::Return,
+    },
+    /// all inputs were sent, the generator can still be resumed
+    InputsExhausted {
+        yields: Vec<G::Yield>,
+        remaining: GenIterExchange<G, R>,
+    },
+}
+
+#[cfg(feature = "alloc")]
+impl<G, R> fmt::Debug for SendAllOutcome<G, R>
+where
+    G: Coroutine<R> + Unpin + fmt::Debug,
+    G::Yield: fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SendAllOutcome::Completed { ref yields, ref ret } => f
+                .debug_struct("Completed")
+                .field("yields", yields)
+                .field("ret", ret)
+                .finish(),
+            SendAllOutcome::InputsExhausted { ref yields, ref remaining } => f
+                .debug_struct("InputsExhausted")
+                .field("yields", yields)
+                .field("remaining", remaining)
+                .finish(),
+        }
+    }
+}
+
+impl<G: Coroutine<R> + Unpin, R> GenIterExchange<G, R> {
+    /// send every item of `inputs` to the generator, and collect what it yields.
+    ///
+    /// stops at the first of the inputs running out or the generator completing.
+    /// the input sent to the resume that completed the generator is dropped.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::{gen_exchange, SendAllOutcome};
+    ///
+    /// let g = gen_exchange!(|mut x: u32| {
+    ///     while x != 0 {
+    ///         x = yield x * 2;
+    ///     }
+    ///     "done"
+    /// });
+    ///
+    /// match g.send_all([1, 2, 0, 3]) {
+    ///     SendAllOutcome::Completed { yields, ret } => {
+    ///         assert_eq!(yields, [2, 4]);
+    ///         assert_eq!(ret, "done");
+    ///     },
+    ///     SendAllOutcome::InputsExhausted { .. } => unreachable!(),
+    /// }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn send_all<I>(self, inputs: I) -> SendAllOutcome<G, R>
+    where
+        I: IntoIterator<Item = R>,
+    {
+        let mut yields = Vec::new();
+        match self.send_all_with(inputs, |y| yields.push(y)) {
+            Ok(ret) => SendAllOutcome::Completed { yields, ret },
+            Err(remaining) => SendAllOutcome::InputsExhausted { yields, remaining },
+        }
+    }
+
+    /// like `send_all` (with `alloc`), but hands every yield to
+    /// `on_yield` instead of collecting them.
+    ///
+    /// returns the return value of the generator if it completed,
+    /// or `self` if the inputs ran out first.
+    pub fn send_all_with<I, F>(mut self, inputs: I, mut on_yield: F) -> Result<G::Return, Self>
+    where
+        I: IntoIterator<Item = R>,
+        F: FnMut(G::Yield),
+    {
+        for input in inputs {
+            match self.next_with(input) {
+                Some(y) => on_yield(y),
+                None => break,
+            }
+        }
+        self.return_or_self()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIterExchange;
+
+    fn doubler() -> GenIterExchange<impl ::core::ops::Coroutine<u32, Yield = u32, Return = u32> + Unpin, u32> {
+        GenIterExchange::new(#[coroutine] |mut x: u32| {
+            let mut sum = 0;
+            while x != 0 {
+                sum += x;
+                x = yield x * 2;
+            }
+            sum
+        })
+    }
+
+    #[test]
+    fn send_all_with() {
+        let mut seen = 0;
+        let ret = doubler().send_all_with([1, 2, 3, 0, 4], |y| seen += y);
+        assert_eq!(ret.ok(), Some(6));
+        assert_eq!(seen, 12);
+
+        let g = match doubler().send_all_with(1..3, |_| ()) {
+            Ok(_) => panic!("generator is done but should not"),
+            Err(g) => g,
+        };
+        assert_eq!(g.send_all_with([0], |_| ()).ok(), Some(3));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn send_all() {
+        use crate::SendAllOutcome;
+
+        match doubler().send_all([1, 2, 0, 5]) {
+            SendAllOutcome::Completed { yields, ret } => {
+                assert_eq!(yields, [2, 4]);
+                assert_eq!(ret, 3);
+            },
+            _ => panic!("unexpected outcome"),
+        }
+
+        let remaining = match doubler().send_all([3, 4]) {
+            SendAllOutcome::InputsExhausted { yields, remaining } => {
+                assert_eq!(yields, [6, 8]);
+                remaining
+            },
+            _ => panic!("unexpected outcome"),
+        };
+
+        match remaining.send_all([5, 0]) {
+            SendAllOutcome::Completed { yields, ret } => {
+                assert_eq!(yields, [10]);
+                assert_eq!(ret, 12);
+            },
+            _ => panic!("unexpected outcome"),
+        }
+    }
+}
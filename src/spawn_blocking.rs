@@ -0,0 +1,186 @@
+use core::ops::Coroutine;
+use core::marker::Unpin;
+
+use tokio::sync::mpsc;
+use tokio::task::{self, JoinHandle};
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::{GenIter, GenIterReturn};
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// drive the generator on the tokio blocking pool, its yields arrive
+    /// through the returned stream.
+    ///
+    /// the stream holds at most `buffer` yields, the generator waits when it is
+    /// full. dropping the stream stops the generator at its next yield,
+    /// that yield is lost.
+    ///
+    /// # Panics
+    /// if `buffer` is `0`, or when called outside of a tokio runtime.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// extern crate futures;
+    /// extern crate gen_iter;
+    /// extern crate tokio;
+    ///
+    /// use futures::StreamExt;
+    /// use gen_iter::gen_iter;
+    ///
+    /// let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// rt.block_on(async {
+    ///     let stream = gen_iter!({
+    ///         for i in 0..3 {
+    ///             // blocking work here
+    ///             yield i;
+    ///         }
+    ///     }).spawn_blocking_stream(1);
+    ///
+    ///     assert_eq!(stream.collect::<Vec<_>>().await, [0, 1, 2]);
+    /// });
+    /// ```
+    pub fn spawn_blocking_stream(self, buffer: usize) -> ReceiverStream<G::Yield>
+    where
+        G: Send + 'static,
+        G::Yield: Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(buffer);
+        task::spawn_blocking(move || {
+            for y in self {
+                if tx.blocking_send(y).is_err() {
+                    break;
+                }
+            }
+        });
+        ReceiverStream::new(rx)
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// like [`GenIter::spawn_blocking_stream`], the returned `JoinHandle`
+    /// gives back the return value of the generator, or the generator itself
+    /// if the stream was dropped before it completed.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// extern crate futures;
+    /// extern crate gen_iter;
+    /// extern crate tokio;
+    ///
+    /// use futures::StreamExt;
+    /// use gen_iter::gen_iter_return;
+    ///
+    /// let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// rt.block_on(async {
+    ///     let (stream, handle) = gen_iter_return!({
+    ///         yield 1;
+    ///         yield 2;
+    ///         return "done";
+    ///     }).spawn_blocking_stream(4);
+    ///
+    ///     assert_eq!(stream.collect::<Vec<_>>().await, [1, 2]);
+    ///     assert_eq!(handle.await.unwrap().ok(), Some("done"));
+    /// });
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn spawn_blocking_stream(mut self, buffer: usize) -> (ReceiverStream<G::Yield>, JoinHandle<Result<G::Return, Self>>)
+    where
+        G: Send + 'static,
+        G::Yield: Send + 'static,
+        G::Return: Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(buffer);
+        let handle = task::spawn_blocking(move || {
+            for y in &mut self {
+                if tx.blocking_send(y).is_err() {
+                    break;
+                }
+            }
+            self.return_or_self()
+        });
+        (ReceiverStream::new(rx), handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, GenIterReturn};
+    use futures::StreamExt;
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use std::vec::Vec;
+
+    #[tokio::test]
+    async fn stream_yields() {
+        let stream = GenIter(#[coroutine] || {
+            for i in 0..50u32 {
+                yield i * 2;
+            }
+        }).spawn_blocking_stream(4);
+
+        let items = stream.collect::<Vec<_>>().await;
+        assert_eq!(items, (0..50).map(|i| i * 2).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn return_value() {
+        let (mut stream, handle) = GenIterReturn::new(#[coroutine] || {
+            let mut sum = 0;
+            for i in 0..10u32 {
+                sum += i;
+                yield i;
+            }
+            sum
+        }).spawn_blocking_stream(2);
+
+        let mut count = 0;
+        while let Some(i) = stream.next().await {
+            assert_eq!(i, count);
+            count += 1;
+        }
+        assert_eq!(count, 10);
+        assert_eq!(handle.await.unwrap().ok(), Some(45));
+    }
+
+    #[tokio::test]
+    async fn cancellation() {
+        let (mut stream, handle) = GenIterReturn::new(#[coroutine] || {
+            let mut i = 0u64;
+            loop {
+                i += 1;
+                yield i;
+            }
+        }).spawn_blocking_stream(1);
+
+        assert_eq!(stream.next().await, Some(1));
+        drop(stream);
+
+        let g = tokio::time::timeout(Duration::from_secs(5), handle).await
+            .expect("the blocking task stopped")
+            .unwrap();
+        assert!(g.is_err());
+    }
+
+    #[tokio::test]
+    async fn cancellation_drops_generator() {
+        // the sender is dropped with the generator
+        let (alive_tx, alive_rx) = mpsc::channel::<()>();
+        let mut stream = GenIter(#[coroutine] move || {
+            let _alive = alive_tx;
+            loop {
+                yield ();
+            }
+        }).spawn_blocking_stream(1);
+
+        assert_eq!(stream.next().await, Some(()));
+        drop(stream);
+
+        let stopped = tokio::task::spawn_blocking(move || alive_rx.recv_timeout(Duration::from_secs(5)));
+        assert_eq!(stopped.await.unwrap(), Err(mpsc::RecvTimeoutError::Disconnected));
+    }
+}
@@ -0,0 +1,191 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::future::Future;
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::fmt;
+
+use crate::GenIterReturn;
+
+/// `StepFuture<G>` runs a generator that yields `()` as a [`Future`]
+/// of its return value.
+///
+/// Every poll resumes the generator up to [`steps`](StepFuture::steps) times
+/// (once by default). If it did not complete, the task is woken right away
+/// and the poll returns `Pending`, so that the executor can run other tasks
+/// between the steps. A long computation stays cancellable this way:
+/// dropping the future drops the generator.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::StepFuture;
+/// use std::future::Future;
+/// use std::pin::pin;
+/// use std::task::{Context, Poll, Waker};
+///
+/// let mut f = pin!(StepFuture::new(#[coroutine] || {
+///     let mut sum = 0;
+///     for i in 0..10 {
+///         sum += i;
+///         yield;
+///     }
+///     sum
+/// }).steps(4));
+///
+/// let mut cx = Context::from_waker(Waker::noop());
+/// assert_eq!(f.as_mut().poll(&mut cx), Poll::Pending);
+/// assert_eq!(f.as_mut().poll(&mut cx), Poll::Pending);
+/// assert_eq!(f.as_mut().poll(&mut cx), Poll::Ready(45));
+/// ```
+pub struct StepFuture<G: Coroutine<Yield = ()> + Unpin> {
+    state: Option<Result<G::Return, G>>,
+    steps: usize,
+}
+
+// the generator is `Unpin`, the return value is never pinned
+impl<G: Coroutine<Yield = ()> + Unpin> Unpin for StepFuture<G> {}
+
+impl<G: Coroutine<Yield = ()> + Unpin> StepFuture<G> {
+    #[inline]
+    pub fn new(g: G) -> Self {
+        StepFuture { state: Some(Err(g)), steps: 1 }
+    }
+
+    /// resume the generator up to `steps` times per poll
+    ///
+    /// # Panics
+    /// if `steps` is 0
+    #[inline]
+    pub fn steps(mut self, steps: usize) -> Self {
+        assert!(steps > 0, "a StepFuture needs at least one step per poll");
+        self.steps = steps;
+        self
+    }
+}
+
+impl<G: Coroutine<Yield = ()> + Unpin> Future for StepFuture<G> {
+    type Output = G::Return;
+
+    /// # Panics
+    /// if polled after it returned `Ready`
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<G::Return> {
+        let this = self.get_mut();
+        let mut g = match this.state.take().expect("StepFuture polled after completion") {
+            Ok(r) => return Poll::Ready(r),
+            Err(g) => g,
+        };
+
+        for _ in 0..this.steps {
+            if let CoroutineState::Complete(r) = Pin::new(&mut g).resume(()) {
+                return Poll::Ready(r);
+            }
+        }
+
+        this.state = Some(Err(g));
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+impl<G> fmt::Debug for StepFuture<G>
+where
+    G: Coroutine<Yield = ()> + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StepFuture")
+            .field("state", &self.state)
+            .field("steps", &self.steps)
+            .finish()
+    }
+}
+
+impl<G: Coroutine<Yield = ()> + Unpin> From<G> for StepFuture<G> {
+    #[inline]
+    fn from(g: G) -> Self {
+        StepFuture::new(g)
+    }
+}
+
+impl<G: Coroutine<Yield = ()> + Unpin> GenIterReturn<G> {
+    /// run the rest of the generator as a [`StepFuture`].
+    ///
+    /// if the generator is already done, the future is ready at the first poll.
+    #[inline]
+    pub fn into_future(self) -> StepFuture<G> {
+        StepFuture { state: Some(self.into_result()), steps: 1 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StepFuture;
+    use crate::GenIterReturn;
+    use core::future::Future;
+    use core::pin::Pin;
+    use core::task::{Context, Poll, Waker};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    struct CountWake(AtomicUsize);
+
+    impl Wake for CountWake {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn pending_between_steps() {
+        let mut f = StepFuture::new(#[coroutine] || {
+            yield;
+            yield;
+            "done"
+        });
+
+        let wakes = Arc::new(CountWake(AtomicUsize::new(0)));
+        let waker = Waker::from(wakes.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(Pin::new(&mut f).poll(&mut cx), Poll::Pending);
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+        assert_eq!(Pin::new(&mut f).poll(&mut cx), Poll::Pending);
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 2);
+        assert_eq!(Pin::new(&mut f).poll(&mut cx), Poll::Ready("done"));
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn into_future_when_done() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield;
+            7
+        });
+        assert_eq!((&mut g).count(), 1);
+
+        let mut f = g.into_future();
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(Pin::new(&mut f).poll(&mut cx), Poll::Ready(7));
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn block_on() {
+        use futures::executor::block_on;
+        use futures::future::join;
+
+        let count = |n: u32| GenIterReturn::new(#[coroutine] move || {
+            let mut total = 0;
+            for i in 0..n {
+                total += i;
+                yield;
+            }
+            total
+        });
+
+        assert_eq!(block_on(count(100).into_future().steps(7)), 4950);
+        assert_eq!(block_on(join(count(10).into_future(), count(20).into_future())), (45, 190));
+    }
+}
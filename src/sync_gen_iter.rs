@@ -0,0 +1,181 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::fmt;
+
+use std::sync::Mutex;
+
+use crate::GenIter;
+
+/// `SyncGenIter<G>` shares one generator among several threads.
+///
+/// `&SyncGenIter<G>` is an iterator, every `next` locks the generator,
+/// resumes it once and unlocks it, so each yield goes to exactly one thread.
+/// There is no fairness between the threads.
+///
+/// If the generator panics, the panic unwinds out of the `next` call
+/// that resumed it, and every later `next` returns `None`.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter, SyncGenIter};
+/// use std::sync::atomic::{AtomicU32, Ordering};
+/// use std::thread;
+///
+/// let shared = SyncGenIter::from(gen_iter!({
+///     for i in 1..=100u32 {
+///         yield i;
+///     }
+/// }));
+/// let sum = AtomicU32::new(0);
+///
+/// thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| for i in &shared {
+///             sum.fetch_add(i, Ordering::Relaxed);
+///         });
+///     }
+/// });
+/// assert_eq!(sum.into_inner(), 5050);
+/// ```
+pub struct SyncGenIter<G>(Mutex<Option<G>>)
+where
+    G: Coroutine<Return = ()> + Unpin;
+
+impl<G> SyncGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    #[inline]
+    pub fn new(g: G) -> Self {
+        SyncGenIter(Mutex::new(Some(g)))
+    }
+
+    /// whether the generator completed, or panicked
+    pub fn is_done(&self) -> bool {
+        match self.0.lock() {
+            Ok(g) => g.is_none(),
+            Err(_) => true,
+        }
+    }
+
+    /// get back the generator, `None` if it completed or panicked
+    pub fn into_inner(self) -> Option<G> {
+        self.0.into_inner().ok().flatten()
+    }
+}
+
+impl<G> Iterator for &SyncGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        // poisoned: the generator panicked while it was resumed
+        let mut g = self.0.lock().ok()?;
+        match Pin::new(g.as_mut()?).resume(()) {
+            CoroutineState::Yielded(y) => Some(y),
+            CoroutineState::Complete(()) => {
+                *g = None;
+                None
+            },
+        }
+    }
+}
+
+impl<G> FusedIterator for &SyncGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{}
+
+impl<G> From<G> for SyncGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    #[inline]
+    fn from(g: G) -> Self {
+        SyncGenIter::new(g)
+    }
+}
+
+impl<G> From<GenIter<G>> for SyncGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    #[inline]
+    fn from(g: GenIter<G>) -> Self {
+        SyncGenIter::new(g.0)
+    }
+}
+
+impl<G> fmt::Debug for SyncGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("SyncGenIter").field(&self.0).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SyncGenIter;
+    use crate::GenIter;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::thread;
+    use std::vec::Vec;
+
+    #[test]
+    fn drain_from_threads() {
+        let shared = SyncGenIter::from(GenIter(#[coroutine] || {
+            for i in 0..10_000u32 {
+                yield i;
+            }
+        }));
+
+        let mut seen = thread::scope(|s| {
+            let workers = (0..4).map(|_| s.spawn(|| (&shared).collect::<Vec<_>>())).collect::<Vec<_>>();
+            workers.into_iter().flat_map(|w| w.join().unwrap()).collect::<Vec<_>>()
+        });
+
+        // each thread sees its items in order
+        seen.sort_unstable();
+        // none is lost, none is seen twice
+        assert_eq!(seen, (0..10_000).collect::<Vec<_>>());
+        assert!(shared.is_done());
+        assert_eq!((&shared).next(), None);
+    }
+
+    #[test]
+    fn panic_poisons() {
+        let shared = SyncGenIter::new(#[coroutine] || {
+            yield 1;
+            panic!("generator failed");
+        });
+
+        assert_eq!((&shared).next(), Some(1));
+        let err = thread::scope(|s| s.spawn(|| (&shared).next()).join()).unwrap_err();
+        assert_eq!(err.downcast_ref::<&str>(), Some(&"generator failed"));
+
+        assert!(shared.is_done());
+        assert_eq!((&shared).next(), None);
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| (&shared).next())).is_ok());
+        assert!(shared.into_inner().is_none());
+    }
+
+    #[test]
+    fn into_inner() {
+        let shared = SyncGenIter::new(#[coroutine] || {
+            yield 1;
+            yield 2;
+        });
+        assert_eq!((&shared).next(), Some(1));
+
+        let mut g = GenIter(shared.into_inner().unwrap());
+        assert_eq!(g.next(), Some(2));
+        assert_eq!(g.next(), None);
+    }
+}
@@ -0,0 +1,197 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use crate::GenIterReturn;
+
+/// how a [`TakeResumes`] ended, from [`TakeResumes::into_outcome`]
+#[derive(Debug)]
+pub enum LimitOutcome<G: Coroutine + Unpin> {
+    /// the generator completed with this return value
+    Completed(G::Return),
+    /// the generator was resumed as many times as allowed,
+    /// the rest of it was never resumed
+    LimitReached(GenIterReturn<G>),
+    /// neither, the iteration stopped before
+    StillRunning(GenIterReturn<G>),
+}
+
+/// `TakeResumes<G>` is a [`GenIterReturn`] that is resumed at most `n` times,
+/// against a generator that could run forever.
+///
+/// created by [`GenIterReturn::take_resumes`]. Like `GenIterReturn`,
+/// `&mut TakeResumes<G>` is the iterator.
+///
+/// Unlike `Iterator::take`, the limit is on the resumes, including the one completing
+/// the generator, so it also bounds the work of the items a consumer like `filter`
+/// drops. A generator yielding `k` items takes `k + 1` resumes.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter_return, LimitOutcome};
+///
+/// let mut search = gen_iter_return!({
+///     for i in 1.. {
+///         yield i;
+///     }
+///     "not found"
+/// }).take_resumes(1000);
+///
+/// assert_eq!((&mut search).find(|i| i % 1024 == 0), None);
+/// assert!(matches!(search.into_outcome(), LimitOutcome::LimitReached(_)));
+/// ```
+pub struct TakeResumes<G: Coroutine + Unpin> {
+    gen: GenIterReturn<G>,
+    left: usize,
+}
+
+impl<G: Coroutine + Unpin> TakeResumes<G> {
+    /// the number of resumes left
+    #[inline]
+    pub fn resumes_left(&self) -> usize {
+        self.left
+    }
+
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.gen.is_done()
+    }
+
+    /// the return value if the generator completed, else the rest of the generator
+    pub fn into_outcome(self) -> LimitOutcome<G> {
+        match self.gen.return_or_self() {
+            Ok(r) => LimitOutcome::Completed(r),
+            Err(g) if self.left == 0 => LimitOutcome::LimitReached(g),
+            Err(g) => LimitOutcome::StillRunning(g),
+        }
+    }
+}
+
+impl<G: Coroutine + Unpin> Iterator for &mut TakeResumes<G> {
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        if self.left == 0 || self.gen.is_done() {
+            return None;
+        }
+        self.left -= 1;
+        (&mut self.gen).next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.gen.is_done() {
+            (0, Some(0))
+        } else {
+            (0, Some(self.left))
+        }
+    }
+}
+
+impl<G: Coroutine + Unpin> FusedIterator for &mut TakeResumes<G> {}
+
+impl<G> fmt::Debug for TakeResumes<G>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TakeResumes")
+            .field("gen", &self.gen)
+            .field("left", &self.left)
+            .finish()
+    }
+}
+
+impl<G: Coroutine + Unpin> GenIterReturn<G> {
+    /// resume this generator at most `n` times, see [`TakeResumes`]
+    #[inline]
+    pub fn take_resumes(self, n: usize) -> TakeResumes<G> {
+        TakeResumes { gen: self, left: n }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIterReturn, LimitOutcome};
+    use std::vec::Vec;
+
+    #[test]
+    fn below() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            yield 2;
+            yield 3;
+            "done"
+        }).take_resumes(2);
+        assert_eq!((&mut g).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(g.resumes_left(), 0);
+        match g.into_outcome() {
+            LimitOutcome::LimitReached(mut rest) => {
+                assert_eq!((&mut rest).collect::<Vec<_>>(), [3]);
+                assert_eq!(rest.return_or_self().ok(), Some("done"));
+            },
+            _ => panic!("expected the limit to be reached"),
+        }
+
+        // every yield, but not the completion
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            yield 2;
+            yield 3;
+            "done"
+        }).take_resumes(3);
+        assert_eq!((&mut g).count(), 3);
+        assert!(!g.is_done());
+        match g.into_outcome() {
+            LimitOutcome::LimitReached(rest) => assert!(rest.return_or_self().is_err()),
+            _ => panic!("expected the limit to be reached"),
+        }
+    }
+
+    #[test]
+    fn equal_and_above() {
+        for n in [4, 10] {
+            let mut g = GenIterReturn::new(#[coroutine] || {
+                yield 1;
+                yield 2;
+                yield 3;
+                "done"
+            }).take_resumes(n);
+            assert_eq!((&mut g).collect::<Vec<_>>(), [1, 2, 3]);
+            // 3 yields, 4 resumes
+            assert_eq!(g.resumes_left(), n - 4);
+            assert!(matches!(g.into_outcome(), LimitOutcome::Completed("done")));
+        }
+    }
+
+    #[test]
+    fn still_running() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield 1;
+            yield 2;
+            yield 3;
+            "done"
+        }).take_resumes(10);
+        let mut it = &mut g;
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(it.size_hint(), (0, Some(9)));
+        match g.into_outcome() {
+            LimitOutcome::StillRunning(mut rest) => assert_eq!((&mut rest).count(), 2),
+            _ => panic!("expected the generator to be running"),
+        }
+    }
+
+    #[test]
+    fn counts_filtered_resumes() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            for i in 0.. {
+                yield i;
+            }
+        }).take_resumes(100);
+        assert_eq!((&mut g).filter(|i| i % 7 == 0).count(), 15);
+        assert!(matches!(g.into_outcome(), LimitOutcome::LimitReached(_)));
+    }
+}
@@ -0,0 +1,221 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::cell::RefCell;
+use core::pin::Pin;
+use core::fmt;
+
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+
+use crate::{GenIterReturn, ReturnSlot};
+
+struct Shared<G: Coroutine + Unpin> {
+    // `None` once the generator completed
+    gen: Option<G>,
+    slot: ReturnSlot<G::Return>,
+    // the items one half is ahead of the other
+    buffer: VecDeque<G::Yield>,
+    // the half the items in `buffer` are for
+    behind: bool,
+}
+
+/// `TeeHalf<G>` is one of the two iterators over the items of a generator,
+/// created by [`GenIterReturn::tee`], with the `alloc` feature.
+///
+/// Both halves go over every item. The items one of them is ahead of the other
+/// are kept until the other one gets them, so the buffer only grows as far as they
+/// drift apart. Once the generator completes, its return value goes into the
+/// [`ReturnSlot`]. A half can be dropped, the other one goes on on its own.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter_return;
+///
+/// let (left, right, ret) = gen_iter_return!({
+///     yield 1;
+///     yield 2;
+///     "done"
+/// }).tee();
+///
+/// assert_eq!(left.zip(right.map(|x| x * 10)).collect::<Vec<_>>(), [(1, 10), (2, 20)]);
+/// assert_eq!(ret.take(), Some("done"));
+/// ```
+pub struct TeeHalf<G: Coroutine + Unpin> {
+    shared: Rc<RefCell<Shared<G>>>,
+    id: bool,
+}
+
+impl<G: Coroutine + Unpin> TeeHalf<G> {
+    #[cfg(test)]
+    pub(crate) fn buffered(&self) -> usize {
+        self.shared.borrow().buffer.len()
+    }
+}
+
+impl<G> Iterator for TeeHalf<G>
+where
+    G: Coroutine + Unpin,
+    G::Yield: Clone,
+{
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        let alone = Rc::strong_count(&self.shared) == 1;
+        let mut shared = self.shared.borrow_mut();
+        if shared.behind == self.id {
+            if let Some(y) = shared.buffer.pop_front() {
+                return Some(y);
+            }
+        }
+        match Pin::new(shared.gen.as_mut()?).resume(()) {
+            CoroutineState::Yielded(y) => {
+                if !alone {
+                    shared.behind = !self.id;
+                    shared.buffer.push_back(y.clone());
+                }
+                Some(y)
+            },
+            CoroutineState::Complete(r) => {
+                shared.gen = None;
+                shared.slot.set(r);
+                None
+            },
+        }
+    }
+}
+
+impl<G> FusedIterator for TeeHalf<G>
+where
+    G: Coroutine + Unpin,
+    G::Yield: Clone,
+{}
+
+impl<G: Coroutine + Unpin> Drop for TeeHalf<G> {
+    fn drop(&mut self) {
+        // the items kept for this half are not needed anymore
+        let mut shared = self.shared.borrow_mut();
+        if shared.behind == self.id {
+            shared.buffer.clear();
+        }
+    }
+}
+
+impl<G> fmt::Debug for TeeHalf<G>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Yield: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let shared = self.shared.borrow();
+        let buffer = if shared.behind == self.id { Some(&shared.buffer) } else { None };
+        f.debug_struct("TeeHalf")
+            .field("gen", &shared.gen)
+            .field("buffer", &buffer)
+            .finish()
+    }
+}
+
+impl<G> GenIterReturn<G>
+where
+    G: Coroutine + Unpin,
+    G::Yield: Clone,
+{
+    /// split this generator into two iterators over its items, and a slot
+    /// for its return value, see [`TeeHalf`]
+    pub fn tee(self) -> (TeeHalf<G>, TeeHalf<G>, ReturnSlot<G::Return>) {
+        let slot = ReturnSlot::new();
+        let gen = match self.into_result() {
+            Ok(r) => {
+                slot.set(r);
+                None
+            },
+            Err(g) => Some(g),
+        };
+        let shared = Rc::new(RefCell::new(Shared {
+            gen,
+            slot: slot.clone(),
+            buffer: VecDeque::new(),
+            behind: false,
+        }));
+        (TeeHalf { shared: shared.clone(), id: false }, TeeHalf { shared, id: true }, slot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIterReturn;
+    use std::vec::Vec;
+
+    fn five() -> GenIterReturn<impl core::ops::Coroutine<Yield = u32, Return = &'static str> + Unpin> {
+        GenIterReturn::new(#[coroutine] || {
+            for i in 0..5 {
+                yield i;
+            }
+            "done"
+        })
+    }
+
+    #[test]
+    fn one_then_the_other() {
+        let (mut a, mut b, ret) = five().tee();
+        assert_eq!(a.by_ref().take(3).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!((a.buffered(), b.buffered()), (3, 3));
+        assert_eq!(b.next(), Some(0));
+        assert_eq!(b.buffered(), 2);
+
+        assert_eq!(a.by_ref().collect::<Vec<_>>(), [3, 4]);
+        assert_eq!(ret.take(), Some("done"));
+        assert_eq!(b.collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert_eq!(a.buffered(), 0);
+    }
+
+    #[test]
+    fn lockstep() {
+        let (mut a, mut b, ret) = five().tee();
+        for i in 0..5 {
+            // the half behind catches up, the buffer never grows past one item
+            if i % 2 == 0 {
+                assert_eq!((a.next(), b.next()), (Some(i), Some(i)));
+            } else {
+                assert_eq!((b.next(), a.next()), (Some(i), Some(i)));
+            }
+            assert_eq!(a.buffered(), 0);
+        }
+        assert!(!ret.is_set());
+        assert_eq!((a.next(), b.next()), (None, None));
+        assert_eq!(ret.take(), Some("done"));
+    }
+
+    #[test]
+    fn drop_one_half() {
+        let (mut a, b, ret) = five().tee();
+        assert_eq!(a.next(), Some(0));
+        assert_eq!(a.buffered(), 1);
+        drop(b);
+        assert_eq!(a.buffered(), 0);
+        // nothing is kept for the dropped half
+        assert_eq!(a.next(), Some(1));
+        assert_eq!(a.buffered(), 0);
+        assert_eq!(a.collect::<Vec<_>>(), [2, 3, 4]);
+        assert_eq!(ret.take(), Some("done"));
+
+        // dropping the half ahead keeps the items of the other one
+        let (mut a, mut b, _) = five().tee();
+        assert_eq!(a.by_ref().take(2).count(), 2);
+        drop(a);
+        assert_eq!(b.buffered(), 2);
+        assert_eq!(b.by_ref().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+        assert_eq!(b.buffered(), 0);
+    }
+
+    #[test]
+    fn already_done() {
+        let mut g = five();
+        assert_eq!((&mut g).count(), 5);
+        let (mut a, mut b, ret) = g.tee();
+        assert_eq!(ret.take(), Some("done"));
+        assert_eq!((a.next(), b.next()), (None, None));
+    }
+}
@@ -0,0 +1,225 @@
+use core::ops::Coroutine;
+use core::iter::Iterator;
+use core::marker::Unpin;
+use core::time::Duration;
+use core::fmt;
+
+use std::thread;
+use std::time::Instant;
+
+use crate::GenIter;
+
+/// below this wait, [`Throttle`] spins instead of sleeping,
+/// which could oversleep by the granularity of the scheduler
+const SPIN_BELOW: Duration = Duration::from_millis(1);
+
+/// the result of [`Throttle::poll_throttle`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ThrottlePoll<Y> {
+    /// too early for the next item, try again after this duration
+    Wait(Duration),
+    /// the next item of the generator
+    Item(Y),
+    /// the generator completed
+    Done,
+}
+
+/// `Throttle<G>` iterates over a generator like [`GenIter`],
+/// with at least a minimum interval between two items.
+///
+/// created by [`GenIter::throttle`].
+///
+/// The interval is measured from the delivery of the previous item, so the
+/// first item is immediate, and the time spent by the consumer counts.
+/// `next()` sleeps until the interval has passed, spinning for the last
+/// millisecond, then resumes the generator. [`poll_throttle`](Throttle::poll_throttle)
+/// never blocks, for a loop doing something else meanwhile.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter;
+/// use std::time::{Duration, Instant};
+///
+/// let start = Instant::now();
+/// let ticks = gen_iter!({
+///     for i in 0..3 {
+///         yield i;
+///     }
+/// }).throttle(Duration::from_millis(10)).count();
+///
+/// assert_eq!(ticks, 3);
+/// assert!(start.elapsed() >= Duration::from_millis(20));
+/// ```
+pub struct Throttle<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    gen: GenIter<G>,
+    min_interval: Duration,
+    // when the next item can be delivered, `None` before the first one
+    ready_at: Option<Instant>,
+}
+
+impl<G> Throttle<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// the minimum interval between two items
+    #[inline]
+    pub fn min_interval(&self) -> Duration {
+        self.min_interval
+    }
+
+    /// the next item if the interval has passed, without waiting
+    pub fn poll_throttle(&mut self) -> ThrottlePoll<G::Yield> {
+        if let Some(ready_at) = self.ready_at {
+            let now = Instant::now();
+            if now < ready_at {
+                return ThrottlePoll::Wait(ready_at - now);
+            }
+        }
+        match self.resume() {
+            Some(y) => ThrottlePoll::Item(y),
+            None => ThrottlePoll::Done,
+        }
+    }
+
+    /// get back the generator
+    #[inline]
+    pub fn into_inner(self) -> GenIter<G> {
+        self.gen
+    }
+
+    fn resume(&mut self) -> Option<G::Yield> {
+        let y = self.gen.next()?;
+        if !self.min_interval.is_zero() {
+            self.ready_at = Some(Instant::now() + self.min_interval);
+        }
+        Some(y)
+    }
+}
+
+impl<G> Iterator for Throttle<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        if let Some(ready_at) = self.ready_at {
+            loop {
+                let now = Instant::now();
+                if now >= ready_at {
+                    break;
+                }
+                let wait = ready_at - now;
+                if wait > SPIN_BELOW {
+                    thread::sleep(wait - SPIN_BELOW);
+                } else {
+                    core::hint::spin_loop();
+                }
+            }
+        }
+        self.resume()
+    }
+}
+
+impl<G> fmt::Debug for Throttle<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Throttle")
+            .field("gen", &self.gen)
+            .field("min_interval", &self.min_interval)
+            .field("ready_at", &self.ready_at)
+            .finish()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// deliver at most one item every `min_interval`, see [`Throttle`]
+    #[inline]
+    pub fn throttle(self, min_interval: Duration) -> Throttle<G> {
+        Throttle { gen: self, min_interval, ready_at: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, ThrottlePoll};
+    use core::cell::Cell;
+    use std::time::{Duration, Instant};
+
+    const INTERVAL: Duration = Duration::from_millis(40);
+
+    #[test]
+    fn spaced_items() {
+        let start = Instant::now();
+        let mut it = GenIter(#[coroutine] || {
+            for i in 0..3 {
+                yield i;
+            }
+        }).throttle(INTERVAL);
+        assert_eq!(it.next(), Some(0));
+
+        // the interval starts during the previous `next()`
+        let mut asked = start;
+        for i in 1..3 {
+            let now = Instant::now();
+            assert_eq!(it.next(), Some(i));
+            assert!(asked.elapsed() >= INTERVAL);
+            asked = now;
+        }
+        assert_eq!(it.next(), None);
+        assert!(start.elapsed() >= 2 * INTERVAL);
+    }
+
+    #[test]
+    fn poll_throttle() {
+        let resumes = Cell::new(0);
+        let mut it = GenIter(#[coroutine] || {
+            for i in 0..2 {
+                resumes.set(resumes.get() + 1);
+                yield i;
+            }
+        }).throttle(INTERVAL);
+        assert_eq!(it.poll_throttle(), ThrottlePoll::Item(0));
+
+        match it.poll_throttle() {
+            ThrottlePoll::Wait(wait) => assert!(wait <= INTERVAL),
+            other => panic!("expected a wait, got {:?}", other),
+        }
+        // the generator was not resumed
+        assert_eq!(resumes.get(), 1);
+
+        std::thread::sleep(INTERVAL);
+        assert_eq!(it.poll_throttle(), ThrottlePoll::Item(1));
+        std::thread::sleep(INTERVAL);
+        assert_eq!(it.poll_throttle(), ThrottlePoll::Done);
+    }
+
+    #[test]
+    fn zero_interval() {
+        let mut it = GenIter(#[coroutine] || {
+            for i in 0..1000 {
+                yield i;
+            }
+        }).throttle(Duration::ZERO);
+        for i in 0..1000 {
+            assert_eq!(it.poll_throttle(), ThrottlePoll::Item(i));
+        }
+        assert_eq!(it.poll_throttle(), ThrottlePoll::Done);
+
+        let it = GenIter(#[coroutine] || {
+            for i in 0..1000 {
+                yield i;
+            }
+        }).throttle(Duration::ZERO);
+        assert_eq!(it.sum::<u32>(), 499500);
+    }
+}
@@ -0,0 +1,233 @@
+use core::ops::Coroutine;
+use core::iter::Iterator;
+use core::marker::Unpin;
+use core::time::Duration;
+use core::fmt;
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use crate::GenIter;
+
+/// `TimedGenIter<G>` iterates over a generator like [`GenIter`], and measures
+/// how long every resume takes, to find the slow yield points of a generator.
+///
+/// created by [`GenIter::timed`].
+///
+/// The resumes are numbered from 0: resume `i` gives the item `i`, and the last one
+/// completes the generator. Each `next()` reads the clock twice, the statistics are
+/// only computed when queried, and the last durations are only kept after
+/// [`keep_samples`](TimedGenIter::keep_samples).
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter;
+/// use std::time::Duration;
+///
+/// let mut timed = gen_iter!({
+///     yield 'a';
+///     std::thread::sleep(Duration::from_millis(20));
+///     yield 'b';
+/// }).timed();
+///
+/// assert_eq!(timed.by_ref().collect::<String>(), "ab");
+/// assert_eq!(timed.resumes(), 3);
+/// assert_eq!(timed.max_index(), Some(1));
+/// assert!(timed.max_duration() >= Duration::from_millis(20));
+/// ```
+pub struct TimedGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    gen: GenIter<G>,
+    resumes: usize,
+    last: Duration,
+    total: Duration,
+    max: Duration,
+    max_index: usize,
+    samples: VecDeque<Duration>,
+    keep: usize,
+}
+
+impl<G> TimedGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// also keep the durations of the last `n` resumes, see [`samples`](TimedGenIter::samples)
+    pub fn keep_samples(mut self, n: usize) -> Self {
+        self.keep = n;
+        while self.samples.len() > n {
+            self.samples.pop_front();
+        }
+        self.samples.reserve(n - self.samples.len());
+        self
+    }
+
+    /// how many times the generator was resumed
+    #[inline]
+    pub fn resumes(&self) -> usize {
+        self.resumes
+    }
+
+    /// the duration of the last resume, zero before the first one
+    #[inline]
+    pub fn last_duration(&self) -> Duration {
+        self.last
+    }
+
+    /// the duration of all the resumes
+    #[inline]
+    pub fn total_duration(&self) -> Duration {
+        self.total
+    }
+
+    /// the duration of the slowest resume, zero before the first one
+    #[inline]
+    pub fn max_duration(&self) -> Duration {
+        self.max
+    }
+
+    /// the index of the slowest resume, the first one on ties
+    #[inline]
+    pub fn max_index(&self) -> Option<usize> {
+        if self.resumes == 0 {
+            None
+        } else {
+            Some(self.max_index)
+        }
+    }
+
+    /// the durations of the last resumes kept, oldest first
+    #[inline]
+    pub fn samples(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.samples.iter().copied()
+    }
+
+    /// get back the generator
+    #[inline]
+    pub fn into_inner(self) -> GenIter<G> {
+        self.gen
+    }
+}
+
+impl<G> Iterator for TimedGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        let start = Instant::now();
+        let next = self.gen.next();
+        let elapsed = start.elapsed();
+
+        if elapsed > self.max || self.resumes == 0 {
+            self.max = elapsed;
+            self.max_index = self.resumes;
+        }
+        self.resumes += 1;
+        self.last = elapsed;
+        self.total += elapsed;
+        if self.keep > 0 {
+            if self.samples.len() == self.keep {
+                self.samples.pop_front();
+            }
+            self.samples.push_back(elapsed);
+        }
+        next
+    }
+}
+
+impl<G> fmt::Debug for TimedGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TimedGenIter")
+            .field("gen", &self.gen)
+            .field("resumes", &self.resumes)
+            .field("total", &self.total)
+            .field("max", &self.max)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// measure every resume of this generator, see [`TimedGenIter`]
+    #[inline]
+    pub fn timed(self) -> TimedGenIter<G> {
+        TimedGenIter {
+            gen: self,
+            resumes: 0,
+            last: Duration::ZERO,
+            total: Duration::ZERO,
+            max: Duration::ZERO,
+            max_index: 0,
+            samples: VecDeque::new(),
+            keep: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIter;
+    use std::thread;
+    use std::time::Duration;
+    use std::vec::Vec;
+
+    const SLOW: Duration = Duration::from_millis(30);
+
+    #[test]
+    fn max_index() {
+        let mut timed = GenIter(#[coroutine] || {
+            for i in 0..6 {
+                if i == 3 {
+                    thread::sleep(SLOW);
+                }
+                yield i;
+            }
+        }).timed();
+        assert_eq!(timed.max_index(), None);
+        assert_eq!(timed.last_duration(), Duration::ZERO);
+
+        assert_eq!(timed.by_ref().take(3).count(), 3);
+
+        assert_eq!(timed.next(), Some(3));
+        assert!(timed.last_duration() >= SLOW);
+        assert_eq!(timed.by_ref().count(), 2);
+
+        assert_eq!(timed.resumes(), 7);
+        assert_eq!(timed.max_index(), Some(3));
+        assert!(timed.max_duration() >= SLOW);
+        assert!(timed.total_duration() >= timed.max_duration());
+    }
+
+    #[test]
+    fn samples() {
+        let mut timed = GenIter(#[coroutine] || {
+            for i in 0..6 {
+                if i == 4 {
+                    thread::sleep(SLOW);
+                }
+                yield i;
+            }
+        }).timed().keep_samples(3);
+        assert_eq!(timed.samples().count(), 0);
+
+        timed.by_ref().take(5).for_each(drop);
+        let samples = timed.samples().collect::<Vec<_>>();
+        assert_eq!(samples.len(), 3);
+        // resumes 2, 3 and 4
+        assert!(samples[2] >= SLOW);
+        assert_eq!(timed.max_index(), Some(4));
+
+        let timed = timed.keep_samples(1);
+        assert_eq!(timed.samples().collect::<Vec<_>>(), [samples[2]]);
+        assert_eq!(timed.into_inner().collect::<Vec<_>>(), [5]);
+    }
+}
@@ -0,0 +1,328 @@
+use core::ops::{Coroutine, CoroutineState};
+use core::marker::Unpin;
+use core::pin::Pin;
+use core::fmt;
+
+use tracing::{Level, Span};
+
+use crate::{GenIter, GenIterReturn};
+
+/// `tracing::event!` with a level only known at runtime
+macro_rules! event_at {
+    ($level:expr, $($rest:tt)+) => {
+        match $level {
+            Level::ERROR => tracing::event!(Level::ERROR, $($rest)+),
+            Level::WARN => tracing::event!(Level::WARN, $($rest)+),
+            Level::INFO => tracing::event!(Level::INFO, $($rest)+),
+            Level::DEBUG => tracing::event!(Level::DEBUG, $($rest)+),
+            Level::TRACE => tracing::event!(Level::TRACE, $($rest)+),
+        }
+    };
+}
+
+/// `tracing::span!` with a level only known at runtime
+fn span_at(level: Level, name: &'static str) -> Span {
+    match level {
+        Level::ERROR => tracing::span!(Level::ERROR, "gen_iter", name),
+        Level::WARN => tracing::span!(Level::WARN, "gen_iter", name),
+        Level::INFO => tracing::span!(Level::INFO, "gen_iter", name),
+        Level::DEBUG => tracing::span!(Level::DEBUG, "gen_iter", name),
+        Level::TRACE => tracing::span!(Level::TRACE, "gen_iter", name),
+    }
+}
+
+/// a generator that reports its progress to `tracing`.
+///
+/// created by [`GenIter::traced`] and [`GenIterReturn::traced`],
+/// or their `traced_at` variants choosing the levels.
+///
+/// - a `gen_iter` span, with a `name` field, lives as long as the generator,
+///   and is entered during every resume, so the events of the generator body
+///   belong to it
+/// - every yield is a `"yield"` event with its `index`, at the resume level
+/// - the end of the generator is a `"complete"` event with the number of `yields`,
+///   at the span level. `returned` is `true` when the generator completed,
+///   `false` when it was dropped before
+///
+/// With the default levels the span and the `"complete"` event are `INFO`
+/// and the `"yield"` events are `TRACE`.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter_return;
+///
+/// let mut g = gen_iter_return!({
+///     for i in 0..3 {
+///         yield i;
+///     }
+///     "done"
+/// }).traced("numbers");
+///
+/// // gen_iter{name="numbers"}: yield index=0, ..., complete yields=3 returned=true
+/// assert_eq!((&mut g).sum::<i32>(), 3);
+/// assert_eq!(g.return_or_self().ok(), Some("done"));
+/// ```
+pub struct Traced<G> {
+    gen: G,
+    span: Span,
+    resume_level: Level,
+    span_level: Level,
+    yields: usize,
+    done: bool,
+}
+
+impl<G> Traced<G> {
+    fn new(gen: G, name: &'static str, span_level: Level, resume_level: Level) -> Self {
+        Traced {
+            gen,
+            span: span_at(span_level, name),
+            resume_level,
+            span_level,
+            yields: 0,
+            done: false,
+        }
+    }
+
+    /// the span of the generator
+    #[inline]
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
+// the span and the levels are never pinned
+impl<G: Unpin> Unpin for Traced<G> {}
+
+impl<G, R> Coroutine<R> for Traced<G>
+where
+    G: Coroutine<R> + Unpin,
+{
+    type Yield = G::Yield;
+    type Return = G::Return;
+
+    fn resume(self: Pin<&mut Self>, arg: R) -> CoroutineState<Self::Yield, Self::Return> {
+        let this = self.get_mut();
+        let _entered = this.span.enter();
+        let state = Pin::new(&mut this.gen).resume(arg);
+        match state {
+            CoroutineState::Yielded(_) => {
+                event_at!(this.resume_level, index = this.yields, "yield");
+                this.yields += 1;
+            },
+            CoroutineState::Complete(_) => {
+                event_at!(this.span_level, yields = this.yields, returned = true, "complete");
+                this.done = true;
+            },
+        }
+        state
+    }
+}
+
+impl<G> Drop for Traced<G> {
+    fn drop(&mut self) {
+        if !self.done {
+            let _entered = self.span.enter();
+            event_at!(self.span_level, yields = self.yields, returned = false, "complete");
+        }
+    }
+}
+
+impl<G: fmt::Debug> fmt::Debug for Traced<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Traced")
+            .field("gen", &self.gen)
+            .field("span", &self.span)
+            .field("yields", &self.yields)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// report the progress of this generator to `tracing`, see [`Traced`]
+    #[inline]
+    pub fn traced(self, name: &'static str) -> GenIter<Traced<G>> {
+        self.traced_at(name, Level::INFO, Level::TRACE)
+    }
+
+    /// [`traced`](GenIter::traced), with the levels of the span and of the yield events
+    #[inline]
+    pub fn traced_at(self, name: &'static str, span_level: Level, resume_level: Level) -> GenIter<Traced<G>> {
+        GenIter(Traced::new(self.0, name, span_level, resume_level))
+    }
+}
+
+impl<G> GenIterReturn<G>
+where
+    G: Coroutine + Unpin,
+{
+    /// report the progress of this generator to `tracing`, see [`Traced`].
+    ///
+    /// if it is already done, there is nothing left to report.
+    #[inline]
+    pub fn traced(self, name: &'static str) -> GenIterReturn<Traced<G>> {
+        self.traced_at(name, Level::INFO, Level::TRACE)
+    }
+
+    /// [`traced`](GenIterReturn::traced), with the levels of the span and of the yield events
+    #[inline]
+    pub fn traced_at(self, name: &'static str, span_level: Level, resume_level: Level) -> GenIterReturn<Traced<G>> {
+        self.map_inner(|g| Traced::new(g, name, span_level, resume_level))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, GenIterReturn};
+    use core::fmt;
+    use core::sync::atomic::{AtomicU64, Ordering};
+    use std::collections::HashMap;
+    use std::format;
+    use std::string::String;
+    use std::sync::{Arc, Mutex};
+    use std::vec::Vec;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Level, Metadata, Subscriber};
+
+    /// an in-memory subscriber, logging a line per span and event
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<State>);
+
+    #[derive(Default)]
+    struct State {
+        next_id: AtomicU64,
+        names: Mutex<HashMap<u64, String>>,
+        entered: Mutex<Vec<u64>>,
+        log: Mutex<Vec<String>>,
+    }
+
+    struct Fields(String);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if !self.0.is_empty() {
+                self.0.push(' ');
+            }
+            if field.name() == "message" {
+                self.0 += &format!("{:?}", value);
+            } else {
+                self.0 += &format!("{}={:?}", field, value);
+            }
+        }
+    }
+
+    impl Recorder {
+        fn log(&self) -> Vec<String> {
+            core::mem::take(&mut *self.0.log.lock().unwrap())
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let id = self.0.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+            let mut fields = Fields(String::new());
+            span.record(&mut fields);
+            self.0.log.lock().unwrap().push(format!("{} new {}{{{}}}", span.metadata().level(), span.metadata().name(), fields.0));
+            self.0.names.lock().unwrap().insert(id, fields.0);
+            Id::from_u64(id)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(String::new());
+            event.record(&mut fields);
+            let span = match self.0.entered.lock().unwrap().last() {
+                Some(id) => self.0.names.lock().unwrap()[id].clone(),
+                None => String::from("-"),
+            };
+            self.0.log.lock().unwrap().push(format!("{} {{{}}}: {}", event.metadata().level(), span, fields.0));
+        }
+
+        fn enter(&self, span: &Id) {
+            self.0.entered.lock().unwrap().push(span.into_u64());
+        }
+
+        fn exit(&self, _: &Id) {
+            self.0.entered.lock().unwrap().pop();
+        }
+
+        fn try_close(&self, span: Id) -> bool {
+            let name = self.0.names.lock().unwrap()[&span.into_u64()].clone();
+            self.0.log.lock().unwrap().push(format!("close {{{}}}", name));
+            true
+        }
+    }
+
+    #[test]
+    fn three_yields() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let mut g = GenIter(#[coroutine] || {
+                yield 10;
+                tracing::info!("between");
+                yield 20;
+                yield 30;
+            }).traced("three");
+            assert_eq!(recorder.log(), ["INFO new gen_iter{name=\"three\"}"]);
+            assert_eq!(g.by_ref().sum::<u32>(), 60);
+            assert_eq!(recorder.log(), [
+                "TRACE {name=\"three\"}: yield index=0",
+                "INFO {name=\"three\"}: between",
+                "TRACE {name=\"three\"}: yield index=1",
+                "TRACE {name=\"three\"}: yield index=2",
+                "INFO {name=\"three\"}: complete yields=3 returned=true",
+            ]);
+            drop(g);
+            assert_eq!(recorder.log(), ["close {name=\"three\"}"]);
+        });
+    }
+
+    #[test]
+    fn dropped_early() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let mut g = GenIter(#[coroutine] || {
+                yield 10;
+                yield 20;
+            }).traced_at("early", Level::DEBUG, Level::INFO);
+            assert_eq!(g.next(), Some(10));
+            drop(g);
+            assert_eq!(recorder.log(), [
+                "DEBUG new gen_iter{name=\"early\"}",
+                "INFO {name=\"early\"}: yield index=0",
+                "DEBUG {name=\"early\"}: complete yields=1 returned=false",
+                "close {name=\"early\"}",
+            ]);
+        });
+    }
+
+    #[test]
+    fn with_return() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let mut g = GenIterReturn::new(#[coroutine] || {
+                yield 'a';
+                "done"
+            }).traced("ret");
+            assert_eq!((&mut g).collect::<String>(), "a");
+            assert_eq!(g.return_or_self().ok(), Some("done"));
+            assert_eq!(recorder.log(), [
+                "INFO new gen_iter{name=\"ret\"}",
+                "TRACE {name=\"ret\"}: yield index=0",
+                "INFO {name=\"ret\"}: complete yields=1 returned=true",
+                "close {name=\"ret\"}",
+            ]);
+        });
+    }
+}
@@ -0,0 +1,125 @@
+use core::ops::Coroutine;
+use core::marker::Unpin;
+
+use crate::GenIter;
+
+/// an iterator over the values `f` takes out of `state`, until it returns `None`,
+/// like `core::iter::from_fn` with an explicit state.
+///
+/// `f` is not called anymore once it returned `None`.
+///
+/// ```
+/// #![feature(coroutines)]
+///
+/// use gen_iter::unfold;
+///
+/// // the Collatz sequence of 6
+/// let it = unfold(6u32, |n| match *n {
+///     0 => None,
+///     1 => {
+///         *n = 0;
+///         Some(1)
+///     },
+///     x => {
+///         *n = if x % 2 == 0 { x / 2 } else { 3 * x + 1 };
+///         Some(x)
+///     },
+/// });
+/// assert_eq!(it.collect::<Vec<_>>(), [6, 3, 10, 5, 16, 8, 4, 2, 1]);
+/// ```
+pub fn unfold<S, Y, F>(mut state: S, mut f: F) -> GenIter<impl Coroutine<Yield = Y, Return = ()> + Unpin>
+where
+    F: FnMut(&mut S) -> Option<Y>,
+{
+    GenIter(#[coroutine] move || {
+        while let Some(y) = f(&mut state) {
+            yield y;
+        }
+    })
+}
+
+/// an iterator starting at `first`, each item computed by `f` from the previous one,
+/// until it returns `None`, like `core::iter::successors`.
+///
+/// ```
+/// #![feature(coroutines)]
+///
+/// use gen_iter::successors;
+///
+/// let powers = successors(Some(1u8), |n| n.checked_mul(10));
+/// assert_eq!(powers.collect::<Vec<_>>(), [1, 10, 100]);
+/// ```
+pub fn successors<T, F>(first: Option<T>, mut f: F) -> GenIter<impl Coroutine<Yield = T, Return = ()> + Unpin>
+where
+    F: FnMut(&T) -> Option<T>,
+{
+    GenIter(#[coroutine] move || {
+        let mut next = first;
+        while let Some(item) = next {
+            next = f(&item);
+            yield item;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{successors, unfold};
+    use core::cell::Cell;
+    use core::iter;
+    use std::vec::Vec;
+
+    #[test]
+    fn unfold_like_from_fn() {
+        let counter = |limit: u32| move |n: &mut u32| {
+            *n += 1;
+            if *n <= limit { Some(*n) } else { None }
+        };
+        for limit in [0, 1, 5] {
+            let mut n = 0;
+            let f = counter(limit);
+            let expected = iter::from_fn(|| f(&mut n)).collect::<Vec<_>>();
+            assert_eq!(unfold(0, counter(limit)).collect::<Vec<_>>(), expected);
+        }
+
+        // nothing at all
+        assert_eq!(unfold((), |_| None::<u8>).count(), 0);
+    }
+
+    #[test]
+    fn unfold_not_called_after_none() {
+        let calls = Cell::new(0);
+        let mut it = unfold(3, |n| {
+            calls.set(calls.get() + 1);
+            if *n == 0 {
+                return None;
+            }
+            *n -= 1;
+            Some(*n)
+        });
+        assert_eq!(it.by_ref().collect::<Vec<_>>(), [2, 1, 0]);
+        assert_eq!(calls.get(), 4);
+    }
+
+    #[test]
+    fn successors_like_std() {
+        fn halves(n: &u32) -> Option<u32> {
+            if *n > 1 { Some(n / 2) } else { None }
+        }
+        for first in [None, Some(0), Some(1), Some(100)] {
+            assert_eq!(
+                successors(first, halves).collect::<Vec<_>>(),
+                iter::successors(first, halves).collect::<Vec<_>>(),
+            );
+        }
+        assert_eq!(successors(Some(100), halves).collect::<Vec<_>>(), [100, 50, 25, 12, 6, 3, 1]);
+    }
+
+    #[test]
+    fn composes() {
+        // a `Coroutine`, like any other generator of the crate
+        let mut g = successors(Some(1u64), |n| Some(n * 3)).0;
+        let mut it = crate::GenIterReturn::new(&mut g).take_resumes(4);
+        assert_eq!((&mut it).collect::<Vec<_>>(), [1, 3, 9, 27]);
+    }
+}
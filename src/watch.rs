@@ -0,0 +1,306 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::fmt;
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::GenIter;
+
+/// the slot shared by a `WatchedGenIter` and its handles,
+/// or by a `ProgressGenIter` and its handles
+pub(crate) struct Watch<Y> {
+    // only the pointer is swapped or cloned under the lock
+    latest: Mutex<Option<Arc<Y>>>,
+    finished: AtomicBool,
+}
+
+impl<Y> Watch<Y> {
+    pub(crate) fn new(latest: Option<Y>) -> Arc<Self> {
+        Arc::new(Watch {
+            latest: Mutex::new(latest.map(Arc::new)),
+            finished: AtomicBool::new(false),
+        })
+    }
+
+    pub(crate) fn store(&self, y: Y) {
+        let y = Arc::new(y);
+        let old = self.latest.lock().unwrap_or_else(PoisonError::into_inner).replace(y);
+        // dropped after the lock is released
+        drop(old);
+    }
+
+    #[inline]
+    pub(crate) fn finish(&self) {
+        self.finished.store(true, Ordering::Release);
+    }
+
+    #[inline]
+    fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Acquire)
+    }
+}
+
+/// `WatchedGenIter<G>` iterates over a generator like [`GenIter`], and stores
+/// a clone of every yield where its [`WatchHandle`]s can read it.
+///
+/// created by [`GenIter::with_watch`].
+pub struct WatchedGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    gen: GenIter<G>,
+    watch: Arc<Watch<G::Yield>>,
+}
+
+impl<G> WatchedGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: Clone,
+{
+    /// another handle on the yields of this iterator
+    #[inline]
+    pub fn watch(&self) -> WatchHandle<G::Yield> {
+        WatchHandle(self.watch.clone())
+    }
+}
+
+impl<G> Iterator for WatchedGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: Clone,
+{
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        if self.watch.is_finished() {
+            return None;
+        }
+        match self.gen.next() {
+            Some(y) => {
+                self.watch.store(y.clone());
+                Some(y)
+            },
+            None => {
+                self.watch.finish();
+                None
+            },
+        }
+    }
+}
+
+impl<G> FusedIterator for WatchedGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: Clone,
+{}
+
+impl<G> fmt::Debug for WatchedGenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WatchedGenIter")
+            .field("gen", &self.gen)
+            .field("finished", &self.watch.finished.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+/// `WatchHandle<Y>` reads the latest yield of a [`WatchedGenIter`],
+/// or the latest [`ProgressCounts`](crate::ProgressCounts) of a
+/// [`ProgressGenIter`](crate::ProgressGenIter), from any thread.
+///
+/// Cloning it is cheap, all the clones read the same slot.
+/// The slot holds the latest yield behind an `Arc`: the iterating thread only
+/// holds it to swap that pointer, and a reader to clone the pointer, so a slow
+/// `Clone` of the yield in [`latest`](WatchHandle::latest) never blocks the
+/// iterating thread.
+pub struct WatchHandle<Y>(pub(crate) Arc<Watch<Y>>);
+
+impl<Y: Clone> WatchHandle<Y> {
+    /// a clone of the latest yield, `None` before the first one
+    pub fn latest(&self) -> Option<Y> {
+        let latest = self.0.latest.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        // cloned after the lock is released
+        latest.map(|y| Y::clone(&y))
+    }
+
+    /// whether the generator completed.
+    ///
+    /// stays `false` if the iterator is dropped before the end.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.0.is_finished()
+    }
+}
+
+impl<Y> Clone for WatchHandle<Y> {
+    #[inline]
+    fn clone(&self) -> Self {
+        WatchHandle(self.0.clone())
+    }
+}
+
+impl<Y> fmt::Debug for WatchHandle<Y> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WatchHandle")
+            .field("finished", &self.0.finished.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: Clone + Send,
+{
+    /// iterate as usual, while the returned handle can read the latest yield
+    /// from another thread, like for a progress report.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_iter;
+    ///
+    /// let (mut it, progress) = gen_iter!({
+    ///     for i in 1..=3 {
+    ///         yield i;
+    ///     }
+    /// }).with_watch();
+    ///
+    /// assert_eq!(progress.latest(), None);
+    /// assert_eq!(it.next(), Some(1));
+    /// assert_eq!(progress.latest(), Some(1));
+    ///
+    /// assert_eq!(it.count(), 2);
+    /// assert_eq!(progress.latest(), Some(3));
+    /// assert!(progress.is_finished());
+    /// ```
+    pub fn with_watch(self) -> (WatchedGenIter<G>, WatchHandle<G::Yield>) {
+        let watch = Watch::new(None);
+        let handle = WatchHandle(watch.clone());
+        (WatchedGenIter { gen: self, watch }, handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIter;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::vec::Vec;
+
+    #[test]
+    fn poll_from_other_thread() {
+        let (it, progress) = GenIter(#[coroutine] || {
+            for i in 0..10_000u32 {
+                yield i;
+            }
+        }).with_watch();
+
+        let poller = {
+            let progress = progress.clone();
+            thread::spawn(move || {
+                let mut seen = Vec::new();
+                while !progress.is_finished() {
+                    if let Some(i) = progress.latest() {
+                        seen.push(i);
+                    }
+                }
+                seen
+            })
+        };
+
+        assert_eq!(it.count(), 10_000);
+        let seen = poller.join().unwrap();
+        // the poller only ever sees the values moving forward
+        assert!(seen.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(progress.latest(), Some(9_999));
+        assert!(progress.is_finished());
+    }
+
+    #[test]
+    fn finished_flips_at_the_end() {
+        let (step_tx, step_rx) = mpsc::channel::<()>();
+        let (done_tx, done_rx) = mpsc::channel();
+        let (mut it, progress) = GenIter(#[coroutine] || {
+            yield "a";
+            yield "b";
+        }).with_watch();
+
+        let iterating = thread::spawn(move || {
+            for () in step_rx {
+                done_tx.send(it.next()).unwrap();
+            }
+            it
+        });
+
+        for (item, finished) in [(Some("a"), false), (Some("b"), false), (None, true)] {
+            step_tx.send(()).unwrap();
+            assert_eq!(done_rx.recv().unwrap(), item);
+            assert_eq!(progress.latest(), Some(item.unwrap_or("b")));
+            assert_eq!(progress.is_finished(), finished);
+        }
+        drop(step_tx);
+
+        let mut it = iterating.join().unwrap();
+        assert_eq!(it.next(), None);
+        assert_eq!(it.watch().latest(), Some("b"));
+    }
+
+    #[test]
+    fn slow_clone_out_of_the_lock() {
+        use std::sync::{Arc, Barrier};
+
+        // blocks in the clone of the reader until the iterating thread moved on
+        struct Slow(u32, Arc<Barrier>);
+
+        impl Clone for Slow {
+            fn clone(&self) -> Self {
+                if thread::current().name() == Some("reader") {
+                    self.1.wait();
+                    self.1.wait();
+                }
+                Slow(self.0, self.1.clone())
+            }
+        }
+
+        let gate = Arc::new(Barrier::new(2));
+        let (mut it, progress) = {
+            let gate = gate.clone();
+            GenIter(#[coroutine] move || {
+                for i in 0..3 {
+                    yield Slow(i, gate.clone());
+                }
+            }).with_watch()
+        };
+        assert_eq!(it.next().map(|s| s.0), Some(0));
+
+        let reader = thread::Builder::new()
+            .name("reader".into())
+            .spawn(move || progress.latest().map(|s| s.0))
+            .unwrap();
+        // the reader is cloning the first yield
+        gate.wait();
+        assert_eq!(it.next().map(|s| s.0), Some(1));
+        gate.wait();
+        assert_eq!(reader.join().unwrap(), Some(0));
+        assert_eq!(it.watch().latest().map(|s| s.0), Some(1));
+    }
+
+    #[test]
+    fn dropped_early() {
+        let (mut it, progress) = GenIter(#[coroutine] || {
+            loop {
+                yield 1;
+            }
+        }).with_watch();
+        it.next();
+        drop(it);
+
+        assert_eq!(progress.latest(), Some(1));
+        assert!(!progress.is_finished());
+    }
+}
@@ -0,0 +1,205 @@
+use core::ops::Coroutine;
+use core::iter::Iterator;
+use core::marker::Unpin;
+use core::time::Duration;
+use core::fmt;
+
+use std::time::Instant;
+use std::vec::Vec;
+
+use crate::GenIter;
+
+/// the resume that stopped a strict [`Watchdog`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WatchdogTripped {
+    /// the index of the resume, like in [`Watchdog::violations`]
+    pub index: usize,
+    /// how long it took
+    pub duration: Duration,
+}
+
+/// `Watchdog<G>` iterates over a generator like [`GenIter`], and flags the resumes
+/// taking longer than a limit, like a blocking call in a generator meant to be quick.
+///
+/// created by [`GenIter::watchdog`].
+///
+/// The resumes are numbered from 0: resume `i` gives the item `i`, and the last one
+/// completes the generator. [`violations`](Watchdog::violations) lists the resumes over
+/// the limit. After [`strict`](Watchdog::strict), the first one ends the iteration instead:
+/// its item is still returned, the generator is not resumed anymore, and
+/// [`tripped`](Watchdog::tripped) tells which resume it was.
+///
+/// A resume is measured once it returned, the watchdog cannot interrupt a resume
+/// that hangs.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter;
+/// use std::time::Duration;
+///
+/// let mut it = gen_iter!({
+///     yield 'a';
+///     std::thread::sleep(Duration::from_millis(200));
+///     yield 'b';
+/// }).watchdog(Duration::from_millis(100));
+///
+/// assert_eq!(it.by_ref().collect::<String>(), "ab");
+/// assert_eq!(it.violations(), [1]);
+/// ```
+pub struct Watchdog<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    gen: GenIter<G>,
+    limit: Duration,
+    resumes: usize,
+    violations: Vec<usize>,
+    strict: bool,
+    tripped: Option<WatchdogTripped>,
+}
+
+impl<G> Watchdog<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// end the iteration at the first resume over the limit
+    #[inline]
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// the limit of every resume
+    #[inline]
+    pub fn limit(&self) -> Duration {
+        self.limit
+    }
+
+    /// the indices of the resumes over the limit
+    #[inline]
+    pub fn violations(&self) -> &[usize] {
+        &self.violations
+    }
+
+    /// the resume which ended the iteration, if [`strict`](Watchdog::strict)
+    #[inline]
+    pub fn tripped(&self) -> Option<WatchdogTripped> {
+        self.tripped
+    }
+
+    /// get back the generator
+    #[inline]
+    pub fn into_inner(self) -> GenIter<G> {
+        self.gen
+    }
+}
+
+impl<G> Iterator for Watchdog<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        if self.tripped.is_some() {
+            return None;
+        }
+        let start = Instant::now();
+        let next = self.gen.next();
+        let duration = start.elapsed();
+
+        let index = self.resumes;
+        self.resumes += 1;
+        if duration > self.limit {
+            self.violations.push(index);
+            if self.strict {
+                self.tripped = Some(WatchdogTripped { index, duration });
+            }
+        }
+        next
+    }
+}
+
+impl<G> fmt::Debug for Watchdog<G>
+where
+    G: Coroutine<Return = ()> + Unpin + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Watchdog")
+            .field("gen", &self.gen)
+            .field("limit", &self.limit)
+            .field("violations", &self.violations)
+            .field("strict", &self.strict)
+            .field("tripped", &self.tripped)
+            .finish()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+{
+    /// flag the resumes of this generator taking longer than `per_resume_limit`,
+    /// see [`Watchdog`]
+    #[inline]
+    pub fn watchdog(self, per_resume_limit: Duration) -> Watchdog<G> {
+        Watchdog {
+            gen: self,
+            limit: per_resume_limit,
+            resumes: 0,
+            violations: Vec::new(),
+            strict: false,
+            tripped: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GenIter;
+    use std::thread;
+    use std::time::Duration;
+    use std::vec::Vec;
+
+    // much longer than a fast resume can take, even on a loaded machine
+    const LIMIT: Duration = Duration::from_millis(200);
+
+    #[test]
+    fn violations() {
+        let mut it = GenIter(#[coroutine] || {
+            for i in 0..5 {
+                if i == 2 {
+                    thread::sleep(2 * LIMIT);
+                }
+                yield i;
+            }
+        }).watchdog(LIMIT);
+        assert_eq!(it.by_ref().take(2).count(), 2);
+
+        assert_eq!(it.by_ref().collect::<Vec<_>>(), [2, 3, 4]);
+        assert!(it.violations().contains(&2));
+        assert_eq!(it.tripped(), None);
+    }
+
+    #[test]
+    fn strict() {
+        let mut it = GenIter(#[coroutine] || {
+            for i in 0..5 {
+                if i == 3 {
+                    thread::sleep(2 * LIMIT);
+                }
+                yield i;
+            }
+        }).watchdog(LIMIT).strict();
+        assert_eq!(it.by_ref().collect::<Vec<_>>(), [0, 1, 2, 3]);
+        let tripped = it.tripped().unwrap();
+        assert_eq!(tripped.index, 3);
+        assert!(tripped.duration >= 2 * LIMIT);
+        assert_eq!(it.violations(), [3]);
+
+        // not resumed anymore
+        assert_eq!(it.next(), None);
+        assert_eq!(it.into_inner().next(), Some(4));
+    }
+}
@@ -0,0 +1,191 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use crate::{GenIter, GenIterReturn};
+
+/// `WithLast<I>` iterates like `I`, and keeps a clone of the latest item,
+/// like a UI showing the last value while waiting for the next one.
+///
+/// created by [`GenIter::with_last`], or [`GenIterReturn::with_last`] which
+/// borrows the `GenIterReturn`, so its return value can be taken as usual after.
+///
+/// [`latest`](WithLast::latest) is `None` before the first item, and keeps
+/// the final one once the iterator is exhausted. It is not named `last`,
+/// which would be `Iterator::last` consuming the iterator.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::gen_iter_return;
+///
+/// let mut g = gen_iter_return!({
+///     yield String::from("loading");
+///     yield String::from("ready");
+///     200
+/// });
+///
+/// let mut status = g.with_last();
+/// assert_eq!(status.latest(), None);
+/// assert_eq!(status.next().as_deref(), Some("loading"));
+/// assert_eq!(status.by_ref().count(), 1);
+/// assert_eq!(status.latest().map(String::as_str), Some("ready"));
+///
+/// assert_eq!(g.return_or_self().ok(), Some(200));
+/// ```
+pub struct WithLast<I: Iterator> {
+    iter: I,
+    last: Option<I::Item>,
+}
+
+impl<I: Iterator> WithLast<I> {
+    #[inline]
+    pub fn new(iter: I) -> Self {
+        WithLast { iter, last: None }
+    }
+
+    /// the latest item, `None` before the first one
+    #[inline]
+    pub fn latest(&self) -> Option<&I::Item> {
+        self.last.as_ref()
+    }
+
+    /// take the latest item, dropping the iterator
+    #[inline]
+    pub fn into_latest(self) -> Option<I::Item> {
+        self.last
+    }
+
+    /// get back the iterator
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.iter
+    }
+}
+
+impl<I> Iterator for WithLast<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let y = self.iter.next()?;
+        match self.last {
+            Some(ref mut last) => last.clone_from(&y),
+            None => self.last = Some(y.clone()),
+        }
+        Some(y)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> FusedIterator for WithLast<I>
+where
+    I: FusedIterator,
+    I::Item: Clone,
+{}
+
+impl<I> fmt::Debug for WithLast<I>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WithLast")
+            .field("iter", &self.iter)
+            .field("last", &self.last)
+            .finish()
+    }
+}
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: Clone,
+{
+    /// keep a clone of the latest yield, see [`WithLast`]
+    #[inline]
+    pub fn with_last(self) -> WithLast<Self> {
+        WithLast::new(self)
+    }
+}
+
+impl<G> GenIterReturn<G>
+where
+    G: Coroutine + Unpin,
+    G::Yield: Clone,
+{
+    /// keep a clone of the latest yield while iterating over `&mut self`, see [`WithLast`]
+    #[inline]
+    pub fn with_last(&mut self) -> WithLast<&mut Self> {
+        WithLast::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, GenIterReturn};
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+
+    #[test]
+    fn gen_iter() {
+        let mut it = GenIter(#[coroutine] || {
+            for i in 1..=3 {
+                yield i.to_string();
+            }
+        }).with_last();
+        assert_eq!(it.latest(), None);
+
+        assert_eq!(it.next().as_deref(), Some("1"));
+        assert_eq!(it.latest().map(String::as_str), Some("1"));
+        assert_eq!(it.next().as_deref(), Some("2"));
+        assert_eq!(it.latest().map(String::as_str), Some("2"));
+
+        assert_eq!(it.by_ref().count(), 1);
+        assert_eq!(it.latest().map(String::as_str), Some("3"));
+        assert_eq!(it.into_latest().as_deref(), Some("3"));
+    }
+
+    #[test]
+    fn gen_iter_return() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield 'a';
+            yield 'b';
+            "done"
+        });
+
+        let mut it = g.with_last();
+        assert_eq!(it.latest(), None);
+        assert_eq!(it.next(), Some('a'));
+        assert_eq!(it.latest(), Some(&'a'));
+        assert_eq!(it.by_ref().collect::<Vec<_>>(), ['b']);
+        // `&mut GenIterReturn` is fused
+        assert_eq!(it.next(), None);
+        assert_eq!(it.latest(), Some(&'b'));
+
+        assert!(g.is_done());
+        assert_eq!(g.return_or_self().ok(), Some("done"));
+    }
+
+    #[test]
+    fn empty() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            if false {
+                yield 0;
+            }
+            1
+        });
+        let mut it = g.with_last();
+        assert_eq!(it.next(), None);
+        assert_eq!(it.latest(), None);
+        assert_eq!(g.return_or_self().ok(), Some(1));
+    }
+}
@@ -0,0 +1,149 @@
+use core::ops::Coroutine;
+use core::marker::Unpin;
+
+use std::io::{self, Write};
+
+use crate::{GenIter, GenIterReturn};
+
+impl<G> GenIter<G>
+where
+    G: Coroutine<Return = ()> + Unpin,
+    G::Yield: AsRef<[u8]>,
+{
+    /// write every yielded chunk of bytes into `w`,
+    /// returns the number of bytes written.
+    ///
+    /// on the first write error the generator is dropped, and the error returned.
+    /// a generator yielding single `u8`s can be copied with
+    /// [`into_read`](GenIter::into_read) and `io::copy`.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_iter;
+    ///
+    /// let mut out = Vec::new();
+    /// let n = gen_iter!({
+    ///     yield "hello";
+    ///     yield " world";
+    /// }).write_all_to(&mut out).unwrap();
+    ///
+    /// assert_eq!(n, 11);
+    /// assert_eq!(out, b"hello world");
+    /// ```
+    pub fn write_all_to<W: Write + ?Sized>(self, w: &mut W) -> io::Result<u64> {
+        let mut n = 0;
+        for chunk in self {
+            let chunk = chunk.as_ref();
+            w.write_all(chunk)?;
+            n += chunk.len() as u64;
+        }
+        Ok(n)
+    }
+}
+
+impl<G> GenIterReturn<G>
+where
+    G: Coroutine + Unpin,
+    G::Yield: AsRef<[u8]>,
+{
+    /// like [`GenIter::write_all_to`], also returns the return value of the generator.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::gen_iter_return;
+    ///
+    /// let mut out = Vec::new();
+    /// let (n, lines) = gen_iter_return!({
+    ///     yield "a\n";
+    ///     yield "b\n";
+    ///     return 2;
+    /// }).write_all_to(&mut out).unwrap();
+    ///
+    /// assert_eq!((n, lines), (4, 2));
+    /// assert_eq!(out, b"a\nb\n");
+    /// ```
+    pub fn write_all_to<W: Write + ?Sized>(mut self, w: &mut W) -> io::Result<(u64, G::Return)> {
+        let mut n = 0;
+        for chunk in &mut self {
+            let chunk = chunk.as_ref();
+            w.write_all(chunk)?;
+            n += chunk.len() as u64;
+        }
+        match self.return_or_self() {
+            Ok(r) => Ok((n, r)),
+            Err(_) => unreachable!("generator is done"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, GenIterReturn};
+    use std::io::{self, Write};
+    use std::sync::mpsc;
+    use std::vec::Vec;
+
+    /// accepts `left` bytes, then fails
+    struct Failing {
+        left: usize,
+        written: Vec<u8>,
+    }
+
+    impl Write for Failing {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.left == 0 {
+                return Err(io::Error::other("disk full"));
+            }
+            let n = buf.len().min(self.left);
+            self.written.extend_from_slice(&buf[..n]);
+            self.left -= n;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn into_vec() {
+        let mut out = Vec::new();
+        let (n, chunks) = GenIterReturn::new(#[coroutine] || {
+            let mut chunks = 0;
+            for len in [3, 0, 1000, 7] {
+                yield std::vec![len as u8; len];
+                chunks += 1;
+            }
+            chunks
+        }).write_all_to(&mut out).unwrap();
+
+        assert_eq!((n, chunks), (1010, 4));
+        let expected = [3, 0, 1000, 7].iter().flat_map(|&len| std::vec![len as u8; len]).collect::<Vec<_>>();
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn failing_writer() {
+        // the sender is dropped with the generator
+        let (alive_tx, alive_rx) = mpsc::channel::<()>();
+        let mut w = Failing { left: 5, written: Vec::new() };
+        let err = GenIterReturn::new(#[coroutine] move || {
+            let _alive = alive_tx;
+            loop {
+                yield b"abc";
+            }
+        }).write_all_to(&mut w).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        assert_eq!(w.written, b"abcab");
+        assert_eq!(alive_rx.recv(), Err(mpsc::RecvError));
+
+        let mut w = Failing { left: 0, written: Vec::new() };
+        let g = GenIter(#[coroutine] || {
+            yield "x";
+        });
+        assert!(g.write_all_to(&mut w as &mut dyn Write).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+use core::ops::{ControlFlow, Coroutine, CoroutineState};
+use core::iter::IntoIterator;
+use core::marker::Unpin;
+use core::pin::Pin;
+
+use crate::GenIter;
+
+// `yield_from!` tells a generator from an iterator by autoref: called on `&source`,
+// `__YieldFromCoroutineKind` is implemented for the source and found first,
+// `__YieldFromIterKind` is implemented for `&source` and needs one more autoref.
+
+#[doc(hidden)]
+pub struct __YieldFromCoroutine;
+
+impl __YieldFromCoroutine {
+    #[inline]
+    pub fn into_source<G>(self, g: G) -> GenIter<G>
+    where
+        G: Coroutine<Return = ()> + Unpin,
+    {
+        GenIter(g)
+    }
+}
+
+#[doc(hidden)]
+pub struct __YieldFromIter;
+
+impl __YieldFromIter {
+    #[inline]
+    pub fn into_source<I: IntoIterator>(self, i: I) -> I::IntoIter {
+        i.into_iter()
+    }
+}
+
+#[doc(hidden)]
+pub trait __YieldFromCoroutineKind {
+    #[inline]
+    fn yield_from_kind(&self) -> __YieldFromCoroutine {
+        __YieldFromCoroutine
+    }
+}
+
+impl<G: Coroutine<Return = ()>> __YieldFromCoroutineKind for G {}
+
+#[doc(hidden)]
+pub trait __YieldFromIterKind {
+    #[inline]
+    fn yield_from_kind(&self) -> __YieldFromIter {
+        __YieldFromIter
+    }
+}
+
+impl<I: IntoIterator> __YieldFromIterKind for &I {}
+
+/// macro to yield every item of a source from a generator body, like `yield from`
+/// in Python.
+///
+/// the source is an `IntoIterator`, or a generator with the return type `()`,
+/// which has to be `Unpin`: a `static` one can be given pinned, like
+/// `Box::pin(gen)`. It expands to a plain loop in the generator using it, and
+/// evaluates to `()`.
+///
+/// the source can borrow the variables captured by the generator. Like any
+/// borrow held across a yield, borrowing the locals of the generator needs it
+/// to be `static`, pinned like `GenIter(Box::pin(gen))`.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter, yield_from};
+///
+/// let words = ["a", "bc"];
+/// let g = gen_iter!({
+///     yield_from!(words.iter().map(|w| w.len()));
+///     yield_from!(#[coroutine] || {
+///         yield 10;
+///         yield 20;
+///     });
+/// });
+///
+/// assert_eq!(g.collect::<Vec<_>>(), [1, 2, 10, 20]);
+/// ```
+#[macro_export]
+macro_rules! yield_from {
+    ($source: expr) => {{
+        #[allow(unused_imports)]
+        use $crate::{__YieldFromCoroutineKind as _, __YieldFromIterKind as _};
+        let source = $source;
+        for item in (&source).yield_from_kind().into_source(source) {
+            yield item;
+        }
+    }};
+}
+
+#[doc(hidden)]
+#[inline]
+pub fn __resume_sub<G: Coroutine>(g: Pin<&mut G>) -> ControlFlow<G::Return, G::Yield> {
+    match g.resume(()) {
+        CoroutineState::Yielded(y) => ControlFlow::Continue(y),
+        CoroutineState::Complete(r) => ControlFlow::Break(r),
+    }
+}
+
+/// macro to yield every item of a generator from a generator body, and evaluate
+/// to its return value, like `yield from` in Python.
+///
+/// `yield_all_return!(gen)` resumes an `Unpin` generator in a plain loop.
+/// `yield_all_return!(pin gen)` pins it locally with [`pin!`](core::pin::pin)
+/// first, for a `static` generator which is not `Unpin`.
+///
+/// the sub-generator is moved into the state of the generator using the macro.
+/// It can borrow the variables this one captured, but a borrow of one of its
+/// locals is held across the yields of the delegation, which needs a `static`
+/// generator: make the sub-generator `move` to give it its own values instead.
+/// The `pin` form borrows the pinned sub-generator across the yields too, so it
+/// can only be used in a `static` generator.
+///
+/// ```
+/// #![feature(coroutines, stmt_expr_attributes)]
+///
+/// use gen_iter::{gen_iter_return, yield_all_return};
+///
+/// let mut g = gen_iter_return!({
+///     let n = yield_all_return!(#[coroutine] || {
+///         yield "a";
+///         yield "b";
+///         2
+///     });
+///     yield "c";
+///     n + 1
+/// });
+///
+/// assert_eq!((&mut g).collect::<Vec<_>>(), ["a", "b", "c"]);
+/// assert_eq!(g.return_or_self().ok(), Some(3));
+/// ```
+#[macro_export]
+macro_rules! yield_all_return {
+    ($gen: expr) => {{
+        let mut gen = $gen;
+        loop {
+            match $crate::__resume_sub(::core::pin::Pin::new(&mut gen)) {
+                ::core::ops::ControlFlow::Continue(y) => yield y,
+                ::core::ops::ControlFlow::Break(r) => break r,
+            }
+        }
+    }};
+    (pin $gen: expr) => {{
+        let mut gen = ::core::pin::pin!($gen);
+        loop {
+            match $crate::__resume_sub(gen.as_mut()) {
+                ::core::ops::ControlFlow::Continue(y) => yield y,
+                ::core::ops::ControlFlow::Break(r) => break r,
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIter, GenIterReturn};
+    use core::ops::Coroutine;
+    use std::boxed::Box;
+    use std::vec::Vec;
+
+    fn inner(n: u32) -> GenIter<impl Coroutine<Yield = u32, Return = ()> + Unpin> {
+        GenIter(#[coroutine] move || {
+            yield_from!(0..n);
+            yield_from!(#[coroutine] || {
+                yield 100;
+            });
+        })
+    }
+
+    #[test]
+    fn nested() {
+        let g = GenIter(#[coroutine] || {
+            yield 1000;
+            yield_from!(GenIter(#[coroutine] || {
+                yield_from!(inner(2));
+                yield_from!(inner(1));
+            }));
+            yield 1000;
+        });
+        assert_eq!(g.collect::<Vec<_>>(), [1000, 0, 1, 100, 0, 100, 1000]);
+    }
+
+    #[test]
+    fn mixed_sources() {
+        let data = Vec::from([1, 2, 3]);
+        let g = GenIter(#[coroutine] || {
+            // an iterator and a generator borrowing `data`
+            yield_from!(&data);
+            yield_from!(#[coroutine] || {
+                for x in data.iter().rev() {
+                    yield x;
+                }
+            });
+            // a `static` one, given pinned
+            yield_from!(Box::pin(#[coroutine] static || {
+                let local = 7;
+                let r = &local;
+                yield &data[*r - 7];
+            }));
+        });
+        assert_eq!(g.copied().collect::<Vec<_>>(), [1, 2, 3, 3, 2, 1, 1]);
+    }
+
+    #[test]
+    fn borrows_locals() {
+        // borrowing the locals of the outer generator across its yields
+        // needs it to be `static`
+        let g = GenIter(Box::pin(#[coroutine] static || {
+            let words = Vec::from(["ab", "c"]);
+            yield_from!(words.iter().copied());
+            let mut sub = GenIter(#[coroutine] || {
+                yield "d";
+                yield "e";
+            });
+            yield_from!(sub.by_ref().take(1));
+            yield_from!(&mut sub);
+        }));
+        assert_eq!(g.collect::<Vec<_>>(), ["ab", "c", "d", "e"]);
+    }
+
+    fn child(name: &'static str, n: u32) -> impl Coroutine<Yield = &'static str, Return = u32> + Unpin {
+        #[coroutine] move || {
+            for _ in 0..n {
+                yield name;
+            }
+            n * 10
+        }
+    }
+
+    #[test]
+    fn sum_of_returns() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            yield "start";
+            let a = yield_all_return!(child("a", 2));
+            let b = yield_all_return!(child("b", 1));
+            yield "end";
+            a + b
+        });
+        assert_eq!((&mut g).collect::<Vec<_>>(), ["start", "a", "a", "b", "end"]);
+        assert_eq!(g.return_or_self().ok(), Some(30));
+    }
+
+    #[test]
+    fn nested_returns() {
+        let mut g = GenIterReturn::new(#[coroutine] || {
+            let inner = yield_all_return!(#[coroutine] || {
+                let a = yield_all_return!(child("a", 1));
+                yield "between";
+                a + 1
+            });
+            // a child with nothing to yield
+            inner + yield_all_return!(child("none", 0))
+        });
+        assert_eq!((&mut g).collect::<Vec<_>>(), ["a", "between"]);
+        assert_eq!(g.return_or_self().ok(), Some(11));
+    }
+
+    #[test]
+    fn pinned() {
+        let mut g = GenIterReturn::new(Box::pin(#[coroutine] static || {
+            let local = Vec::from([1, 2, 3]);
+            // not `Unpin`, and borrowing a local of the outer generator
+            let sum = yield_all_return!(pin #[coroutine] static || {
+                let mut sum = 0;
+                for x in &local {
+                    sum += x;
+                    yield *x;
+                }
+                sum
+            });
+            sum * 2
+        }));
+        assert_eq!((&mut g).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(g.return_or_self().ok(), Some(12));
+    }
+}
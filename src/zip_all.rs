@@ -0,0 +1,281 @@
+use core::ops::Coroutine;
+use core::iter::{FusedIterator, Iterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use alloc::vec::Vec;
+
+use crate::GenIterReturn;
+
+/// how a [`ZipAll`] ended, from [`ZipAll::return_or_self`]
+#[derive(Debug)]
+pub struct ZipAllOutcome<G: Coroutine + Unpin> {
+    /// the index of the generator which completed
+    pub index: usize,
+    /// its return value
+    pub ret: G::Return,
+    /// the items of the generators before it in the last step
+    pub partial: Vec<G::Yield>,
+    /// the other generators, in order
+    pub rest: Vec<GenIterReturn<G>>,
+}
+
+/// `ZipAll<G>` resumes a `Vec` of generators in lock-step, until one of them completes,
+/// like entities of a simulation moving at every tick.
+///
+/// created by [`zip_all`], with the `alloc` feature. Like [`GenIterReturn`],
+/// `&mut ZipAll<G>` is the iterator, over the items of every generator at every step.
+///
+/// The generators are resumed in order. When one of them completes, the iteration ends:
+/// [`return_or_self`](ZipAll::return_or_self) gives its index and return value, the items
+/// the generators before it yielded in this step, and the other generators.
+/// [`zip_all_longest`] goes on with the generators still running instead.
+///
+/// Without any generator, there is no item.
+///
+/// ```
+/// #![feature(coroutines, coroutine_trait, stmt_expr_attributes)]
+///
+/// use gen_iter::{zip_all, GenIterReturn};
+///
+/// let walker = |speed: u32, steps: u32| GenIterReturn::new(#[coroutine] move || {
+///     for i in 1..=steps {
+///         yield i * speed;
+///     }
+///     speed
+/// });
+///
+/// let mut world = zip_all(vec![walker(1, 3), walker(2, 2)]);
+/// assert_eq!((&mut world).collect::<Vec<_>>(), [vec![1, 2], vec![2, 4]]);
+///
+/// let outcome = world.return_or_self().ok().unwrap();
+/// assert_eq!((outcome.index, outcome.ret), (1, 2));
+/// assert_eq!(outcome.partial, [3]);
+/// ```
+pub struct ZipAll<G: Coroutine + Unpin> {
+    gens: Vec<GenIterReturn<G>>,
+    // the generator which completed and the items before it
+    ended: Option<(usize, Vec<G::Yield>)>,
+}
+
+impl<G: Coroutine + Unpin> ZipAll<G> {
+    /// whether one of the generators completed
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.ended.is_some()
+    }
+
+    /// the generator which completed and the others, else `self`
+    pub fn return_or_self(self) -> Result<ZipAllOutcome<G>, Self> {
+        let (index, partial) = match self.ended {
+            Some(ended) => ended,
+            None => return Err(self),
+        };
+        let mut rest = self.gens;
+        match rest.remove(index).return_or_self() {
+            Ok(ret) => Ok(ZipAllOutcome { index, ret, partial, rest }),
+            Err(_) => unreachable!(),
+        }
+    }
+}
+
+impl<G: Coroutine + Unpin> Iterator for &mut ZipAll<G> {
+    type Item = Vec<G::Yield>;
+
+    fn next(&mut self) -> Option<Vec<G::Yield>> {
+        if self.ended.is_some() || self.gens.is_empty() {
+            return None;
+        }
+        let mut step = Vec::with_capacity(self.gens.len());
+        for (i, mut g) in self.gens.iter_mut().enumerate() {
+            match g.next() {
+                Some(y) => step.push(y),
+                None => {
+                    self.ended = Some((i, step));
+                    return None;
+                },
+            }
+        }
+        Some(step)
+    }
+}
+
+impl<G: Coroutine + Unpin> FusedIterator for &mut ZipAll<G> {}
+
+impl<G> fmt::Debug for ZipAll<G>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Yield: fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ZipAll")
+            .field("gens", &self.gens)
+            .field("ended", &self.ended)
+            .finish()
+    }
+}
+
+/// resume `gens` in lock-step until one of them completes, see [`ZipAll`]
+#[inline]
+pub fn zip_all<G: Coroutine + Unpin>(gens: Vec<GenIterReturn<G>>) -> ZipAll<G> {
+    ZipAll { gens, ended: None }
+}
+
+/// `ZipAllLongest<G>` resumes a `Vec` of generators in lock-step, until all of them
+/// complete.
+///
+/// created by [`zip_all_longest`], with the `alloc` feature. Like [`GenIterReturn`],
+/// `&mut ZipAllLongest<G>` is the iterator, over the items of every generator
+/// at every step, `None` for the generators which completed. They are not resumed
+/// anymore, unlike [`ZipAll`] the iteration goes on with the others.
+///
+/// Once they all completed, [`return_or_self`](ZipAllLongest::return_or_self) gives
+/// their return values, in order.
+///
+/// ```
+/// #![feature(coroutines, coroutine_trait, stmt_expr_attributes)]
+///
+/// use gen_iter::{zip_all_longest, GenIterReturn};
+///
+/// let countdown = |from: u32| GenIterReturn::new(#[coroutine] move || {
+///     for i in (1..=from).rev() {
+///         yield i;
+///     }
+///     from
+/// });
+///
+/// let mut g = zip_all_longest(vec![countdown(1), countdown(2)]);
+/// assert_eq!((&mut g).collect::<Vec<_>>(), [vec![Some(1), Some(2)], vec![None, Some(1)]]);
+/// assert_eq!(g.return_or_self().ok(), Some(vec![1, 2]));
+/// ```
+pub struct ZipAllLongest<G: Coroutine + Unpin> {
+    gens: Vec<GenIterReturn<G>>,
+}
+
+impl<G: Coroutine + Unpin> ZipAllLongest<G> {
+    /// whether all of the generators completed
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.gens.iter().all(GenIterReturn::is_done)
+    }
+
+    /// the return values of the generators once they all completed, else `self`
+    pub fn return_or_self(self) -> Result<Vec<G::Return>, Self> {
+        if !self.is_done() {
+            return Err(self);
+        }
+        Ok(self.gens.into_iter().map(|g| match g.into_result() {
+            Ok(r) => r,
+            Err(_) => unreachable!(),
+        }).collect())
+    }
+}
+
+impl<G: Coroutine + Unpin> Iterator for &mut ZipAllLongest<G> {
+    type Item = Vec<Option<G::Yield>>;
+
+    fn next(&mut self) -> Option<Vec<Option<G::Yield>>> {
+        let step = self.gens.iter_mut().map(|mut g| g.next()).collect::<Vec<_>>();
+        if step.iter().all(Option::is_none) {
+            None
+        } else {
+            Some(step)
+        }
+    }
+}
+
+impl<G: Coroutine + Unpin> FusedIterator for &mut ZipAllLongest<G> {}
+
+impl<G> fmt::Debug for ZipAllLongest<G>
+where
+    G: Coroutine + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ZipAllLongest")
+            .field("gens", &self.gens)
+            .finish()
+    }
+}
+
+/// resume `gens` in lock-step until all of them complete, see [`ZipAllLongest`]
+#[inline]
+pub fn zip_all_longest<G: Coroutine + Unpin>(gens: Vec<GenIterReturn<G>>) -> ZipAllLongest<G> {
+    ZipAllLongest { gens }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{zip_all, zip_all_longest};
+    use crate::GenIterReturn;
+    use std::vec;
+    use std::vec::Vec;
+
+    /// yields `id * 10 + i` for `i` in `0..len`, returns `id`
+    fn entity(id: u32, len: u32) -> GenIterReturn<impl core::ops::Coroutine<Yield = u32, Return = u32> + Unpin> {
+        GenIterReturn::new(#[coroutine] move || {
+            for i in 0..len {
+                yield id * 10 + i;
+            }
+            id
+        })
+    }
+
+    fn entities(lens: &[u32]) -> Vec<GenIterReturn<impl core::ops::Coroutine<Yield = u32, Return = u32> + Unpin>> {
+        lens.iter().enumerate().map(|(id, len)| entity(id as u32, *len)).collect()
+    }
+
+    #[test]
+    fn shortest() {
+        let mut g = zip_all(entities(&[3, 4, 2, 5]));
+        assert_eq!((&mut g).collect::<Vec<_>>(), [vec![0, 10, 20, 30], vec![1, 11, 21, 31]]);
+        assert!(g.is_done());
+
+        let outcome = g.return_or_self().ok().unwrap();
+        assert_eq!((outcome.index, outcome.ret), (2, 2));
+        assert_eq!(outcome.partial, [2, 12]);
+        // the others go on from the last step
+        let rest = outcome.rest.into_iter().map(|mut g| (&mut g).collect::<Vec<_>>()).collect::<Vec<_>>();
+        assert_eq!(rest, [vec![], vec![13], vec![32, 33, 34]]);
+    }
+
+    #[test]
+    fn first_completes() {
+        let mut g = zip_all(entities(&[0, 2]));
+        assert_eq!((&mut g).count(), 0);
+        let outcome = g.return_or_self().ok().unwrap();
+        assert_eq!(outcome.index, 0);
+        assert!(outcome.partial.is_empty());
+        assert_eq!(outcome.rest.len(), 1);
+    }
+
+    #[test]
+    fn no_generator() {
+        let mut g = zip_all(entities(&[]));
+        assert_eq!((&mut g).next(), None);
+        assert!(g.return_or_self().is_err());
+    }
+
+    #[test]
+    fn longest() {
+        let mut g = zip_all_longest(entities(&[1, 3, 2]));
+        assert_eq!((&mut g).collect::<Vec<_>>(), [
+            vec![Some(0), Some(10), Some(20)],
+            vec![None, Some(11), Some(21)],
+            vec![None, Some(12), None],
+        ]);
+        assert!(g.is_done());
+        assert_eq!(g.return_or_self().ok(), Some(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn longest_not_done() {
+        let mut g = zip_all_longest(entities(&[1, 2]));
+        assert_eq!((&mut g).take(2).count(), 2);
+        // the second one did not complete yet
+        let mut g = g.return_or_self().err().unwrap();
+        assert_eq!((&mut g).next(), None);
+        assert_eq!(g.return_or_self().ok(), Some(vec![0, 1]));
+    }
+}
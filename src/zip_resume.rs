@@ -0,0 +1,223 @@
+use core::ops::Coroutine;
+use core::iter::{Iterator, FusedIterator};
+use core::marker::Unpin;
+use core::fmt;
+
+use crate::GenIterExchange;
+
+/// which side of a [`ZipResume`] ended the iteration
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ZipEnded {
+    /// the input iterator ran out, the generator is still running
+    Inputs,
+    /// the generator completed
+    Coroutine,
+}
+
+/// an iterator that sends every item of `I` into a generator as resume argument,
+/// and pairs it with what the generator yields in response.
+///
+/// created by [`GenIterExchange::zip_resume`].
+pub struct ZipResume<G, I>
+where
+    I: Iterator,
+    G: Coroutine<I::Item> + Unpin,
+{
+    exchange: GenIterExchange<G, I::Item>,
+    inputs: I,
+    ended: Option<ZipEnded>,
+}
+
+impl<G, I> ZipResume<G, I>
+where
+    I: Iterator,
+    G: Coroutine<I::Item> + Unpin,
+{
+    /// which side ended the iteration, `None` while it is still going
+    #[inline]
+    pub fn ended(&self) -> Option<ZipEnded> {
+        self.ended
+    }
+
+    /// split into the generator and the remaining inputs
+    #[inline]
+    pub fn into_parts(self) -> (GenIterExchange<G, I::Item>, I) {
+        (self.exchange, self.inputs)
+    }
+}
+
+impl<G, I> Clone for ZipResume<G, I>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+    G: Coroutine<I::Item> + Unpin + Clone,
+    G::Return: Clone,
+{
+    fn clone(&self) -> Self {
+        ZipResume {
+            exchange: self.exchange.clone(),
+            inputs: self.inputs.clone(),
+            ended: self.ended,
+        }
+    }
+}
+
+impl<G, I> fmt::Debug for ZipResume<G, I>
+where
+    I: Iterator + fmt::Debug,
+    G: Coroutine<I::Item> + Unpin + fmt::Debug,
+    G::Return: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ZipResume")
+            .field("exchange", &self.exchange)
+            .field("inputs", &self.inputs)
+            .field("ended", &self.ended)
+            .finish()
+    }
+}
+
+impl<G, I> Iterator for ZipResume<G, I>
+where
+    I: Iterator,
+    I::Item: Clone,
+    G: Coroutine<I::Item> + Unpin,
+{
+    type Item = (I::Item, G::Yield);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ended.is_some() {
+            return None;
+        }
+        if self.exchange.is_complete() {
+            // no input is taken for a generator that can't be resumed
+            self.ended = Some(ZipEnded::Coroutine);
+            return None;
+        }
+
+        let input = match self.inputs.next() {
+            Some(input) => input,
+            None => {
+                self.ended = Some(ZipEnded::Inputs);
+                return None;
+            }
+        };
+
+        match self.exchange.next_with(input.clone()) {
+            Some(y) => Some((input, y)),
+            None => {
+                self.ended = Some(ZipEnded::Coroutine);
+                None
+            }
+        }
+    }
+}
+
+impl<G, I> FusedIterator for ZipResume<G, I>
+where
+    I: Iterator,
+    I::Item: Clone,
+    G: Coroutine<I::Item> + Unpin,
+{}
+
+impl<G: Coroutine<R> + Unpin, R> GenIterExchange<G, R> {
+    /// send every item of `inputs` into the generator, and iterate over
+    /// `(input, yield)` pairs.
+    ///
+    /// each input is cloned before it is sent, so `R` must be `Clone`.
+    /// the iteration ends as soon as either side is done,
+    /// [`ZipResume::ended`] tells which one. the input sent to the resume
+    /// that completed the generator has no pair and is dropped.
+    ///
+    /// ```
+    /// #![feature(coroutines, stmt_expr_attributes)]
+    ///
+    /// use gen_iter::{GenIterExchange, ZipEnded};
+    ///
+    /// let mut pairs = GenIterExchange::new(#[coroutine] |mut x: u32| {
+    ///     loop {
+    ///         x = yield x * 2;
+    ///     }
+    /// }).zip_resume(1..4);
+    ///
+    /// assert_eq!(pairs.by_ref().collect::<Vec<_>>(), [(1, 2), (2, 4), (3, 6)]);
+    /// assert_eq!(pairs.ended(), Some(ZipEnded::Inputs));
+    /// ```
+    #[inline]
+    pub fn zip_resume<I>(self, inputs: I) -> ZipResume<G, I>
+    where
+        I: Iterator<Item = R>,
+        R: Clone,
+    {
+        ZipResume {
+            exchange: self,
+            inputs,
+            ended: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenIterExchange, ZipEnded};
+    use std::vec::Vec;
+
+    #[test]
+    fn doubling() {
+        let mut pairs = GenIterExchange::new(#[coroutine] |mut x: u32| {
+            loop {
+                x = yield x * 2;
+            }
+        }).zip_resume(0..5);
+
+        assert_eq!(pairs.ended(), None);
+        assert_eq!(
+            pairs.by_ref().collect::<Vec<_>>(),
+            [(0, 0), (1, 2), (2, 4), (3, 6), (4, 8)]
+        );
+        assert_eq!(pairs.ended(), Some(ZipEnded::Inputs));
+        assert_eq!(pairs.next(), None);
+
+        let (exchange, _) = pairs.into_parts();
+        assert!(!exchange.is_complete());
+    }
+
+    #[test]
+    fn early_completion() {
+        let mut pairs = GenIterExchange::new(#[coroutine] |mut x: u32| {
+            let mut sum = 0;
+            for _ in 0..2 {
+                sum += x;
+                x = yield x * 2;
+            }
+            sum
+        }).zip_resume(1..10);
+
+        assert_eq!(pairs.by_ref().collect::<Vec<_>>(), [(1, 2), (2, 4)]);
+        assert_eq!(pairs.ended(), Some(ZipEnded::Coroutine));
+
+        let (exchange, mut inputs) = pairs.into_parts();
+        assert_eq!(exchange.return_or_self().ok(), Some(3));
+        assert_eq!(inputs.next(), Some(4));
+    }
+
+    #[test]
+    fn already_complete() {
+        let mut exchange = GenIterExchange::new(#[coroutine] |x: u32| {
+            yield x;
+            x
+        });
+        assert_eq!(exchange.next_with(7), Some(7));
+        assert_eq!(exchange.next_with(8), None);
+
+        let mut pairs = exchange.zip_resume(1..4);
+        assert_eq!(pairs.next(), None);
+        assert_eq!(pairs.ended(), Some(ZipEnded::Coroutine));
+
+        // the inputs are untouched
+        let (exchange, inputs) = pairs.into_parts();
+        assert_eq!(exchange.return_or_self().ok(), Some(7));
+        assert_eq!(inputs.collect::<Vec<_>>(), [1, 2, 3]);
+    }
+}
@@ -0,0 +1,80 @@
+//! counts the allocations of `GenPool` with a counting global allocator,
+//! in its own test binary so the other tests do not count.
+
+#![feature(coroutines)]
+#![cfg_attr(feature = "alloc", feature(stmt_expr_attributes))]
+#![cfg(feature = "alloc")]
+
+extern crate gen_iter;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use gen_iter::GenPool;
+
+struct Counting;
+
+thread_local! {
+    // the allocations of this thread, the test harness allocates on its own
+    static ALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCS.try_with(|n| n.set(n.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocs() -> usize {
+    ALLOCS.with(Cell::get)
+}
+
+// too slow under miri, which checks the pool with the unit tests instead
+#[test]
+#[cfg_attr(miri, ignore)]
+fn steady_state() {
+    let pool = GenPool::new();
+    // warm up: the blocks of 4 generators alive at once, and the free list
+    let mut total = 0;
+    let round = |total: &mut u64| {
+        let gens = [0u64, 1, 2, 3].map(|n| pool.create(|| #[coroutine] move || {
+            let data = [n; 16];
+            for x in data {
+                yield x;
+            }
+        }));
+        for it in gens {
+            *total += it.sum::<u64>();
+        }
+    };
+    round(&mut total);
+
+    let before = allocs();
+    for _ in 0..10_000 {
+        round(&mut total);
+    }
+    assert_eq!(allocs() - before, 0);
+    assert_eq!(total, 10_001 * 16 * 6);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn boxed_allocates() {
+    // what the pool saves
+    let before = allocs();
+    for n in 0..100u64 {
+        let it = Box::pin(#[coroutine] move || {
+            yield n;
+        });
+        drop(it);
+    }
+    assert_eq!(allocs() - before, 100);
+}
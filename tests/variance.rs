@@ -0,0 +1,211 @@
+//! compile-time checks for the variance and auto traits of the wrappers.
+//!
+//! most of these tests do nothing at runtime: if a refactor changes
+//! the variance or the `Send` / `Sync` / `Unpin` propagation of a wrapper,
+//! this file stops compiling. the negative auto trait cases are checked
+//! with `assert_not_impl!`, the negative variance cases of `GenIterReturn`
+//! and `GenIterExchange` are `compile_fail` doctests on the types.
+//!
+//! audit result:
+//! - `GenIter<T>` is covariant in `T`, and `Send` / `Sync` / `Unpin` exactly
+//!   when `T` is.
+//! - `GenIterReturn<G>` is invariant in `G`, because it stores `G::Return`,
+//!   and gets its auto traits from `G` and `G::Return`.
+//! - `GenIterExchange<G, R>` is like `GenIterReturn<G>` in `G`. It is invariant
+//!   in `R` too, as `G::Return` is `<G as Coroutine<R>>::Return`, see the
+//!   `compile_fail` doctest on the type. It only holds `PhantomData<fn(R)>`,
+//!   so `R` never changes its auto traits.
+//! - the adaptors built on them store their generator and plain fields, with no
+//!   `unsafe impl Send` / `Sync`, so they follow their generator. Their manual
+//!   `Unpin` impls only require the fields that are not pinned to be `Unpin`,
+//!   checked here on `OnIncompleteDrop`.
+//! - `PooledGenIter` holds an `Rc` to its pool and a pointer to its
+//!   generator: it is neither `Send` nor `Sync`, whatever the generator.
+//!   Neither is `FfiGenIter`, a raw pointer to its boxed iterator.
+
+#![feature(coroutine_trait)]
+
+extern crate gen_iter;
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::ops::{Coroutine, CoroutineState};
+use std::pin::Pin;
+use std::rc::Rc;
+
+use gen_iter::{GenIter, GenIterExchange, GenIterReturn};
+
+/// a hand written coroutine that is covariant in `'a`
+struct Once<'a>(Option<&'a str>);
+
+impl<'a> Coroutine for Once<'a> {
+    type Yield = &'a str;
+    type Return = ();
+
+    fn resume(mut self: Pin<&mut Self>, _: ()) -> CoroutineState<&'a str, ()> {
+        match self.0.take() {
+            Some(s) => CoroutineState::Yielded(s),
+            None => CoroutineState::Complete(()),
+        }
+    }
+}
+
+/// a coroutine with a configurable marker, used to
+/// check auto trait propagation
+struct Marked<M>(PhantomData<M>);
+
+impl<M, R> Coroutine<R> for Marked<M> {
+    type Yield = ();
+    type Return = ();
+
+    fn resume(self: Pin<&mut Self>, _: R) -> CoroutineState<(), ()> {
+        CoroutineState::Complete(())
+    }
+}
+
+impl<M> Unpin for Marked<M> {}
+
+fn is_send<T: Send>() {}
+fn is_sync<T: Sync>() {}
+fn is_unpin<T: Unpin>() {}
+
+/// fails to compile if `$t` implements `$trait`: the path
+/// `<$t as AmbiguousIfImpl<_>>::some_item` is then ambiguous between
+/// the two impls, while only the first one applies otherwise.
+macro_rules! assert_not_impl {
+    ($t: ty: $trait: path) => {
+        const _: fn() = || {
+            trait AmbiguousIfImpl<A> {
+                fn some_item() {}
+            }
+
+            impl<T: ?Sized> AmbiguousIfImpl<()> for T {}
+
+            struct Invalid;
+
+            impl<T: ?Sized + $trait> AmbiguousIfImpl<Invalid> for T {}
+
+            let _ = <$t as AmbiguousIfImpl<_>>::some_item;
+        };
+    };
+}
+
+#[test]
+fn gen_iter_is_covariant() {
+    fn shorten<'s, 'l: 's>(g: GenIter<Once<'l>>) -> GenIter<Once<'s>> {
+        g
+    }
+
+    let owned = String::from("hello");
+    let g = GenIter(Once(Some("static")));
+    let mut g = shorten(g);
+
+    assert_eq!(g.next(), Some("static"));
+    assert_eq!(g.next(), None);
+
+    let mut g = shorten(GenIter(Once(Some(&owned))));
+    assert_eq!(g.next(), Some("hello"));
+}
+
+/// `GenIterReturn<G>` names `G::Return` in its field, which makes it
+/// invariant in `G`. only lifetimes that do not appear in `G` can shrink.
+#[test]
+fn gen_iter_return_holds_borrows() {
+    fn first<'a>(mut g: &mut GenIterReturn<Once<'a>>) -> Option<&'a str> {
+        g.next()
+    }
+
+    let owned = String::from("hello");
+    let mut g = GenIterReturn::new(Once(Some(&owned)));
+
+    assert_eq!(first(&mut g), Some("hello"));
+    assert_eq!(first(&mut g), None);
+    assert!(g.is_done());
+}
+
+#[test]
+fn gen_iter_auto_traits() {
+    is_send::<GenIter<Marked<u8>>>();
+    is_sync::<GenIter<Marked<u8>>>();
+    is_unpin::<GenIter<Marked<u8>>>();
+
+    // `Cell` is `Send` but not `Sync`
+    is_send::<GenIter<Marked<Cell<u8>>>>();
+
+    // `Unpin` does not depend on the marker
+    is_unpin::<GenIter<Marked<Rc<u8>>>>();
+
+    assert_not_impl!(GenIter<Marked<Cell<u8>>>: Sync);
+    assert_not_impl!(GenIter<Marked<Rc<u8>>>: Send);
+    assert_not_impl!(GenIter<Marked<Rc<u8>>>: Sync);
+}
+
+#[test]
+fn gen_iter_return_auto_traits() {
+    is_send::<GenIterReturn<Marked<u8>>>();
+    is_sync::<GenIterReturn<Marked<u8>>>();
+    is_unpin::<GenIterReturn<Marked<u8>>>();
+
+    is_send::<GenIterReturn<Marked<Cell<u8>>>>();
+
+    is_unpin::<GenIterReturn<Marked<Rc<u8>>>>();
+
+    assert_not_impl!(GenIterReturn<Marked<Cell<u8>>>: Sync);
+    assert_not_impl!(GenIterReturn<Marked<Rc<u8>>>: Send);
+    assert_not_impl!(GenIterReturn<Marked<Rc<u8>>>: Sync);
+}
+
+#[test]
+fn gen_iter_exchange_auto_traits() {
+    is_send::<GenIterExchange<Marked<u8>, u8>>();
+    is_sync::<GenIterExchange<Marked<u8>, u8>>();
+    is_unpin::<GenIterExchange<Marked<u8>, u8>>();
+
+    // the resume argument type is never stored
+    is_send::<GenIterExchange<Marked<u8>, Rc<u8>>>();
+    is_sync::<GenIterExchange<Marked<u8>, Cell<u8>>>();
+
+    is_send::<GenIterExchange<Marked<Cell<u8>>, u8>>();
+
+    assert_not_impl!(GenIterExchange<Marked<Cell<u8>>, u8>: Sync);
+    assert_not_impl!(GenIterExchange<Marked<Rc<u8>>, u8>: Send);
+    assert_not_impl!(GenIterExchange<Marked<Rc<u8>>, u8>: Sync);
+}
+
+/// a manual `Unpin` adaptor: only its generator has to be `Unpin`,
+/// the auto traits come from the generator and the closure
+#[test]
+fn on_incomplete_drop_auto_traits() {
+    use gen_iter::OnIncompleteDrop;
+    use std::marker::PhantomPinned;
+
+    is_send::<OnIncompleteDrop<Marked<u8>, fn(usize)>>();
+    is_sync::<OnIncompleteDrop<Marked<u8>, fn(usize)>>();
+    is_unpin::<OnIncompleteDrop<Marked<u8>, fn(usize)>>();
+
+    // a closure which is not `Unpin` is never pinned
+    let pinned = PhantomPinned;
+    let g = GenIterReturn::new(Marked::<u8>(PhantomData)).on_incomplete_drop(move |_| {
+        let _ = &pinned;
+    });
+    fn unpin_val<T: Unpin>(_: &T) {}
+    unpin_val(&g);
+
+    assert_not_impl!(OnIncompleteDrop<PhantomPinned, fn(usize)>: Unpin);
+    assert_not_impl!(OnIncompleteDrop<Marked<Rc<u8>>, fn(usize)>: Send);
+    assert_not_impl!(OnIncompleteDrop<Marked<Cell<u8>>, fn(usize)>: Sync);
+}
+
+#[cfg(feature = "ffi")]
+#[test]
+fn ffi_gen_iter_auto_traits() {
+    assert_not_impl!(gen_iter::FfiGenIter<u8>: Send);
+    assert_not_impl!(gen_iter::FfiGenIter<u8>: Sync);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn pooled_gen_iter_auto_traits() {
+    assert_not_impl!(gen_iter::PooledGenIter<Marked<u8>>: Send);
+    assert_not_impl!(gen_iter::PooledGenIter<Marked<u8>>: Sync);
+}
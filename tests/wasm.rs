@@ -0,0 +1,140 @@
+//! run with `wasm-pack test --node -- --features wasm-bindgen`
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm-bindgen"))]
+#![feature(coroutines, stmt_expr_attributes)]
+
+extern crate gen_iter;
+extern crate js_sys;
+extern crate wasm_bindgen;
+extern crate wasm_bindgen_test;
+
+use gen_iter::{gen_iter, JsGenIter};
+use js_sys::{Array, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen(inline_js = "
+export function collect(iterable) {
+    const out = [];
+    for (const v of iterable) {
+        out.push(v);
+    }
+    return out;
+}
+
+export function steps(it, n) {
+    const out = [];
+    for (let i = 0; i < n; i++) {
+        out.push(it.next());
+    }
+    return out;
+}
+")]
+extern "C" {
+    fn collect(iterable: JsValue) -> Array;
+    fn steps(it: JsGenIter, n: u32) -> Array;
+}
+
+fn get(obj: &JsValue, key: &str) -> JsValue {
+    Reflect::get(obj, &JsValue::from_str(key)).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn for_of() {
+    let iterable = gen_iter!({
+        for i in 0..3u32 {
+            yield i * 10;
+        }
+    }).into_js().into_iterable();
+
+    let values = collect(iterable);
+    assert_eq!(values.length(), 3);
+    assert_eq!(values.get(2).as_f64(), Some(20.0));
+}
+
+#[wasm_bindgen_test]
+fn next_protocol() {
+    let it = gen_iter!({
+        yield "a";
+        yield "b";
+    }).into_js();
+
+    let results = steps(it, 4);
+    let done = results.iter().map(|r| get(&r, "done").as_bool()).collect::<Vec<_>>();
+    assert_eq!(done, [Some(false), Some(false), Some(true), Some(true)]);
+    assert_eq!(get(&results.get(1), "value").as_string().as_deref(), Some("b"));
+    assert!(get(&results.get(3), "value").is_undefined());
+}
+
+#[wasm_bindgen_test]
+fn empty() {
+    let iterable = gen_iter!({
+        if false {
+            yield 0;
+        }
+    }).into_js().into_iterable();
+    assert_eq!(collect(iterable).length(), 0);
+}
+
+mod js_coroutine {
+    use gen_iter::{GenIterReturn, JsCoroutine};
+    use js_sys::Object;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen(inline_js = "
+    export function numbers() {
+        return (function* () {
+            yield 1;
+            yield 2;
+            return 'end';
+        })();
+    }
+
+    export function throwing() {
+        return (function* () {
+            yield 'ok';
+            throw new Error('boom');
+        })();
+    }
+
+    export function manual() {
+        let i = 0;
+        return { next: () => i < 2 ? { value: i++, done: false } : { done: true } };
+    }
+    ")]
+    extern "C" {
+        fn numbers() -> Object;
+        fn throwing() -> Object;
+        fn manual() -> Object;
+    }
+
+    #[wasm_bindgen_test]
+    fn yields_and_return() {
+        let mut g = GenIterReturn::new(JsCoroutine::new(numbers()));
+        let yields = (&mut g).map(|v| v.as_f64().unwrap()).collect::<Vec<_>>();
+        assert_eq!(yields, [1.0, 2.0]);
+        assert_eq!(g.return_or_self().ok().unwrap().as_string().as_deref(), Some("end"));
+
+        let count = (&mut GenIterReturn::new(JsCoroutine::new(numbers()))).count();
+        assert_eq!(count, 2);
+    }
+
+    #[wasm_bindgen_test]
+    fn iterator_object() {
+        let mut g = GenIterReturn::new(JsCoroutine::from(manual()));
+        assert_eq!((&mut g).filter_map(|v| v.as_f64()).collect::<Vec<_>>(), [0.0, 1.0]);
+        assert!(g.return_or_self().ok().unwrap().is_undefined());
+    }
+
+    #[wasm_bindgen_test]
+    fn catching() {
+        let mut g = GenIterReturn::new(JsCoroutine::new(throwing()).catching());
+        let first = (&mut g).next().unwrap();
+        assert_eq!(first.unwrap().as_string().as_deref(), Some("ok"));
+        let err = (&mut g).next().unwrap().unwrap_err();
+        assert!(err.is_instance_of::<js_sys::Error>());
+        assert!((&mut g).next().is_none());
+        assert!(g.return_or_self().ok().unwrap().is_undefined());
+    }
+}